@@ -9,20 +9,11 @@
 import { randomUUID } from "node:crypto";
 import type { TapAdapter } from "../types.js";
 import type { TapTransport } from "../../client/transport.js";
+import { KNOWN_SIGNAL_TYPES } from "@sajou/schema";
 import type { SignalEnvelope } from "@sajou/schema";
 
-/** Well-known signal types for quick lookup. */
-const KNOWN_TYPES: ReadonlySet<string> = new Set<string>([
-  "task_dispatch",
-  "tool_call",
-  "tool_result",
-  "token_usage",
-  "agent_state_change",
-  "error",
-  "completion",
-  "text_delta",
-  "thinking",
-]);
+/** Known signal types for quick lookup, from `@sajou/schema`. */
+export const KNOWN_TYPES: ReadonlySet<string> = new Set<string>(KNOWN_SIGNAL_TYPES);
 
 /** Options for the JSON Lines adapter. */
 export interface JsonlAdapterOptions {
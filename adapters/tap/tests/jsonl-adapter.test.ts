@@ -1,5 +1,6 @@
+import { readFileSync } from "node:fs";
 import { describe, it, expect, vi, beforeEach } from "vitest";
-import { JsonlAdapter } from "../src/adapters/jsonl/jsonl-adapter.js";
+import { JsonlAdapter, KNOWN_TYPES } from "../src/adapters/jsonl/jsonl-adapter.js";
 import type { TapTransport } from "../src/client/transport.js";
 import type { SignalEnvelope } from "@sajou/schema";
 
@@ -45,6 +46,28 @@ describe("JsonlAdapter", () => {
     });
   });
 
+  it("knows the same types as the shared signal-parser corpus", () => {
+    const corpus = JSON.parse(
+      readFileSync(
+        new URL(
+          "../../../tools/scene-builder/src/simulator/fixtures/signal-parser.json",
+          import.meta.url,
+        ),
+        "utf8",
+      ),
+    ) as { knownTypes: string[] };
+    expect([...KNOWN_TYPES]).toEqual(corpus.knownTypes);
+  });
+
+  it("passes MIDI signals through with their type", () => {
+    adapter.processLine(
+      JSON.stringify({ type: "midi.note_on", channel: 1, note: 60, velocity: 100 }),
+    );
+
+    expect(transport.sent[0]!.type).toBe("midi.note_on");
+    expect(transport.sent[0]!.payload).toMatchObject({ note: 60 });
+  });
+
   it("wraps JSON with unknown type as text_delta", () => {
     adapter.processLine(
       JSON.stringify({
@@ -31,6 +31,8 @@ export type {
   UserPointPayload,
 } from "./signal-types.js";
 
+export { KNOWN_SIGNAL_TYPES } from "./known-signal-types.js";
+
 export type {
   StageScene,
   StageBoard,
@@ -0,0 +1,25 @@
+/**
+ * Signal types that ingestion paths pass through as typed signals.
+ *
+ * Shared by the scene-builder's signal parser and the tap's JSONL adapter so
+ * both recognise the same set. A message with any other `type` is wrapped by
+ * the receiver rather than forwarded as-is. This is wider than
+ * `WellKnownSignalType` on the MIDI side and leaves out the `user.*` types,
+ * which the stage emits rather than receives.
+ */
+export const KNOWN_SIGNAL_TYPES: readonly string[] = [
+  "task_dispatch",
+  "tool_call",
+  "tool_result",
+  "token_usage",
+  "agent_state_change",
+  "error",
+  "completion",
+  "text_delta",
+  "thinking",
+  "midi.note_on",
+  "midi.note_off",
+  "midi.control_change",
+  "midi.pitch_bend",
+  "midi.program_change",
+];
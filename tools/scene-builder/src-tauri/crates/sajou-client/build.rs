@@ -0,0 +1,361 @@
+//! Build-time generation of the typed signal model.
+//!
+//! Reads `packages/schema/src/signal.schema.json` and writes its payload
+//! definitions, their builders and the `Signal` enum to
+//! `$OUT_DIR/signal_model.rs`, which `src/model.rs` includes. A schema
+//! construct the generator does not map, or an envelope that no longer matches
+//! `SignalEnvelope`, fails the build instead of drifting silently.
+//!
+//! Outside the monorepo (a published package) the bundled copy of the schema
+//! is used instead; a test keeps the two identical.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+
+/// The schema in the monorepo, relative to this crate.
+const MONOREPO_SCHEMA: &str = "../../../../../packages/schema/src/signal.schema.json";
+
+/// Copy of the schema shipped with the package.
+const BUNDLED_SCHEMA: &str = "signal.schema.json";
+
+/// Envelope properties `SignalEnvelope` (in `src/model.rs`) handles.
+const ENVELOPE_FIELDS: [&str; 7] = [
+    "id",
+    "type",
+    "timestamp",
+    "source",
+    "correlationId",
+    "metadata",
+    "payload",
+];
+
+const RUST_KEYWORDS: [&str; 8] = ["as", "fn", "impl", "in", "loop", "match", "type", "use"];
+
+fn main() {
+    let manifest = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let monorepo = manifest.join(MONOREPO_SCHEMA);
+    println!("cargo:rerun-if-changed={}", monorepo.display());
+    println!("cargo:rerun-if-changed={BUNDLED_SCHEMA}");
+    let path = if monorepo.exists() {
+        monorepo
+    } else {
+        manifest.join(BUNDLED_SCHEMA)
+    };
+    let raw = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {e}", path.display()));
+    let schema: Value = serde_json::from_str(&raw).expect("signal.schema.json is not JSON");
+
+    check_envelope(&schema);
+
+    let mut out = String::from("// Generated by build.rs from signal.schema.json — do not edit.\n");
+    let defs = schema["$defs"].as_object().expect("schema has no $defs");
+    for (name, def) in defs {
+        definition(&mut out, name, def);
+    }
+    signal_enum(&mut out, &schema);
+
+    let target = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("signal_model.rs");
+    std::fs::write(target, out).expect("cannot write the generated signal model");
+}
+
+/// The envelope is hand-written; make sure the schema still agrees with it.
+fn check_envelope(schema: &Value) {
+    let mut fields: Vec<&str> = schema["properties"]
+        .as_object()
+        .expect("schema has no envelope properties")
+        .keys()
+        .map(String::as_str)
+        .collect();
+    fields.sort_unstable();
+    let mut expected = ENVELOPE_FIELDS;
+    expected.sort_unstable();
+    assert!(
+        fields == expected,
+        "signal.schema.json envelope properties are {fields:?}; update SignalEnvelope in src/model.rs and ENVELOPE_FIELDS in build.rs",
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Naming
+// ---------------------------------------------------------------------------
+
+/// `taskDispatchPayload` → `TaskDispatchPayload`, `user.click` → `UserClick`.
+fn pascal(name: &str) -> String {
+    name.split(['_', '.', '-'])
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
+
+/// `correlationId` → `correlation_id`.
+fn snake(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    if RUST_KEYWORDS.contains(&out.as_str()) {
+        format!("r#{out}")
+    } else {
+        out
+    }
+}
+
+fn docs(out: &mut String, indent: &str, schema: &Value) {
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        writeln!(out, "{indent}/// {description}").unwrap();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Definitions
+// ---------------------------------------------------------------------------
+
+fn definition(out: &mut String, name: &str, def: &Value) {
+    let type_name = pascal(name);
+    match def.get("type").and_then(Value::as_str) {
+        Some("object") => object(out, &type_name, def),
+        Some("string") if def.get("enum").is_some() => string_enum(out, &type_name, def),
+        other => panic!("signal.schema.json: $defs/{name} has unsupported type {other:?}"),
+    }
+}
+
+fn string_enum(out: &mut String, type_name: &str, def: &Value) {
+    docs(out, "", def);
+    out.push_str(
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]\n",
+    );
+    writeln!(out, "pub enum {type_name} {{").unwrap();
+    for value in def["enum"].as_array().into_iter().flatten() {
+        let value = value.as_str().expect("enum values must be strings");
+        writeln!(out, "    #[serde(rename = \"{value}\")]").unwrap();
+        writeln!(out, "    {},", pascal(value)).unwrap();
+    }
+    out.push_str("}\n\n");
+}
+
+fn object(out: &mut String, type_name: &str, def: &Value) {
+    let required: Vec<&str> = def["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let empty = Map::new();
+    let properties = def["properties"].as_object().unwrap_or(&empty);
+
+    // Inline enums are emitted after the struct, named after their owner.
+    let owner = type_name.strip_suffix("Payload").unwrap_or(type_name);
+    let mut inline = String::new();
+
+    docs(out, "", def);
+    out.push_str("#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]\n");
+    if def.get("additionalProperties") == Some(&Value::Bool(false)) {
+        out.push_str("#[serde(deny_unknown_fields)]\n");
+    }
+    writeln!(out, "pub struct {type_name} {{").unwrap();
+    let mut fields = Vec::new();
+    for (prop, schema) in properties {
+        let ty = field_type(&mut inline, owner, prop, schema);
+        docs(out, "    ", schema);
+        let field = snake(prop);
+        if field.trim_start_matches("r#") != prop {
+            writeln!(out, "    #[serde(rename = \"{prop}\")]").unwrap();
+        }
+        let is_required = required.contains(&prop.as_str());
+        if is_required {
+            writeln!(out, "    pub {field}: {ty},").unwrap();
+        } else {
+            out.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+            writeln!(out, "    pub {field}: Option<{ty}>,").unwrap();
+        }
+        fields.push((field, ty, is_required));
+    }
+    out.push_str("}\n\n");
+    builder(out, type_name, &fields);
+    out.push_str(&inline);
+}
+
+/// `new` taking the required fields, and a setter per optional field.
+fn builder(out: &mut String, type_name: &str, fields: &[(String, String, bool)]) {
+    writeln!(out, "impl {type_name} {{").unwrap();
+    let params: Vec<String> = fields
+        .iter()
+        .filter(|(_, _, required)| *required)
+        .map(|(field, ty, _)| format!("{field}: impl Into<{ty}>"))
+        .collect();
+    if params.is_empty() {
+        out.push_str("    #[allow(clippy::new_without_default)]\n");
+    }
+    writeln!(
+        out,
+        "    pub fn new({}) -> Self {{\n        Self {{",
+        params.join(", ")
+    )
+    .unwrap();
+    for (field, _, required) in fields {
+        if *required {
+            writeln!(out, "            {field}: {field}.into(),").unwrap();
+        } else {
+            writeln!(out, "            {field}: None,").unwrap();
+        }
+    }
+    out.push_str("        }\n    }\n");
+    for (field, ty, _) in fields.iter().filter(|(_, _, required)| !required) {
+        writeln!(
+            out,
+            "\n    pub fn {field}(mut self, {field}: impl Into<{ty}>) -> Self {{\n        self.{field} = Some({field}.into());\n        self\n    }}"
+        )
+        .unwrap();
+    }
+    out.push_str("}\n\n");
+}
+
+fn field_type(inline: &mut String, owner: &str, prop: &str, schema: &Value) -> String {
+    if let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+        let name = target
+            .strip_prefix("#/$defs/")
+            .unwrap_or_else(|| panic!("signal.schema.json: unsupported $ref {target}"));
+        return pascal(name);
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("string") if schema.get("enum").is_some() => {
+            let name = format!("{owner}{}", pascal(prop));
+            string_enum(inline, &name, schema);
+            name
+        }
+        Some("string") => "String".into(),
+        Some("boolean") => "bool".into(),
+        Some("integer") if schema.get("minimum").and_then(Value::as_i64) >= Some(0) => "u64".into(),
+        Some("integer") => "i64".into(),
+        Some("number") => "f64".into(),
+        Some("object") => "serde_json::Map<String, serde_json::Value>".into(),
+        None => "serde_json::Value".into(),
+        Some(other) => panic!("signal.schema.json: {owner}.{prop} has unsupported type {other}"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Signal enum
+// ---------------------------------------------------------------------------
+
+/// `(type, payload type)` for every `if type == … then payload: $ref` rule.
+fn well_known(schema: &Value) -> Vec<(String, String)> {
+    schema["allOf"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|rule| {
+            let kind = rule
+                .pointer("/if/properties/type/const")
+                .and_then(Value::as_str);
+            let target = rule
+                .pointer("/then/properties/payload/$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.strip_prefix("#/$defs/"));
+            match (kind, target) {
+                (Some(kind), Some(target)) => (kind.to_string(), pascal(target)),
+                _ => panic!("signal.schema.json: unsupported allOf rule {rule}"),
+            }
+        })
+        .collect()
+}
+
+fn signal_enum(out: &mut String, schema: &Value) {
+    let types = well_known(schema);
+
+    writeln!(
+        out,
+        "/// Every well-known signal type, in schema order.\npub const WELL_KNOWN_TYPES: [&str; {}] = [",
+        types.len()
+    )
+    .unwrap();
+    for (kind, _) in &types {
+        writeln!(out, "    \"{kind}\",").unwrap();
+    }
+    out.push_str("];\n\n");
+
+    out.push_str("/// A signal's `type` with its typed `payload`.\n");
+    out.push_str("#[derive(Debug, Clone, PartialEq)]\npub enum Signal {\n");
+    for (kind, payload) in &types {
+        writeln!(out, "    /// `{kind}`\n    {}({payload}),", pascal(kind)).unwrap();
+    }
+    out.push_str(concat!(
+        "    /// Any other type — the protocol is open.\n",
+        "    Custom {\n",
+        "        kind: String,\n",
+        "        payload: serde_json::Map<String, serde_json::Value>,\n",
+        "    },\n",
+        "}\n\n",
+    ));
+
+    out.push_str("impl Signal {\n");
+    out.push_str(
+        "    /// The signal's `type`.\n    pub fn kind(&self) -> &str {\n        match self {\n",
+    );
+    for (kind, _) in &types {
+        writeln!(out, "            Self::{}(_) => \"{kind}\",", pascal(kind)).unwrap();
+    }
+    out.push_str("            Self::Custom { kind, .. } => kind,\n        }\n    }\n\n");
+
+    out.push_str(concat!(
+        "    /// Type the `payload` of a signal of type `kind`.\n",
+        "    pub fn from_parts(kind: &str, payload: serde_json::Value) -> serde_json::Result<Self> {\n",
+        "        Ok(match kind {\n",
+    ));
+    for (kind, _) in &types {
+        writeln!(
+            out,
+            "            \"{kind}\" => Self::{}(serde_json::from_value(payload)?),",
+            pascal(kind)
+        )
+        .unwrap();
+    }
+    out.push_str(concat!(
+        "            _ => Self::Custom {\n",
+        "                kind: kind.to_string(),\n",
+        "                payload: serde_json::from_value(payload)?,\n",
+        "            },\n",
+        "        })\n    }\n\n",
+    ));
+
+    out.push_str(concat!(
+        "    /// The `payload` as JSON.\n",
+        "    pub fn payload(&self) -> serde_json::Result<serde_json::Value> {\n",
+        "        match self {\n",
+    ));
+    for (kind, _) in &types {
+        writeln!(
+            out,
+            "            Self::{}(p) => serde_json::to_value(p),",
+            pascal(kind)
+        )
+        .unwrap();
+    }
+    out.push_str(concat!(
+        "            Self::Custom { payload, .. } => Ok(serde_json::Value::Object(payload.clone())),\n",
+        "        }\n    }\n}\n",
+    ));
+
+    // A payload type shared by several signal types has no single variant.
+    for (kind, payload) in &types {
+        if types.iter().filter(|(_, p)| p == payload).count() == 1 {
+            writeln!(
+                out,
+                "\nimpl From<{payload}> for Signal {{\n    fn from(payload: {payload}) -> Self {{\n        Self::{}(payload)\n    }}\n}}",
+                pascal(kind)
+            )
+            .unwrap();
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Buffering — hold signals back and send them together.
+//!
+//! [`Buffered`] wraps any transport. Signals queue up until the buffer is
+//! full or its oldest signal has waited long enough, then go out in one
+//! [`Transport::send_all`] call. There is no background thread: the age
+//! limit is checked on every send, and whatever is left is flushed on
+//! [`Transport::close`] or drop.
+//!
+//! The endpoint takes one signal per request, so over HTTP each signal is
+//! still its own POST; over WebSocket the frames are written in one go.
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::model::SignalEnvelope;
+use crate::transport::Transport;
+
+/// A transport that holds signals back and sends them in groups.
+#[derive(Debug)]
+pub struct Buffered<T: Transport> {
+    inner: T,
+    max_signals: usize,
+    max_delay: Duration,
+    pending: Vec<SignalEnvelope>,
+    /// When the oldest pending signal was queued.
+    since: Option<Instant>,
+}
+
+impl<T: Transport> Buffered<T> {
+    /// Groups of up to 32 signals, held at most 100 ms.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            max_signals: 32,
+            max_delay: Duration::from_millis(100),
+            pending: Vec::new(),
+            since: None,
+        }
+    }
+
+    /// Signals per group.
+    pub fn max_signals(mut self, max_signals: usize) -> Self {
+        self.max_signals = max_signals.max(1);
+        self
+    }
+
+    /// How long a signal may wait for its group to fill.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Signals queued and not sent yet.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Send everything queued now.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        self.inner.send_all(&self.pending)?;
+        self.pending.clear();
+        self.since = None;
+        Ok(())
+    }
+
+    /// The wrapped transport.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Transport> Transport for Buffered<T> {
+    fn connect(&mut self) -> io::Result<()> {
+        self.inner.connect()
+    }
+
+    fn send(&mut self, signal: &SignalEnvelope) -> io::Result<()> {
+        self.pending.push(signal.clone());
+        let since = *self.since.get_or_insert_with(Instant::now);
+        if self.pending.len() >= self.max_signals || since.elapsed() >= self.max_delay {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn send_all(&mut self, signals: &[SignalEnvelope]) -> io::Result<()> {
+        signals.iter().try_for_each(|signal| self.send(signal))
+    }
+
+    /// Flush, then close the wrapped transport.
+    fn close(&mut self) -> io::Result<()> {
+        self.flush()?;
+        self.inner.close()
+    }
+
+    fn connected(&self) -> bool {
+        self.inner.connected()
+    }
+}
+
+impl<T: Transport> Drop for Buffered<T> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ThinkingPayload;
+
+    /// Records the size of every group it is asked to send.
+    #[derive(Default)]
+    struct Recorder(Vec<usize>);
+
+    impl Transport for Recorder {
+        fn connect(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn send(&mut self, _: &SignalEnvelope) -> io::Result<()> {
+            self.0.push(1);
+            Ok(())
+        }
+
+        fn send_all(&mut self, signals: &[SignalEnvelope]) -> io::Result<()> {
+            self.0.push(signals.len());
+            Ok(())
+        }
+
+        fn close(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn connected(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn sends_full_groups_and_flushes_the_rest() {
+        let signal = SignalEnvelope::new("adapter:test", ThinkingPayload::new("a1", "…"));
+        let mut buffered = Buffered::new(Recorder::default())
+            .max_signals(3)
+            .max_delay(Duration::from_secs(60));
+        for _ in 0..7 {
+            buffered.send(&signal).unwrap();
+        }
+        assert_eq!(buffered.get_ref().0, [3, 3]);
+        assert_eq!(buffered.pending(), 1);
+        buffered.close().unwrap();
+        assert_eq!(buffered.get_ref().0, [3, 3, 1]);
+
+        let mut buffered = Buffered::new(Recorder::default()).max_delay(Duration::ZERO);
+        buffered.send(&signal).unwrap();
+        assert_eq!(buffered.get_ref().0, [1]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Emitter — wraps signals in envelopes and sends them.
+//!
+//! An [`Emitter`] stamps every signal with its `source`, a fresh ID and the
+//! current time. A [`Scope`] additionally sets `correlationId`, so the
+//! signals of one task or conversation are grouped as a flow in sajou.
+
+use std::io;
+
+use crate::model::{Signal, SignalEnvelope};
+use crate::transport::{create_transport, Transport};
+
+/// Default `source` of emitted signals.
+pub const DEFAULT_SOURCE: &str = "adapter:rust";
+
+/// Sends signals from one source over a transport.
+#[derive(Debug)]
+pub struct Emitter<T: Transport> {
+    transport: T,
+    source: String,
+}
+
+impl Emitter<Box<dyn Transport + Send>> {
+    /// Emitter for `endpoint` (`http://` or `ws://`), or for the running app.
+    pub fn connect(endpoint: Option<&str>) -> io::Result<Self> {
+        let mut transport = create_transport(endpoint)?;
+        transport.connect()?;
+        Ok(Self::new(transport))
+    }
+}
+
+impl<T: Transport> Emitter<T> {
+    /// Emitter over `transport`, with source [`DEFAULT_SOURCE`].
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            source: DEFAULT_SOURCE.to_string(),
+        }
+    }
+
+    /// Set the `source` of emitted signals, by convention `adapter:<name>`.
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Emit a signal; returns its ID.
+    pub fn emit(&mut self, signal: impl Into<Signal>) -> io::Result<String> {
+        self.send(SignalEnvelope::new(self.source.clone(), signal))
+    }
+
+    /// Send a prepared envelope as-is; returns its ID.
+    pub fn send(&mut self, envelope: SignalEnvelope) -> io::Result<String> {
+        self.transport.send(&envelope)?;
+        Ok(envelope.id)
+    }
+
+    /// Emit signals belonging to the flow `correlation_id`.
+    pub fn scope(&mut self, correlation_id: impl Into<String>) -> Scope<'_, T> {
+        Scope {
+            emitter: self,
+            correlation_id: correlation_id.into(),
+        }
+    }
+
+    /// Emit signals belonging to a new flow.
+    pub fn flow(&mut self) -> Scope<'_, T> {
+        self.scope(uuid::Uuid::new_v4().to_string())
+    }
+
+    /// The underlying transport.
+    pub fn transport(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Close the transport, flushing anything it buffers.
+    pub fn close(mut self) -> io::Result<()> {
+        self.transport.close()
+    }
+}
+
+/// An [`Emitter`] borrowed for one flow.
+#[derive(Debug)]
+pub struct Scope<'a, T: Transport> {
+    emitter: &'a mut Emitter<T>,
+    correlation_id: String,
+}
+
+impl<T: Transport> Scope<'_, T> {
+    pub fn correlation_id(&self) -> &str {
+        &self.correlation_id
+    }
+
+    /// Emit a signal in this flow; returns its ID.
+    pub fn emit(&mut self, signal: impl Into<Signal>) -> io::Result<String> {
+        let envelope = SignalEnvelope::new(self.emitter.source.clone(), signal)
+            .correlation_id(self.correlation_id.clone());
+        self.emitter.send(envelope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{AgentState, AgentStateChangePayload, TaskDispatchPayload};
+
+    #[derive(Default)]
+    struct Recorder(Vec<SignalEnvelope>);
+
+    impl Transport for Recorder {
+        fn connect(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn send(&mut self, signal: &SignalEnvelope) -> io::Result<()> {
+            self.0.push(signal.clone());
+            Ok(())
+        }
+
+        fn close(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn connected(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn scopes_stamp_the_correlation_id() {
+        let mut emitter = Emitter::new(Recorder::default()).source("adapter:solver");
+        emitter
+            .emit(AgentStateChangePayload::new(
+                "solver",
+                AgentState::Idle,
+                AgentState::Thinking,
+            ))
+            .unwrap();
+        let mut flow = emitter.scope("task-7");
+        let id = flow
+            .emit(TaskDispatchPayload::new("task-7", "orchestrator", "solver"))
+            .unwrap();
+
+        let sent = &emitter.transport().0;
+        assert_eq!(sent.len(), 2);
+        assert!(sent.iter().all(|s| s.source == "adapter:solver"));
+        assert_eq!(sent[0].correlation_id, None);
+        assert_eq!(sent[1].correlation_id.as_deref(), Some("task-7"));
+        assert_eq!(sent[1].id, id);
+    }
+}
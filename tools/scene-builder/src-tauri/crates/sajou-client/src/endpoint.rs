@@ -0,0 +1,139 @@
+//! Finding the running sajou app.
+//!
+//! The desktop app publishes its listener in [`endpoint_file`]; emitters use
+//! it unless an endpoint or port is given explicitly.
+
+use std::io;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Preferred port of the app's listener. Sits inside the 5173–5180 range
+/// probed by `@sajou/tap`, after the Vite dev server's 5175.
+pub const DEFAULT_PORT: u16 = 5180;
+
+/// Path of the signal ingestion route.
+pub const SIGNAL_PATH: &str = "/api/signal";
+
+/// Address of the running listener, as advertised in [`endpoint_file`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerInfo {
+    pub port: u16,
+    pub pid: u32,
+}
+
+impl ServerInfo {
+    /// Base URL of the listener (`http://127.0.0.1:<port>`).
+    pub fn base_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+}
+
+/// Path of the file advertising the running app's listener.
+pub fn endpoint_file() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("dev.sajou.scene-builder").join("server.json"))
+}
+
+/// Read the advertised listener, if the app is (or was last) running.
+pub fn read_endpoint() -> Option<ServerInfo> {
+    let raw = std::fs::read_to_string(endpoint_file()?).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Resolve the signal endpoint.
+///
+/// Order: `explicit` > `SAJOU_ENDPOINT` > `SAJOU_PORT` > the running app's
+/// advertised port > [`DEFAULT_PORT`].
+pub fn resolve_endpoint(explicit: Option<&str>) -> String {
+    if let Some(endpoint) = explicit {
+        return endpoint.to_string();
+    }
+    if let Ok(endpoint) = std::env::var("SAJOU_ENDPOINT") {
+        return endpoint;
+    }
+    let port = std::env::var("SAJOU_PORT")
+        .ok()
+        .and_then(|p| p.parse::<u16>().ok())
+        .or_else(|| read_endpoint().map(|info| info.port))
+        .unwrap_or(DEFAULT_PORT);
+    format!("http://127.0.0.1:{port}{SIGNAL_PATH}")
+}
+
+/// Split an `http://host[:port][/path]` URL. Only plain HTTP is supported.
+pub fn split_url(url: &str) -> io::Result<(String, u16, String)> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported endpoint: {url}"),
+        )
+    })?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((h, p)) if !h.contains(':') || h.ends_with(']') => (
+            h,
+            p.parse().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid port in {url}"),
+                )
+            })?,
+        ),
+        _ => (authority, 80),
+    };
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    Ok((host, port, path.to_string()))
+}
+
+/// Connect to `host:port`, trying every resolved address in turn with
+/// `timeout` each: `localhost` may resolve to `::1` before `127.0.0.1`.
+pub fn connect(host: &str, port: u16, timeout: Duration) -> io::Result<TcpStream> {
+    let mut last = None;
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last = Some(e),
+        }
+    }
+    Err(last.unwrap_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, format!("cannot resolve {host}"))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_urls() {
+        assert_eq!(
+            split_url("http://127.0.0.1:5180/api/signal").unwrap(),
+            ("127.0.0.1".into(), 5180, "/api/signal".into())
+        );
+        assert_eq!(
+            split_url("http://localhost").unwrap(),
+            ("localhost".into(), 80, "/".into())
+        );
+        assert_eq!(
+            split_url("http://[::1]:5180").unwrap(),
+            ("::1".into(), 5180, "/".into())
+        );
+        assert!(split_url("https://example.com").is_err());
+    }
+
+    #[test]
+    fn connect_tries_every_resolved_address() {
+        // Only IPv4 listens; `localhost` may resolve to `::1` first.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let stream = connect("localhost", port, Duration::from_millis(500)).unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), listener.local_addr().unwrap());
+    }
+}
@@ -0,0 +1,148 @@
+//! HTTP transport — one `POST /api/signal` per signal.
+//!
+//! Port of `adapters/tap/src/client/http-client.ts`. Uses a blocking
+//! `TcpStream` with short timeouts so an emitter never waits on a dead
+//! listener; only plain `http://` endpoints are supported.
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use crate::endpoint::{connect, resolve_endpoint, split_url};
+use crate::model::SignalEnvelope;
+use crate::transport::Transport;
+
+/// Connect timeout for a POST.
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Read/write timeout for a POST.
+const IO_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// POST a JSON body to `endpoint`, failing fast if nothing is listening.
+pub fn post_json(endpoint: &str, body: &str) -> io::Result<()> {
+    let (host, port, path) = split_url(endpoint)?;
+    let mut stream = connect(&host, port, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: {host}:{port}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+
+    let mut status_line = [0u8; 12];
+    stream.read_exact(&mut status_line)?;
+    let status = String::from_utf8_lossy(&status_line[9..12]).into_owned();
+    // Drain the (small) response so the server sees an orderly close.
+    let _ = io::copy(&mut stream.take(64 * 1024), &mut io::sink());
+    if status.starts_with('2') {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("HTTP {status}")))
+    }
+}
+
+/// Stateless HTTP POST transport.
+#[derive(Debug, Clone, Default)]
+pub struct HttpTransport {
+    /// Endpoint given by the caller; skips resolution.
+    explicit: Option<String>,
+    /// Endpoint in use once connected.
+    endpoint: Option<String>,
+}
+
+impl HttpTransport {
+    /// Transport posting to `endpoint`.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            explicit: Some(endpoint.into()),
+            endpoint: None,
+        }
+    }
+
+    /// Transport posting to the running app, resolved on connect.
+    pub fn discover() -> Self {
+        Self::default()
+    }
+
+    /// The endpoint in use, once connected.
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+}
+
+impl Transport for HttpTransport {
+    fn connect(&mut self) -> io::Result<()> {
+        self.endpoint = Some(resolve_endpoint(self.explicit.as_deref()));
+        Ok(())
+    }
+
+    fn send(&mut self, signal: &SignalEnvelope) -> io::Result<()> {
+        if self.endpoint.is_none() {
+            self.connect()?;
+        }
+        let body = signal.to_value().map_err(io::Error::other)?.to_string();
+        post_json(self.endpoint.as_deref().unwrap_or_default(), &body)
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        self.endpoint = None;
+        Ok(())
+    }
+
+    fn connected(&self) -> bool {
+        self.endpoint.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::model::ThinkingPayload;
+
+    /// Headers and the whole body have arrived.
+    fn complete(request: &str) -> bool {
+        let Some((head, body)) = request.split_once("\r\n\r\n") else {
+            return false;
+        };
+        let length = head
+            .lines()
+            .find_map(|l| l.strip_prefix("Content-Length: "))
+            .and_then(|n| n.parse::<usize>().ok());
+        length.is_some_and(|n| body.len() >= n)
+    }
+
+    #[test]
+    fn posts_signals_and_reports_http_errors() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/api/signal", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for status in ["200 OK", "422 Unprocessable Entity"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = String::new();
+                let mut buf = [0u8; 4096];
+                while !complete(&request) {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.push_str(std::str::from_utf8(&buf[..n]).unwrap());
+                }
+                write!(stream, "HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n").unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+
+        let mut transport = HttpTransport::new(endpoint);
+        let signal = SignalEnvelope::new("adapter:test", ThinkingPayload::new("a1", "hmm"));
+        transport.send(&signal).unwrap();
+        let error = transport.send(&signal).unwrap_err();
+        assert_eq!(error.to_string(), "HTTP 422");
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /api/signal HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with(&signal.to_value().unwrap().to_string()));
+    }
+}
@@ -0,0 +1,34 @@
+//! Emit sajou signals from Rust.
+//!
+//! Rust counterpart of `@sajou/tap`'s client: typed signal builders generated
+//! from `signal.schema.json` ([`model`]), blocking HTTP and WebSocket
+//! transports ([`HttpTransport`], [`WsTransport`]), buffering ([`Buffered`])
+//! and correlation ID scoping ([`Emitter::scope`]).
+//!
+//! ```no_run
+//! use sajou_client::model::{AgentState, AgentStateChangePayload, ToolCallPayload};
+//! use sajou_client::Emitter;
+//!
+//! // Finds the running sajou app (SAJOU_ENDPOINT, SAJOU_PORT, or its advertised port).
+//! let mut emitter = Emitter::connect(None)?.source("adapter:my-agent");
+//! emitter.emit(AgentStateChangePayload::new("agent-1", AgentState::Idle, AgentState::Acting))?;
+//!
+//! let mut flow = emitter.flow();
+//! flow.emit(ToolCallPayload::new("search", "agent-1").call_id("call-1"))?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+pub mod buffer;
+pub mod emitter;
+pub mod endpoint;
+pub mod http;
+pub mod model;
+pub mod transport;
+pub mod ws;
+
+pub use buffer::Buffered;
+pub use emitter::{Emitter, Scope};
+pub use http::HttpTransport;
+pub use model::{Signal, SignalEnvelope};
+pub use transport::{create_transport, Transport};
+pub use ws::WsTransport;
@@ -0,0 +1,224 @@
+//! Typed signal model.
+//!
+//! Rust counterpart of `@sajou/schema`'s signal types. The payload types
+//! (`ToolCallPayload`, `AgentState`, `ErrorSeverity`, `BoardPosition`…), their
+//! builders and the [`Signal`] enum are generated from `signal.schema.json` by
+//! `build.rs`, so they cannot drift from the schema; types the schema does not
+//! know stay open as [`Signal::Custom`].
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+include!(concat!(env!("OUT_DIR"), "/signal_model.rs"));
+
+/// Mirrors `SignalEnvelope`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalEnvelope {
+    pub id: String,
+    /// Unix epoch in milliseconds.
+    pub timestamp: u64,
+    /// Producer, by convention `adapter:<name>`.
+    pub source: String,
+    pub correlation_id: Option<String>,
+    /// Adapter-specific data the choreographer ignores.
+    pub metadata: Option<Map<String, Value>>,
+    /// `type` and `payload`.
+    pub signal: Signal,
+}
+
+impl Signal {
+    /// A signal of a type the schema does not know.
+    pub fn custom(kind: impl Into<String>, payload: Map<String, Value>) -> Self {
+        Self::Custom {
+            kind: kind.into(),
+            payload,
+        }
+    }
+}
+
+impl SignalEnvelope {
+    /// An envelope from `source`, stamped now with a fresh ID.
+    pub fn new(source: impl Into<String>, signal: impl Into<Signal>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: now_ms(),
+            source: source.into(),
+            correlation_id: None,
+            metadata: None,
+            signal: signal.into(),
+        }
+    }
+
+    /// Attach the envelope to the flow `correlation_id`.
+    pub fn correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    /// Add an adapter-specific `metadata` entry.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.metadata
+            .get_or_insert_with(Map::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Type a JSON envelope.
+    pub fn from_value(value: Value) -> serde_json::Result<Self> {
+        serde_json::from_value(value)
+    }
+
+    /// The envelope as JSON.
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        serde_json::to_value(self)
+    }
+}
+
+/// Current Unix epoch in milliseconds.
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// The envelope as it travels.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Wire {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    timestamp: u64,
+    source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    correlation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<Map<String, Value>>,
+    payload: Value,
+}
+
+impl Serialize for SignalEnvelope {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let payload = self.signal.payload().map_err(serde::ser::Error::custom)?;
+        Wire {
+            id: self.id.clone(),
+            kind: self.signal.kind().to_string(),
+            timestamp: self.timestamp,
+            source: self.source.clone(),
+            correlation_id: self.correlation_id.clone(),
+            metadata: self.metadata.clone(),
+            payload,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SignalEnvelope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let wire = Wire::deserialize(deserializer)?;
+        let signal = Signal::from_parts(&wire.kind, wire.payload).map_err(|e| {
+            serde::de::Error::custom(format!("invalid `{}` payload: {e}", wire.kind))
+        })?;
+        Ok(Self {
+            id: wire.id,
+            timestamp: wire.timestamp,
+            source: wire.source,
+            correlation_id: wire.correlation_id,
+            metadata: wire.metadata,
+            signal,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_well_known_and_custom_signals() {
+        let raw = json!({
+            "id": "sig-1",
+            "type": "agent_state_change",
+            "timestamp": 1700000000000u64,
+            "source": "adapter:test",
+            "correlationId": "flow-1",
+            "payload": { "agentId": "a1", "from": "idle", "to": "acting" },
+        });
+        let envelope = SignalEnvelope::from_value(raw.clone()).unwrap();
+        let Signal::AgentStateChange(change) = &envelope.signal else {
+            panic!("expected agent_state_change, got {:?}", envelope.signal);
+        };
+        assert_eq!(change.to, AgentState::Acting);
+        assert_eq!(envelope.to_value().unwrap(), raw);
+
+        let custom = json!({
+            "id": "sig-2",
+            "type": "deploy.started",
+            "timestamp": 1,
+            "source": "ci",
+            "payload": { "env": "prod" },
+        });
+        let envelope = SignalEnvelope::from_value(custom.clone()).unwrap();
+        assert_eq!(envelope.signal.kind(), "deploy.started");
+        assert!(matches!(envelope.signal, Signal::Custom { .. }));
+        assert_eq!(envelope.to_value().unwrap(), custom);
+        assert_eq!(WELL_KNOWN_TYPES.len(), 14);
+    }
+
+    #[test]
+    fn rejects_payloads_the_schema_rejects() {
+        let envelope = |payload: Value| {
+            SignalEnvelope::from_value(json!({
+                "id": "e", "type": "error", "timestamp": 1, "source": "t", "payload": payload,
+            }))
+        };
+        let ok = envelope(json!({ "message": "boom", "severity": "critical" })).unwrap();
+        assert!(matches!(
+            ok.signal,
+            Signal::Error(ErrorPayload {
+                severity: ErrorSeverity::Critical,
+                ..
+            })
+        ));
+        assert!(envelope(json!({ "message": "boom", "severity": "fatal" })).is_err());
+        assert!(envelope(json!({ "message": "boom", "severity": "error", "extra": 1 })).is_err());
+        assert!(envelope(json!({ "severity": "error" })).is_err());
+    }
+
+    #[test]
+    fn builds_envelopes_from_typed_payloads() {
+        let envelope = SignalEnvelope::new(
+            "adapter:test",
+            ToolCallPayload::new("Bash", "claude").call_id("tu-1"),
+        )
+        .correlation_id("flow-1")
+        .metadata("host", "ci");
+        let value = envelope.to_value().unwrap();
+        assert_eq!(value["type"], "tool_call");
+        assert_eq!(value["correlationId"], "flow-1");
+        assert_eq!(value["metadata"], json!({ "host": "ci" }));
+        assert_eq!(
+            value["payload"],
+            json!({ "toolName": "Bash", "agentId": "claude", "callId": "tu-1" })
+        );
+        assert_eq!(SignalEnvelope::from_value(value).unwrap(), envelope);
+    }
+
+    /// The bundled schema a published crate builds from is the monorepo's.
+    #[test]
+    fn bundles_the_current_schema() {
+        let root = env!("CARGO_MANIFEST_DIR");
+        let monorepo = format!("{root}/../../../../../packages/schema/src/signal.schema.json");
+        if let Ok(schema) = std::fs::read_to_string(monorepo) {
+            let bundled = std::fs::read_to_string(format!("{root}/signal.schema.json")).unwrap();
+            assert!(
+                schema == bundled,
+                "copy packages/schema/src/signal.schema.json to signal.schema.json"
+            );
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Transport abstraction for sending signals to a sajou endpoint.
+//!
+//! Port of `adapters/tap/src/client/transport.ts` and
+//! `create-transport.ts`. All transports are blocking.
+
+use std::io;
+
+use crate::endpoint::resolve_endpoint;
+use crate::http::HttpTransport;
+use crate::model::SignalEnvelope;
+use crate::ws::WsTransport;
+
+/// Pushes signals to a sajou endpoint.
+pub trait Transport {
+    /// Establish the connection (endpoint resolution for stateless transports).
+    fn connect(&mut self) -> io::Result<()>;
+
+    /// Send one envelope. Connects first if needed.
+    fn send(&mut self, signal: &SignalEnvelope) -> io::Result<()>;
+
+    /// Send several envelopes in order. The endpoint takes one signal per
+    /// request; a transport may still write them out together.
+    fn send_all(&mut self, signals: &[SignalEnvelope]) -> io::Result<()> {
+        signals.iter().try_for_each(|signal| self.send(signal))
+    }
+
+    /// Close the connection and release resources.
+    fn close(&mut self) -> io::Result<()>;
+
+    /// Whether the transport is connected and ready to send.
+    fn connected(&self) -> bool;
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn connect(&mut self) -> io::Result<()> {
+        (**self).connect()
+    }
+
+    fn send(&mut self, signal: &SignalEnvelope) -> io::Result<()> {
+        (**self).send(signal)
+    }
+
+    fn send_all(&mut self, signals: &[SignalEnvelope]) -> io::Result<()> {
+        (**self).send_all(signals)
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        (**self).close()
+    }
+
+    fn connected(&self) -> bool {
+        (**self).connected()
+    }
+}
+
+/// Pick a transport from the endpoint's scheme: `ws://` → [`WsTransport`],
+/// anything else → [`HttpTransport`]. `None` resolves the running app's
+/// endpoint (see [`resolve_endpoint`]). TLS (`wss://`, `https://`) is not
+/// supported and is an error rather than a silent downgrade.
+pub fn create_transport(endpoint: Option<&str>) -> io::Result<Box<dyn Transport + Send>> {
+    match endpoint {
+        Some(url) if url.starts_with("wss://") || url.starts_with("https://") => {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{url}: TLS endpoints are not supported, use ws:// or http://"),
+            ))
+        }
+        Some(url) if url.starts_with("ws://") => Ok(Box::new(WsTransport::new(url))),
+        Some(url) => Ok(Box::new(HttpTransport::new(url))),
+        None => Ok(Box::new(HttpTransport::new(resolve_endpoint(None)))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_tls_endpoints() {
+        for url in ["wss://example.com/ws", "https://example.com/api/signal"] {
+            let error = create_transport(Some(url)).err().unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::Unsupported, "{url}");
+        }
+        assert!(create_transport(Some("ws://127.0.0.1:5180/ws")).is_ok());
+        assert!(create_transport(Some("http://127.0.0.1:5180/api/signal")).is_ok());
+    }
+}
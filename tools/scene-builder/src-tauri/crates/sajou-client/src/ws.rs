@@ -0,0 +1,325 @@
+//! WebSocket transport — persistent connection with exponential backoff
+//! reconnect.
+//!
+//! Port of `adapters/tap/src/client/ws-client.ts`. Send-only RFC 6455 over a
+//! blocking `TcpStream`: a `ws://` handshake, then one masked text frame per
+//! signal. Signals sent while the connection is down are buffered and flushed
+//! once a reconnect succeeds. TLS (`wss://`) is not supported.
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+
+use crate::endpoint::{connect, split_url};
+use crate::model::SignalEnvelope;
+use crate::transport::Transport;
+
+/// Connect timeout for the TCP connection and the handshake.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Largest handshake response accepted.
+const MAX_HANDSHAKE: usize = 16 * 1024;
+
+/// Signals kept while disconnected, oldest dropped first.
+const BUFFER_CAPACITY: usize = 1000;
+
+const OP_TEXT: u8 = 0x1;
+const OP_CLOSE: u8 = 0x8;
+
+/// A masked client frame.
+fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        n if n < 126 => frame.push(0x80 | n as u8),
+        n if n <= u16::MAX as usize => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    let mask: [u8; 4] = uuid::Uuid::new_v4().as_bytes()[..4]
+        .try_into()
+        .unwrap_or_default();
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    frame
+}
+
+/// Open `ws://host[:port][/path]` and complete the upgrade handshake.
+fn handshake(url: &str) -> io::Result<TcpStream> {
+    let http = url
+        .strip_prefix("ws://")
+        .map(|rest| format!("http://{rest}"))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("only ws:// URLs are supported: {url}"),
+            )
+        })?;
+    let (host, port, path) = split_url(&http)?;
+    let mut stream = connect(&host, port, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+    let host = if host.contains(':') {
+        format!("[{host}]")
+    } else {
+        host
+    };
+
+    let key = base64::engine::general_purpose::STANDARD.encode(uuid::Uuid::new_v4().as_bytes());
+    write!(
+        stream,
+        "GET {path} HTTP/1.1\r\nHost: {host}:{port}\r\nUpgrade: websocket\r\n\
+         Connection: Upgrade\r\nSec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\r\n"
+    )?;
+
+    // Read byte by byte so nothing after the response head is consumed.
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() > MAX_HANDSHAKE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "handshake response too large",
+            ));
+        }
+        stream.read_exact(&mut byte)?;
+        head.push(byte[0]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    if head.split_whitespace().nth(1) != Some("101") {
+        let line = head.lines().next().unwrap_or_default();
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("upgrade refused: {line}"),
+        ));
+    }
+    stream.set_read_timeout(None)?;
+    Ok(stream)
+}
+
+/// WebSocket transport with exponential backoff reconnect.
+#[derive(Debug)]
+pub struct WsTransport {
+    endpoint: String,
+    max_retries: u32,
+    base_delay: Duration,
+    stream: Option<TcpStream>,
+    retry_count: u32,
+    /// Earliest time of the next reconnect attempt.
+    retry_at: Option<Instant>,
+    /// Signals waiting for a reconnect, already serialized.
+    buffer: VecDeque<String>,
+}
+
+impl WsTransport {
+    /// Transport for `endpoint`, retrying 10 times from a 500 ms delay.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            max_retries: 10,
+            base_delay: Duration::from_millis(500),
+            stream: None,
+            retry_count: 0,
+            retry_at: None,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Reconnection attempts before sends start failing.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first reconnect; doubles on every failure.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Signals buffered while disconnected.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn write(&mut self, texts: &[String]) -> io::Result<()> {
+        let stream = self.stream.as_mut().ok_or(io::ErrorKind::NotConnected)?;
+        let frames: Vec<u8> = texts
+            .iter()
+            .flat_map(|text| frame(OP_TEXT, text.as_bytes()))
+            .collect();
+        stream.write_all(&frames)
+    }
+
+    fn flush_buffer(&mut self) -> io::Result<()> {
+        let pending: Vec<String> = self.buffer.iter().cloned().collect();
+        self.write(&pending)?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Reconnect if the backoff allows it, then flush the buffer.
+    fn reconnect(&mut self) -> io::Result<()> {
+        if self.retry_at.is_some_and(|at| Instant::now() < at) {
+            return Err(io::ErrorKind::NotConnected.into());
+        }
+        match handshake(&self.endpoint) {
+            Ok(stream) => {
+                self.stream = Some(stream);
+                self.retry_count = 0;
+                self.retry_at = None;
+                self.flush_buffer()
+            }
+            Err(e) => {
+                let delay = self.base_delay * 2u32.saturating_pow(self.retry_count);
+                self.retry_count += 1;
+                self.retry_at = Some(Instant::now() + delay);
+                Err(e)
+            }
+        }
+    }
+
+    /// Send `texts`, buffering them while reconnect attempts remain.
+    fn deliver(&mut self, texts: Vec<String>) -> io::Result<()> {
+        if self.stream.is_some() && self.write(&texts).is_ok() {
+            return Ok(());
+        }
+        self.stream = None;
+        for text in texts {
+            if self.buffer.len() == BUFFER_CAPACITY {
+                self.buffer.pop_front();
+            }
+            self.buffer.push_back(text);
+        }
+        match self.reconnect() {
+            Ok(()) => Ok(()),
+            Err(_) if self.retry_count < self.max_retries => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Transport for WsTransport {
+    /// Open the connection, resetting the retry budget.
+    fn connect(&mut self) -> io::Result<()> {
+        self.stream = Some(handshake(&self.endpoint)?);
+        self.retry_count = 0;
+        self.retry_at = None;
+        self.flush_buffer()
+    }
+
+    fn send(&mut self, signal: &SignalEnvelope) -> io::Result<()> {
+        self.send_all(std::slice::from_ref(signal))
+    }
+
+    /// Write all frames at once.
+    fn send_all(&mut self, signals: &[SignalEnvelope]) -> io::Result<()> {
+        let texts = signals
+            .iter()
+            .map(|s| s.to_value().map(|v| v.to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io::Error::other)?;
+        self.deliver(texts)
+    }
+
+    /// Close the connection and stop reconnecting.
+    fn close(&mut self) -> io::Result<()> {
+        self.buffer.clear();
+        self.retry_count = 0;
+        self.retry_at = None;
+        if let Some(mut stream) = self.stream.take() {
+            let _ = stream.write_all(&frame(OP_CLOSE, &1000u16.to_be_bytes()));
+        }
+        Ok(())
+    }
+
+    fn connected(&self) -> bool {
+        self.stream.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::model::ThinkingPayload;
+
+    /// Read one masked client frame and unmask its payload.
+    fn read_frame(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let mut head = [0u8; 2];
+        stream.read_exact(&mut head).unwrap();
+        let len = match head[1] & 0x7f {
+            126 => {
+                let mut n = [0u8; 2];
+                stream.read_exact(&mut n).unwrap();
+                u16::from_be_bytes(n) as usize
+            }
+            n => n as usize,
+        };
+        let mut mask = [0u8; 4];
+        stream.read_exact(&mut mask).unwrap();
+        let mut payload = vec![0u8; len];
+        stream.read_exact(&mut payload).unwrap();
+        for (i, b) in payload.iter_mut().enumerate() {
+            *b ^= mask[i % 4];
+        }
+        (head[0] & 0x0f, payload)
+    }
+
+    fn read_request(stream: &mut TcpStream) {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            let n = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    #[test]
+    fn buffers_until_the_endpoint_accepts() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/signals", listener.local_addr().unwrap());
+        let first = SignalEnvelope::new("adapter:test", ThinkingPayload::new("a1", "one"));
+        let second = SignalEnvelope::new("adapter:test", ThinkingPayload::new("a1", "two"));
+
+        // The first attempt is refused; the signal waits in the buffer.
+        let refuse = std::thread::spawn({
+            let listener = listener.try_clone().unwrap();
+            move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                read_request(&mut stream);
+                stream.write_all(b"HTTP/1.1 403 Forbidden\r\n\r\n").unwrap();
+            }
+        });
+        let mut transport = WsTransport::new(url).base_delay(Duration::ZERO);
+        transport.send(&first).unwrap();
+        refuse.join().unwrap();
+        assert!(!transport.connected());
+        assert_eq!(transport.buffered(), 1);
+
+        let accept = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_request(&mut stream);
+            stream
+                .write_all(b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n")
+                .unwrap();
+            (0..3).map(|_| read_frame(&mut stream)).collect::<Vec<_>>()
+        });
+        transport.send(&second).unwrap();
+        assert!(transport.connected());
+        transport.close().unwrap();
+
+        let frames = accept.join().unwrap();
+        let text = |signal: &SignalEnvelope| signal.to_value().unwrap().to_string().into_bytes();
+        assert_eq!(frames[0], (OP_TEXT, text(&first)));
+        assert_eq!(frames[1], (OP_TEXT, text(&second)));
+        assert_eq!(frames[2], (OP_CLOSE, 1000u16.to_be_bytes().to_vec()));
+    }
+}
@@ -0,0 +1,61 @@
+//! sajou-emit — native signal emitter for Claude Code hooks and shell scripts.
+//!
+//! Usage:
+//!   sajou-emit tool_call '{"toolName":"Bash","agentId":"claude","callId":"xyz"}'
+//!   echo '{"hook_event_name":"PreToolUse",...}' | sajou-emit --stdin
+//!
+//! Endpoint resolution: --endpoint arg > SAJOU_ENDPOINT env > SAJOU_PORT env >
+//! running sajou app > http://127.0.0.1:5180/api/signal
+
+use std::io::Read;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use sajou_client::endpoint::resolve_endpoint;
+use sajou_client::http::post_json;
+use sajou_lib::emit;
+
+/// Hard deadline for the whole process — hooks must never block the agent.
+const HARD_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn main() -> ExitCode {
+    std::thread::spawn(|| {
+        std::thread::sleep(HARD_TIMEOUT);
+        std::process::exit(1);
+    });
+
+    let args = emit::parse_args(std::env::args().skip(1));
+
+    let signal = if args.stdin {
+        let mut raw = String::new();
+        if std::io::stdin().read_to_string(&mut raw).is_err() {
+            return ExitCode::FAILURE;
+        }
+        serde_json::from_str(&raw)
+            .ok()
+            .and_then(|hook| emit::map_hook_to_signal(&hook))
+    } else if let (Some(signal_type), Some(payload)) = (&args.signal_type, &args.payload_json) {
+        match emit::signal_from_args(signal_type, payload) {
+            Ok(signal) => Some(signal),
+            Err(e) => {
+                eprintln!("sajou-emit: {e}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let Some(signal) = signal else {
+        return ExitCode::FAILURE;
+    };
+
+    let endpoint = resolve_endpoint(args.endpoint.as_deref());
+    match post_json(&endpoint, &signal.to_string()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("sajou-emit: {endpoint}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! API keys held by the backend.
+//!
+//! Keys for hosted LLM providers never live in the webview: the user hands a
+//! key over once (or sets the provider's environment variable) and the native
+//! clients read it from here. The webview can only ask whether one is set.
+//! Stored keys live in plain text in `credentials.json` in the app config
+//! dir, readable by the owner only; see [`set_api_key`] for the limits.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::project::files::write_atomic_private;
+
+/// Credentials file name in the app config dir.
+const CREDENTIALS_FILE: &str = "credentials.json";
+
+/// Where a provider's key comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyOrigin {
+    /// Handed over with `set_api_key`.
+    Stored,
+    /// The provider's environment variable.
+    Env,
+}
+
+/// What the webview may know about a provider's key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyStatus {
+    pub configured: bool,
+    pub origin: Option<KeyOrigin>,
+}
+
+/// Environment variable a provider's key can be read from.
+fn env_var(provider: &str) -> Option<&'static str> {
+    match provider {
+        "anthropic" => Some("ANTHROPIC_API_KEY"),
+        "gemini" => Some("GEMINI_API_KEY"),
+        _ => None,
+    }
+}
+
+fn credentials_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|d| d.join(CREDENTIALS_FILE))
+        .map_err(|e| e.to_string())
+}
+
+/// Stored keys; none when the file is missing. A corrupt file is an error
+/// rather than an empty map, so writing a key never wipes the others.
+fn read_stored(path: &Path) -> io::Result<Map<String, Value>> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => return Err(e),
+    };
+    serde_json::from_str(&raw).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed {}: {e}", path.display()),
+        )
+    })
+}
+
+/// Set (or with `None`, remove) the key stored for `provider` in `path`.
+fn write_stored(path: &Path, provider: &str, key: Option<&str>) -> io::Result<()> {
+    let mut keys = read_stored(path)?;
+    match key {
+        Some(key) => keys.insert(provider.to_string(), key.into()),
+        None => keys.remove(provider),
+    };
+    let raw = serde_json::to_string_pretty(&keys).map_err(io::Error::other)?;
+    write_atomic_private(path, raw.as_bytes())
+}
+
+/// The stored key for `provider`, else its environment variable.
+fn lookup(
+    path: &Path,
+    provider: &str,
+    env: impl Fn(&str) -> Option<String>,
+) -> io::Result<Option<(String, KeyOrigin)>> {
+    let stored = read_stored(path)?
+        .get(provider)
+        .and_then(Value::as_str)
+        .filter(|k| !k.is_empty())
+        .map(|k| (k.to_string(), KeyOrigin::Stored));
+    Ok(stored.or_else(|| {
+        env_var(provider)
+            .and_then(env)
+            .filter(|k| !k.is_empty())
+            .map(|k| (k, KeyOrigin::Env))
+    }))
+}
+
+/// The key to use for `provider`, for native clients only.
+pub fn api_key(app: &AppHandle, provider: &str) -> Result<Option<String>, String> {
+    let path = credentials_path(app)?;
+    lookup(&path, provider, |key| std::env::var(key).ok())
+        .map(|found| found.map(|(key, _)| key))
+        .map_err(|e| e.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Whether a key is available for `provider`, and from where.
+#[tauri::command]
+pub fn api_key_status(app: AppHandle, provider: String) -> Result<KeyStatus, String> {
+    let path = credentials_path(&app)?;
+    let origin = lookup(&path, &provider, |key| std::env::var(key).ok())
+        .map_err(|e| e.to_string())?
+        .map(|(_, origin)| origin);
+    Ok(KeyStatus {
+        configured: origin.is_some(),
+        origin,
+    })
+}
+
+/// Store the key for `provider`; an empty or missing `key` forgets it.
+/// The key itself is never handed back.
+///
+/// Limits: there is one key per provider, so every source of that provider
+/// shares it and setting it from one source replaces it for the others. It
+/// is kept in plain text, not in the OS keychain: the 0600 mode (Unix only)
+/// keeps other users out, not other programs running as this user.
+#[tauri::command]
+pub fn set_api_key(
+    app: AppHandle,
+    provider: String,
+    key: Option<String>,
+) -> Result<KeyStatus, String> {
+    let path = credentials_path(&app)?;
+    let key = key.filter(|k| !k.trim().is_empty());
+    write_stored(&path, &provider, key.as_deref().map(str::trim)).map_err(|e| e.to_string())?;
+    api_key_status(app, provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_keys_win_over_the_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config").join(CREDENTIALS_FILE);
+        let env = |key: &str| (key == "ANTHROPIC_API_KEY").then(|| "sk-env".to_string());
+
+        assert_eq!(
+            lookup(&path, "anthropic", env).unwrap(),
+            Some(("sk-env".into(), KeyOrigin::Env))
+        );
+
+        write_stored(&path, "anthropic", Some("sk-stored")).unwrap();
+        assert_eq!(
+            lookup(&path, "anthropic", env).unwrap(),
+            Some(("sk-stored".into(), KeyOrigin::Stored))
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        write_stored(&path, "anthropic", None).unwrap();
+        assert_eq!(lookup(&path, "anthropic", |_| None).unwrap(), None);
+    }
+
+    #[test]
+    fn corrupt_credentials_are_not_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CREDENTIALS_FILE);
+        std::fs::write(&path, r#"{"gemini":"#).unwrap();
+
+        assert!(lookup(&path, "gemini", |_| None).is_err());
+        let err = write_stored(&path, "anthropic", Some("sk-new")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), r#"{"gemini":"#);
+    }
+}
@@ -0,0 +1,388 @@
+//! Local service discovery — native probes driven by an editable registry.
+//!
+//! Port of the probes in `src/state/local-discovery.ts`. Each registry entry
+//! names the ports to try and how to probe them; every entry is probed in
+//! parallel over IPv4 then IPv6 loopback. The registry lives in
+//! `local-services.json` under the app config dir and is seeded with the
+//! built-in services on first use, so new local runtimes (vLLM, llama.cpp
+//! server, a private gateway) can be added without a frontend release.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_http::reqwest;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::sources::ollama::{models_from_tags, OllamaModel};
+
+/// Registry file name, in the app config dir.
+const REGISTRY_FILE: &str = "local-services.json";
+
+/// Timeout for each connect and for each HTTP request.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Loopback addresses tried for every port, in order.
+const LOOPBACK: [IpAddr; 2] = [
+    IpAddr::V4(Ipv4Addr::LOCALHOST),
+    IpAddr::V6(Ipv6Addr::LOCALHOST),
+];
+
+/// How a service is detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProbeKind {
+    /// A TCP connect succeeds.
+    Tcp,
+    /// `GET /v1/models` answers 2xx; model IDs are read from `data[].id`.
+    OpenaiModels,
+    /// Ollama's `GET /api/tags` answers 2xx; models come with their details.
+    OllamaTags,
+}
+
+/// One entry of the service registry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceDefinition {
+    /// Source ID without the `local:` prefix.
+    pub id: String,
+    pub label: String,
+    /// Ports to try, first answer wins.
+    pub ports: Vec<u16>,
+    pub probe: ProbeKind,
+    /// `TransportProtocol` of the resulting signal source.
+    pub protocol: String,
+    /// URL scheme of the resulting source (`http`, `ws`).
+    #[serde(default = "default_scheme")]
+    pub scheme: String,
+    #[serde(default)]
+    pub needs_api_key: bool,
+}
+
+fn default_scheme() -> String {
+    "http".into()
+}
+
+/// Registry file layout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Registry {
+    services: Vec<ServiceDefinition>,
+}
+
+/// Mirrors `DiscoveredService` in `signal-source-state.ts`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredService {
+    pub id: String,
+    pub label: String,
+    pub protocol: String,
+    pub url: String,
+    pub available: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub needs_api_key: bool,
+    pub models: Vec<String>,
+    /// Per-model details, for probes that report them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub model_details: Vec<OllamaModel>,
+}
+
+/// What a successful probe found.
+#[derive(Debug, Default)]
+struct Found {
+    models: Vec<String>,
+    details: Vec<OllamaModel>,
+}
+
+/// The services probed before the registry existed.
+pub fn default_services() -> Vec<ServiceDefinition> {
+    let service =
+        |id: &str, label: &str, port, probe, protocol: &str, scheme: &str, key| ServiceDefinition {
+            id: id.into(),
+            label: label.into(),
+            ports: vec![port],
+            probe,
+            protocol: protocol.into(),
+            scheme: scheme.into(),
+            needs_api_key: key,
+        };
+    vec![
+        service(
+            "openclaw",
+            "OpenClaw",
+            18789,
+            ProbeKind::Tcp,
+            "openclaw",
+            "ws",
+            true,
+        ),
+        service(
+            "lm-studio",
+            "LM Studio",
+            1234,
+            ProbeKind::OpenaiModels,
+            "openai",
+            "http",
+            true,
+        ),
+        service(
+            "ollama",
+            "Ollama",
+            11434,
+            ProbeKind::OllamaTags,
+            "ollama",
+            "http",
+            false,
+        ),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// Registry
+// ---------------------------------------------------------------------------
+
+/// Load the registry at `path`, writing the defaults if it does not exist.
+/// A malformed file is reported and the defaults are used for this scan.
+fn load_registry(path: &Path) -> Vec<ServiceDefinition> {
+    match std::fs::read_to_string(path) {
+        Ok(raw) => match serde_json::from_str::<Registry>(&raw) {
+            Ok(registry) => registry.services,
+            Err(e) => {
+                eprintln!("[sajou] invalid {}: {e}", path.display());
+                default_services()
+            }
+        },
+        Err(_) => {
+            let services = default_services();
+            let raw = serde_json::to_string_pretty(&Registry {
+                services: services.clone(),
+            })
+            .unwrap_or_default();
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            if let Err(e) = std::fs::write(path, raw) {
+                eprintln!("[sajou] cannot write {}: {e}", path.display());
+            }
+            services
+        }
+    }
+}
+
+fn registry_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|d| d.join(REGISTRY_FILE))
+}
+
+// ---------------------------------------------------------------------------
+// Probes
+// ---------------------------------------------------------------------------
+
+/// Host part of a URL for `ip`.
+fn url_host(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => v4.to_string(),
+        IpAddr::V6(v6) => format!("[{v6}]"),
+    }
+}
+
+async fn connect(addr: SocketAddr) -> Option<TcpStream> {
+    timeout(PROBE_TIMEOUT, TcpStream::connect(addr))
+        .await
+        .ok()?
+        .ok()
+}
+
+/// JSON body of `GET path` on `addr`. `None` when the request fails or the
+/// answer is not a 2xx.
+async fn fetch_json(addr: SocketAddr, path: &str) -> Option<Value> {
+    let url = format!("http://{}:{}{path}", url_host(addr.ip()), addr.port());
+    let response = reqwest::Client::new()
+        .get(&url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let body = response.bytes().await.ok()?;
+
+    // Availability does not depend on the body — an unexpected shape just
+    // means no model list.
+    match serde_json::from_slice(&body) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            eprintln!("[sajou] unexpected body from {url}: {e}");
+            Some(Value::Null)
+        }
+    }
+}
+
+/// Model IDs from an OpenAI-compatible `/v1/models` body.
+fn model_ids(json: &Value) -> Vec<String> {
+    json.get("data")
+        .and_then(Value::as_array)
+        .map(|data| {
+            data.iter()
+                .filter_map(|m| m.get("id"))
+                .map(|id| match id {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Probe one address; `Some` when the service answers.
+async fn probe_addr(kind: ProbeKind, addr: SocketAddr) -> Option<Found> {
+    match kind {
+        ProbeKind::Tcp => connect(addr).await.map(|_| Found::default()),
+        ProbeKind::OpenaiModels => {
+            let models = fetch_json(addr, "/v1/models").await?;
+            Some(Found {
+                models: model_ids(&models),
+                details: Vec::new(),
+            })
+        }
+        ProbeKind::OllamaTags => {
+            let tags = fetch_json(addr, "/api/tags").await?;
+            let details = models_from_tags(&tags);
+            Some(Found {
+                models: details.iter().map(|m| m.name.clone()).collect(),
+                details,
+            })
+        }
+    }
+}
+
+/// Probe every port of `def` over IPv4 then IPv6 loopback.
+pub async fn probe_service(def: &ServiceDefinition) -> DiscoveredService {
+    let mut found = None;
+    'ports: for &port in &def.ports {
+        for ip in LOOPBACK {
+            if let Some(probed) = probe_addr(def.probe, SocketAddr::new(ip, port)).await {
+                found = Some((ip, port, probed));
+                break 'ports;
+            }
+        }
+    }
+
+    let available = found.is_some();
+    let (ip, port, probed) = found.unwrap_or_else(|| {
+        let port = def.ports.first().copied().unwrap_or_default();
+        (LOOPBACK[0], port, Found::default())
+    });
+    DiscoveredService {
+        id: format!("local:{}", def.id),
+        label: def.label.clone(),
+        protocol: def.protocol.clone(),
+        url: format!("{}://{}:{port}", def.scheme, url_host(ip)),
+        available,
+        needs_api_key: def.needs_api_key,
+        models: probed.models,
+        model_details: probed.details,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Probe every registered local service in parallel.
+#[tauri::command]
+pub async fn discover_local_services(app: AppHandle) -> Vec<DiscoveredService> {
+    let services = match registry_path(&app) {
+        Some(path) => load_registry(&path),
+        None => default_services(),
+    };
+    join_all(services.iter().map(probe_service)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answer one HTTP request on a loopback port with `response`.
+    fn serve_once(response: &'static str) -> u16 {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        port
+    }
+
+    #[test]
+    fn reads_models_from_a_chunked_response() {
+        let port = serve_once(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+             e\r\n{\"data\":[{\"id\"\r\n\
+             d\r\n:\"qwen3\"}]}  \r\n0\r\n\r\n",
+        );
+        let addr = SocketAddr::new(LOOPBACK[0], port);
+        let models = tauri::async_runtime::block_on(fetch_json(addr, "/v1/models")).unwrap();
+        assert_eq!(model_ids(&models), vec!["qwen3".to_string()]);
+
+        for response in [
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+            // Truncated: the connection closes before the announced length.
+            "HTTP/1.1 200 OK\r\nContent-Length: 50\r\n\r\n{\"data\":",
+        ] {
+            let addr = SocketAddr::new(LOOPBACK[0], serve_once(response));
+            assert_eq!(
+                tauri::async_runtime::block_on(fetch_json(addr, "/v1/models")),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn registry_is_seeded_then_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(REGISTRY_FILE);
+        assert_eq!(load_registry(&path), default_services());
+
+        std::fs::write(
+            &path,
+            r#"{ "services": [{ "id": "vllm", "label": "vLLM", "ports": [8000, 8001],
+                 "probe": "openai-models", "protocol": "openai" }] }"#,
+        )
+        .unwrap();
+        let services = load_registry(&path);
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].scheme, "http");
+        assert!(!services[0].needs_api_key);
+    }
+
+    #[test]
+    fn probes_fall_through_the_port_list() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let def = ServiceDefinition {
+            id: "gateway".into(),
+            label: "Gateway".into(),
+            ports: vec![1, port],
+            probe: ProbeKind::Tcp,
+            protocol: "websocket".into(),
+            scheme: "ws".into(),
+            needs_api_key: false,
+        };
+        let found = tauri::async_runtime::block_on(probe_service(&def));
+        assert!(found.available);
+        assert_eq!(found.url, format!("ws://127.0.0.1:{port}"));
+    }
+}
@@ -0,0 +1,214 @@
+//! Core of the native `sajou-emit` binary.
+//!
+//! Port of `adapters/tap/src/emit-cli.ts`: maps a Claude Code hook payload to
+//! a signal envelope; `sajou-emit` POSTs it to the running app with
+//! [`sajou_client::http::post_json`].
+
+use serde_json::{json, Map, Value};
+
+use crate::signal_parser::record;
+use crate::signals::now_ms;
+
+/// Default source identifier for signals created by tap.
+const DEFAULT_SOURCE: &str = "adapter:tap";
+
+/// Parsed CLI arguments.
+#[derive(Debug, Default, PartialEq)]
+pub struct EmitArgs {
+    /// Signal type (positional arg) — only in arg mode.
+    pub signal_type: Option<String>,
+    /// JSON payload string (positional arg) — only in arg mode.
+    pub payload_json: Option<String>,
+    /// The endpoint URL to send to.
+    pub endpoint: Option<String>,
+    /// Read hook JSON from stdin instead of positional args.
+    pub stdin: bool,
+}
+
+/// Parse arguments (without the program name).
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> EmitArgs {
+    let mut parsed = EmitArgs::default();
+    let mut positional = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--stdin" {
+            parsed.stdin = true;
+        } else if arg == "--endpoint" {
+            parsed.endpoint = args.next();
+        } else if !arg.starts_with("--") {
+            positional.push(arg);
+        }
+    }
+    let mut positional = positional.into_iter();
+    parsed.signal_type = positional.next();
+    parsed.payload_json = positional.next();
+    parsed
+}
+
+/// Build a tap signal envelope, omitting absent optional fields.
+fn tap_signal(
+    signal_type: &str,
+    payload: Map<String, Value>,
+    correlation_id: Option<&Value>,
+) -> Value {
+    let mut envelope = Map::new();
+    envelope.insert(
+        "id".into(),
+        Value::from(format!("tap-{}", uuid::Uuid::new_v4())),
+    );
+    envelope.insert("type".into(), Value::from(signal_type));
+    envelope.insert("timestamp".into(), Value::from(now_ms()));
+    envelope.insert("source".into(), Value::from(DEFAULT_SOURCE));
+    if let Some(id) = correlation_id {
+        envelope.insert("correlationId".into(), id.clone());
+    }
+    envelope.insert("payload".into(), Value::Object(payload));
+    Value::Object(envelope)
+}
+
+/// Map a Claude Code hook event to a signal envelope.
+///
+/// Returns `None` for hook events sajou does not visualise.
+pub fn map_hook_to_signal(hook: &Value) -> Option<Value> {
+    let field = |key: &str| hook.get(key).filter(|v| !v.is_null()).cloned();
+    let or = |key: &str, fallback: &str| Some(field(key).unwrap_or_else(|| fallback.into()));
+    let correlation_id = hook.get("session_id").filter(|v| !v.is_null());
+
+    let (signal_type, payload) = match hook["hook_event_name"].as_str()? {
+        "PreToolUse" => (
+            "tool_call",
+            record([
+                ("toolName", or("tool_name", "unknown")),
+                ("agentId", Some("claude".into())),
+                ("callId", field("tool_use_id")),
+                ("input", field("tool_input")),
+            ]),
+        ),
+        "PostToolUse" => {
+            let output = field("tool_response")
+                .filter(|r| r.as_str() != Some(""))
+                .map(|r| json!({ "response": r }));
+            (
+                "tool_result",
+                record([
+                    ("toolName", or("tool_name", "unknown")),
+                    ("agentId", Some("claude".into())),
+                    ("callId", field("tool_use_id")),
+                    ("success", Some(true.into())),
+                    ("output", output),
+                ]),
+            )
+        }
+        "PostToolUseFailure" => (
+            "error",
+            record([
+                ("agentId", Some("claude".into())),
+                ("message", or("error", "Tool use failed")),
+                ("severity", Some("error".into())),
+                ("code", field("tool_name")),
+            ]),
+        ),
+        "SubagentStart" => (
+            "task_dispatch",
+            record([
+                ("taskId", or("agent_id", "unknown")),
+                ("from", Some("claude".into())),
+                ("to", or("agent_type", "subagent")),
+            ]),
+        ),
+        "SubagentStop" => (
+            "completion",
+            record([
+                ("taskId", or("agent_id", "unknown")),
+                ("agentId", field("agent_type")),
+                ("success", Some(true.into())),
+            ]),
+        ),
+        "Stop" => (
+            "agent_state_change",
+            record([
+                ("agentId", Some("claude".into())),
+                ("from", Some("acting".into())),
+                ("to", Some("done".into())),
+            ]),
+        ),
+        "SessionStart" => (
+            "agent_state_change",
+            record([
+                ("agentId", Some("claude".into())),
+                ("from", Some("idle".into())),
+                ("to", Some("thinking".into())),
+            ]),
+        ),
+        _ => return None,
+    };
+
+    Some(tap_signal(signal_type, payload, correlation_id))
+}
+
+/// Build a signal from positional `<type> <json>` arguments.
+pub fn signal_from_args(signal_type: &str, payload_json: &str) -> Result<Value, String> {
+    let payload: Map<String, Value> =
+        serde_json::from_str(payload_json).map_err(|e| format!("invalid payload JSON: {e}"))?;
+    Ok(tap_signal(signal_type, payload, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_endpoint_and_positionals() {
+        let args = parse_args(
+            [
+                "--endpoint",
+                "http://remote:8080/api/signal",
+                "tool_call",
+                "{}",
+            ]
+            .map(String::from),
+        );
+        assert_eq!(
+            args.endpoint.as_deref(),
+            Some("http://remote:8080/api/signal")
+        );
+        assert_eq!(args.signal_type.as_deref(), Some("tool_call"));
+        assert_eq!(args.payload_json.as_deref(), Some("{}"));
+        assert!(!args.stdin);
+    }
+
+    #[test]
+    fn maps_pre_tool_use_to_tool_call() {
+        let signal = map_hook_to_signal(&json!({
+            "hook_event_name": "PreToolUse",
+            "tool_name": "Bash",
+            "tool_use_id": "tu-1",
+            "tool_input": { "command": "ls" },
+            "session_id": "sess-42",
+        }))
+        .unwrap();
+        assert_eq!(signal["type"], "tool_call");
+        assert_eq!(signal["source"], "adapter:tap");
+        assert_eq!(signal["correlationId"], "sess-42");
+        assert_eq!(
+            signal["payload"],
+            json!({ "toolName": "Bash", "agentId": "claude", "callId": "tu-1", "input": { "command": "ls" } })
+        );
+    }
+
+    #[test]
+    fn maps_subagent_start_with_defaults() {
+        let signal = map_hook_to_signal(&json!({ "hook_event_name": "SubagentStart" })).unwrap();
+        assert_eq!(signal["type"], "task_dispatch");
+        assert!(signal.get("correlationId").is_none());
+        assert_eq!(
+            signal["payload"],
+            json!({ "taskId": "unknown", "from": "claude", "to": "subagent" })
+        );
+    }
+
+    #[test]
+    fn ignores_unmapped_events() {
+        assert!(map_hook_to_signal(&json!({ "hook_event_name": "Notification" })).is_none());
+    }
+}
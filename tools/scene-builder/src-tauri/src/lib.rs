@@ -1,25 +1,118 @@
-/// Read the OpenClaw gateway auth token from ~/.openclaw/openclaw.json.
-/// Returns the token string or an error if the file is missing/malformed.
-#[tauri::command]
-fn read_openclaw_token() -> Result<String, String> {
-    let home = dirs::home_dir().ok_or("cannot resolve home directory")?;
-    let path = home.join(".openclaw").join("openclaw.json");
-    let raw = std::fs::read_to_string(&path)
-        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
-    let json: serde_json::Value =
-        serde_json::from_str(&raw).map_err(|e| format!("invalid JSON: {e}"))?;
-    json.pointer("/gateway/auth/token")
-        .and_then(|v| v.as_str())
-        .map(String::from)
-        .ok_or_else(|| "gateway.auth.token not found in openclaw.json".into())
-}
+use tauri::Manager;
+
+mod credentials;
+mod discovery;
+pub mod emit;
+pub mod mcp;
+mod openclaw;
+mod project;
+pub mod scene;
+mod server;
+pub mod signal_parser;
+mod signals;
+mod sources;
+mod state;
+mod tap;
+mod validation;
+mod ws;
+
+pub use sajou_client::model as signal_model;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_http::init())
-        .invoke_handler(tauri::generate_handler![read_openclaw_token])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            let store = state::StateStore::default();
+            state::store::forward_changes(app.handle(), &store);
+            project::journal::start(app.handle(), &store);
+            app.manage(state::commands::CommandQueue::default());
+            state::commands::forward_mutations(app.handle(), &store);
+            app.manage(store);
+            app.manage(sources::Sources::default());
+            app.manage(validation::Validator::for_app(app.handle()));
+            app.manage(project::Project::for_app(app.handle()));
+            project::document::init(app.handle());
+            app.manage(project::snapshots::Snapshots::for_app(app.handle()));
+            project::snapshots::start(app.handle());
+            tap::init(app.handle());
+            openclaw::watch(app.handle());
+            // A busy or forbidden port must not prevent the editor from opening.
+            if let Err(e) = server::start(app.handle()) {
+                eprintln!("[sajou] signal server unavailable: {e}");
+            }
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            openclaw::read_openclaw_token,
+            openclaw::list_openclaw_profiles,
+            discovery::discover_local_services,
+            sources::openclaw::openclaw_connect,
+            sources::openai::openai_prompt,
+            sources::anthropic::anthropic_probe,
+            sources::anthropic::anthropic_prompt,
+            sources::gemini::gemini_probe,
+            sources::gemini::gemini_prompt,
+            sources::ollama::ollama_models,
+            sources::ollama::ollama_prompt,
+            credentials::api_key_status,
+            credentials::set_api_key,
+            sources::source_disconnect,
+            sources::source_stop_prompt,
+            sources::source_statuses,
+            server::signal_server_info,
+            validation::signal_validation_policy,
+            validation::set_signal_validation_policy,
+            validation::rejected_signals,
+            validation::clear_rejected_signals,
+            tap::tap_status,
+            tap::tap_pick_project,
+            tap::tap_install_hooks,
+            tap::tap_uninstall_hooks,
+            state::store::state_pull,
+            state::store::state_push,
+            state::store::state_execute,
+            state::store::state_reset,
+            state::commands::commands_pending,
+            state::commands::commands_ack,
+            project::project_dir,
+            project::project_open_dir,
+            project::project_read,
+            project::project_write,
+            project::project_asset_keys,
+            project::project_write_asset,
+            project::project_read_assets,
+            project::project_import_indexeddb,
+            project::project_clear,
+            project::document::project_document,
+            project::document::project_recent,
+            project::document::project_new,
+            project::document::project_open,
+            project::document::project_save,
+            project::document::project_save_as,
+            project::document::project_close_ready,
+            project::snapshots::project_snapshot_settings,
+            project::snapshots::set_project_snapshot_settings,
+            project::snapshots::project_snapshots,
+            project::snapshots::project_snapshot_now,
+            project::snapshots::project_snapshot_diff,
+            project::snapshots::project_snapshot_restore,
+            project::journal::project_journal,
+            project::journal::project_recover,
+            scene::scene_export,
+            scene::scene_import_inspect,
+            scene::scene_import
+        ])
+        .on_window_event(project::document::on_window_event)
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                tap::cleanup(app);
+                project::document::on_exit(app);
+                server::clear_endpoint();
+            }
+        });
 }
@@ -1,6 +1,43 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-fn main() {
-    sajou_lib::run()
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("mcp") {
+        attach_parent_console();
+        if args.iter().any(|a| a == "--stdio") {
+            return sajou_lib::mcp::stdio::run();
+        }
+        eprintln!("usage: sajou mcp --stdio");
+        return ExitCode::from(2);
+    }
+    if args.first().map(String::as_str) == Some("scene") {
+        attach_parent_console();
+        return sajou_lib::scene::cli::run(&args[1..]);
+    }
+    sajou_lib::run();
+    ExitCode::SUCCESS
+}
+
+/// Attach to the console of the shell that started a CLI subcommand:
+/// release builds use the GUI subsystem, which gets none of its own.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Fails harmlessly without a parent console; redirected pipes are
+    // inherited either way.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
 }
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
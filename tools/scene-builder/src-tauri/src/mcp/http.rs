@@ -0,0 +1,172 @@
+//! MCP Streamable HTTP endpoint on the loopback listener.
+//!
+//! Port of `packages/mcp-server/src/mcp/transport.ts`. Requests are answered
+//! with plain JSON bodies; the server never initiates messages, so `GET`
+//! (the optional server→client stream) is refused with 405.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use super::ToolContext;
+use crate::signals::SignalHub;
+use crate::state::StateStore;
+
+/// Session header defined by the Streamable HTTP transport.
+const SESSION_HEADER: &str = "mcp-session-id";
+
+#[derive(Clone)]
+struct McpHttp {
+    app: AppHandle,
+    hub: SignalHub,
+    sessions: Arc<Mutex<HashSet<String>>>,
+}
+
+/// `/mcp` route.
+pub(crate) fn routes(app: AppHandle, hub: SignalHub) -> Router {
+    let state = McpHttp {
+        app,
+        hub,
+        sessions: Arc::default(),
+    };
+    Router::new()
+        .route("/mcp", post(post_mcp).get(get_mcp).delete(delete_mcp))
+        .with_state(state)
+}
+
+/// JSON-RPC error with an HTTP status, as the Node transport sends them.
+fn rpc_error(status: StatusCode, message: &str) -> Response {
+    (
+        status,
+        Json(json!({ "jsonrpc": "2.0", "error": { "code": -32000, "message": message }, "id": null })),
+    )
+        .into_response()
+}
+
+/// Reject cross-site browser requests (DNS rebinding). Clients without an
+/// `Origin` header — every non-browser MCP client — are accepted.
+fn origin_allowed(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let origin = origin.to_str().unwrap_or_default();
+    if origin.starts_with("tauri://") {
+        return true;
+    }
+    let host = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    let host = match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or_default(),
+        None => host.split([':', '/']).next().unwrap_or_default(),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1" | "tauri.localhost")
+}
+
+fn session_id(headers: &HeaderMap) -> Option<&str> {
+    headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok())
+}
+
+/// `POST /mcp` — one JSON-RPC message or batch.
+async fn post_mcp(State(mcp): State<McpHttp>, headers: HeaderMap, body: Bytes) -> Response {
+    if !origin_allowed(&headers) {
+        return rpc_error(StatusCode::FORBIDDEN, "Forbidden origin");
+    }
+    let message: Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(super::parse_error(&e))).into_response(),
+    };
+
+    let new_session = if super::is_initialize(&message) {
+        let id = uuid::Uuid::new_v4().to_string();
+        mcp.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id.clone());
+        Some(id)
+    } else {
+        let sessions = mcp.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        match session_id(&headers) {
+            None => {
+                return rpc_error(
+                    StatusCode::BAD_REQUEST,
+                    "No valid session. Send a POST to initialize.",
+                )
+            }
+            Some(id) if !sessions.contains(id) => {
+                return rpc_error(StatusCode::NOT_FOUND, "Session not found")
+            }
+            Some(_) => None,
+        }
+    };
+
+    let store = mcp.app.state::<StateStore>();
+    let emit = |envelope: Value| {
+        mcp.hub.ingest(envelope, "mcp")?;
+        Ok(mcp.hub.client_count())
+    };
+    let ctx = ToolContext {
+        store: &store,
+        emit: &emit,
+    };
+    let reply = super::handle(&ctx, &message);
+
+    let mut res = match reply {
+        Some(reply) => Json(reply).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    };
+    if let Some(id) = new_session.and_then(|id| HeaderValue::from_str(&id).ok()) {
+        res.headers_mut().insert(SESSION_HEADER, id);
+    }
+    res
+}
+
+/// `GET /mcp` — no server-initiated stream is offered.
+async fn get_mcp() -> Response {
+    (
+        StatusCode::METHOD_NOT_ALLOWED,
+        [(header::ALLOW, "POST, DELETE")],
+    )
+        .into_response()
+}
+
+/// `DELETE /mcp` — end a session.
+async fn delete_mcp(State(mcp): State<McpHttp>, headers: HeaderMap) -> Response {
+    let removed = session_id(&headers).is_some_and(|id| {
+        mcp.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(id)
+    });
+    if removed {
+        StatusCode::OK.into_response()
+    } else {
+        rpc_error(StatusCode::NOT_FOUND, "Session not found")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_loopback_origins_are_allowed() {
+        let with_origin = |origin: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ORIGIN, HeaderValue::from_str(origin).unwrap());
+            origin_allowed(&headers)
+        };
+        assert!(origin_allowed(&HeaderMap::new()));
+        assert!(with_origin("http://localhost:5175"));
+        assert!(with_origin("http://[::1]:5180"));
+        assert!(with_origin("tauri://localhost"));
+        assert!(!with_origin("https://evil.example"));
+        assert!(!with_origin("http://localhost.evil.example"));
+    }
+}
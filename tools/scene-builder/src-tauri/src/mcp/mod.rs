@@ -0,0 +1,161 @@
+//! Built-in MCP server.
+//!
+//! Port of `packages/mcp-server/src/server.ts`: the same tool catalog, served
+//! over Streamable HTTP on the loopback listener ([`http`]) and reachable
+//! from stdio-only clients through `sajou mcp --stdio` ([`stdio`]).
+
+pub(crate) mod http;
+pub mod stdio;
+mod tools;
+
+use serde_json::{json, Value};
+
+use crate::state::StateStore;
+
+/// Protocol revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// What tool handlers can touch.
+pub(crate) struct ToolContext<'a> {
+    pub store: &'a StateStore,
+    /// Validate and broadcast a signal envelope; returns the number of
+    /// stream clients, or why the envelope was rejected.
+    pub emit: &'a dyn Fn(Value) -> Result<usize, String>,
+}
+
+/// JSON-RPC error response.
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Whether `message` is an `initialize` request.
+pub(crate) fn is_initialize(message: &Value) -> bool {
+    match message {
+        Value::Array(batch) => batch.iter().any(is_initialize),
+        _ => message.get("method").and_then(Value::as_str) == Some("initialize"),
+    }
+}
+
+/// Handle one JSON-RPC message (or batch). Returns `None` when nothing needs
+/// to be sent back (notifications and responses).
+pub(crate) fn handle(ctx: &ToolContext, message: &Value) -> Option<Value> {
+    if let Value::Array(batch) = message {
+        let replies: Vec<Value> = batch.iter().filter_map(|m| handle(ctx, m)).collect();
+        return (!replies.is_empty()).then_some(Value::Array(replies));
+    }
+
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        // A response from the client (we never send requests) — or garbage.
+        if message.get("result").is_some() || message.get("error").is_some() {
+            return None;
+        }
+        return Some(error_response(
+            Value::Null,
+            INVALID_REQUEST,
+            "Invalid request",
+        ));
+    };
+    // Notifications carry no id and get no reply.
+    let id = message.get("id")?.clone();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => initialize(&params),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tools::list() }),
+        "tools/call" => {
+            let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+            let args = params.get("arguments").cloned().unwrap_or(json!({}));
+            match tools::call(ctx, name, &args) {
+                Some(result) => result,
+                None => {
+                    return Some(error_response(
+                        id,
+                        INVALID_PARAMS,
+                        &format!("Tool {name} not found"),
+                    ))
+                }
+            }
+        }
+        _ => {
+            return Some(error_response(
+                id,
+                METHOD_NOT_FOUND,
+                &format!("Method not found: {method}"),
+            ))
+        }
+    };
+
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+/// Reply to `initialize`, echoing the client's protocol version if supported.
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "sajou", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+/// JSON-RPC reply for a body that is not valid JSON.
+pub(crate) fn parse_error(e: &serde_json::Error) -> Value {
+    error_response(Value::Null, PARSE_ERROR, &format!("Parse error: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(store: &StateStore) -> ToolContext<'_> {
+        ToolContext {
+            store,
+            emit: &|_| Ok(0),
+        }
+    }
+
+    #[test]
+    fn negotiates_protocol_version() {
+        let store = StateStore::default();
+        let reply = handle(
+            &ctx(&store),
+            &json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize",
+                     "params": { "protocolVersion": "2024-11-05" } }),
+        )
+        .unwrap();
+        assert_eq!(reply["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(reply["result"]["serverInfo"]["name"], "sajou");
+    }
+
+    #[test]
+    fn notifications_get_no_reply() {
+        let store = StateStore::default();
+        let reply = handle(
+            &ctx(&store),
+            &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        );
+        assert!(reply.is_none());
+    }
+
+    #[test]
+    fn unknown_tool_is_an_error() {
+        let store = StateStore::default();
+        let reply = handle(
+            &ctx(&store),
+            &json!({ "jsonrpc": "2.0", "id": "a", "method": "tools/call",
+                     "params": { "name": "nope", "arguments": {} } }),
+        )
+        .unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+    }
+}
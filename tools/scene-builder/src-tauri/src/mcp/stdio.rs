@@ -0,0 +1,249 @@
+//! `sajou mcp --stdio` — stdio MCP transport proxied to the running app.
+//!
+//! Clients that only speak stdio (Claude Desktop, Claude Code) launch the
+//! installed binary in this mode. Each newline-delimited JSON-RPC message is
+//! POSTed to the app's `/mcp` endpoint and the reply written back to stdout,
+//! so tools act on the scene open in the editor.
+
+use std::io::{self, BufRead, Read, Write};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use sajou_client::endpoint::{connect, split_url};
+use serde_json::{json, Value};
+
+use crate::server::{self, DEFAULT_PORT};
+
+/// Connect timeout for the loopback POST.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Read/write timeout — generous, a tool call may touch a large scene.
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A parsed HTTP response.
+struct Reply {
+    status: u16,
+    session: Option<String>,
+    body: String,
+}
+
+/// URL of the running app's MCP endpoint.
+///
+/// Order: `SAJOU_MCP_URL` > `SAJOU_PORT` > the running app's advertised
+/// port > [`DEFAULT_PORT`].
+fn resolve_url() -> String {
+    if let Ok(url) = std::env::var("SAJOU_MCP_URL") {
+        return url;
+    }
+    let port = std::env::var("SAJOU_PORT")
+        .ok()
+        .and_then(|p| p.parse::<u16>().ok())
+        .or_else(|| server::read_endpoint().map(|info| info.port))
+        .unwrap_or(DEFAULT_PORT);
+    format!("http://127.0.0.1:{port}/mcp")
+}
+
+/// POST `body` to `url` over a fresh connection.
+fn post(url: &str, session: Option<&str>, body: &str) -> io::Result<Reply> {
+    let (host, port, path) = split_url(url)?;
+    let mut stream = connect(&host, port, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let session_header = session
+        .map(|id| format!("Mcp-Session-Id: {id}\r\n"))
+        .unwrap_or_default();
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: {host}:{port}\r\nContent-Type: application/json\r\n\
+         Accept: application/json, text/event-stream\r\n{session_header}\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(request.as_bytes())?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+    parse_reply(&String::from_utf8_lossy(&raw))
+}
+
+fn parse_reply(raw: &str) -> io::Result<Reply> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response");
+    let (head, body) = raw.split_once("\r\n\r\n").ok_or_else(invalid)?;
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(invalid)?;
+    let session = lines.find_map(|l| {
+        let (name, value) = l.split_once(':')?;
+        name.eq_ignore_ascii_case("mcp-session-id")
+            .then(|| value.trim().to_string())
+    });
+    Ok(Reply {
+        status,
+        session,
+        body: body.to_string(),
+    })
+}
+
+/// Error reply for a request the app could not answer.
+fn unavailable(message: &Value, reason: &str) -> Option<Value> {
+    let id = message.get("id")?.clone();
+    Some(json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": -32000, "message": format!("sajou app unavailable: {reason}") },
+    }))
+}
+
+/// Proxy state across messages.
+#[derive(Default)]
+struct Proxy {
+    session: Option<String>,
+    /// The client's `initialize` request, replayed if the app restarts.
+    initialize: Option<String>,
+}
+
+impl Proxy {
+    /// Forward one message; returns the body to write back, if any.
+    fn forward(&mut self, url: &str, line: &str) -> io::Result<Option<String>> {
+        let message: Value = serde_json::from_str(line).unwrap_or(Value::Null);
+        if crate::mcp::is_initialize(&message) {
+            self.initialize = Some(line.to_string());
+            self.session = None;
+        }
+
+        let mut reply = post(url, self.session.as_deref(), line)?;
+        if reply.status == 404 && self.reinitialize(url)? {
+            // The app restarted and forgot our session — retry on a new one.
+            reply = post(url, self.session.as_deref(), line)?;
+        }
+        if let Some(session) = reply.session.take() {
+            self.session = Some(session);
+        }
+
+        match reply.status {
+            202 => Ok(None),
+            _ if reply.body.trim().is_empty() => Ok(None),
+            _ => Ok(Some(reply.body.trim().to_string())),
+        }
+    }
+
+    /// Replay the client's handshake to open a new session.
+    fn reinitialize(&mut self, url: &str) -> io::Result<bool> {
+        let Some(init) = self.initialize.clone() else {
+            return Ok(false);
+        };
+        let reply = post(url, None, &init)?;
+        let Some(session) = reply.session else {
+            return Ok(false);
+        };
+        let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        post(url, Some(&session), &initialized.to_string())?;
+        self.session = Some(session);
+        Ok(true)
+    }
+}
+
+/// Run the stdio proxy until stdin closes.
+pub fn run() -> ExitCode {
+    let url = resolve_url();
+    let mut proxy = Proxy::default();
+    let stdout = io::stdout();
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let out = match proxy.forward(&url, line) {
+            Ok(body) => body,
+            Err(e) => {
+                eprintln!("[sajou] {url}: {e}");
+                let message: Value = serde_json::from_str(line).unwrap_or(Value::Null);
+                unavailable(&message, &e.to_string()).map(|v| v.to_string())
+            }
+        };
+
+        if let Some(out) = out {
+            let mut stdout = stdout.lock();
+            if writeln!(stdout, "{out}")
+                .and_then(|_| stdout.flush())
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_session_header_case_insensitively() {
+        let reply = parse_reply(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nMcp-Session-Id: abc\r\n\r\n{\"ok\":1}",
+        )
+        .unwrap();
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.session.as_deref(), Some("abc"));
+        assert_eq!(reply.body, "{\"ok\":1}");
+    }
+
+    #[test]
+    fn forwards_with_session_and_skips_accepted() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut seen = Vec::new();
+            for (i, stream) in listener.incoming().take(2).enumerate() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !request.ends_with(b"}") {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                seen.push(String::from_utf8_lossy(&request).into_owned());
+                let res = if i == 0 {
+                    "HTTP/1.1 200 OK\r\nMcp-Session-Id: s1\r\nContent-Length: 2\r\n\r\n{}"
+                } else {
+                    "HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n"
+                };
+                stream.write_all(res.as_bytes()).unwrap();
+            }
+            seen
+        });
+
+        let mut proxy = Proxy::default();
+        let init = r#"{"jsonrpc":"2.0","id":1,"method":"initialize"}"#;
+        assert_eq!(proxy.forward(&url, init).unwrap().as_deref(), Some("{}"));
+        let note = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert_eq!(proxy.forward(&url, note).unwrap(), None);
+
+        let seen = server.join().unwrap();
+        assert!(!seen[0].contains("Mcp-Session-Id"));
+        assert!(seen[1].contains("Mcp-Session-Id: s1\r\n"));
+    }
+
+    #[test]
+    fn requests_get_an_error_when_the_app_is_down() {
+        let reply = unavailable(
+            &json!({ "jsonrpc": "2.0", "id": 7, "method": "tools/list" }),
+            "refused",
+        )
+        .unwrap();
+        assert_eq!(reply["id"], 7);
+        assert!(unavailable(
+            &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            "refused"
+        )
+        .is_none());
+    }
+}
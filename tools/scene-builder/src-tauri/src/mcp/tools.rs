@@ -0,0 +1,1564 @@
+//! MCP tool catalog.
+//!
+//! Port of `packages/mcp-server/src/tools/*.ts`. Names, descriptions and
+//! input schemas match the Node server so agents see the same catalog
+//! whichever one they are pointed at. Reads go through
+//! [`StateStore::read`](crate::state::StateStore::read), writes through the
+//! shared mutations so the webview is notified like for any other change.
+
+use serde_json::{json, Map, Value};
+
+use super::ToolContext;
+use crate::signal_parser::{normalize_http_post, record, truthy};
+use crate::state::mutations;
+use crate::state::store::{ChangeOrigin, ServerState};
+
+type Args = Map<String, Value>;
+
+/// A registered tool.
+struct Tool {
+    name: &'static str,
+    description: &'static str,
+    input_schema: fn() -> Value,
+    /// Returns the text content of the result.
+    handler: fn(&ToolContext, &Args) -> String,
+}
+
+const TOOLS: &[Tool] = &[
+    Tool {
+        name: "emit_signal",
+        description: "Emit a signal to the sajou scene. Signals trigger choreographies that animate entities. \
+            Use well-known types (task_dispatch, tool_call, tool_result, agent_state_change, error, completion) \
+            or any custom string.",
+        input_schema: emit_signal_schema,
+        handler: emit_signal,
+    },
+    Tool {
+        name: "get_scene_state",
+        description: "Get the current state of the sajou scene. Returns all placed entities with \
+            their id, semanticId, asset type, position, visibility, and topology. Also \
+            includes scene dimensions, positions, routes, layers, and current editor mode. \
+            Use this to inspect the scene before emitting signals.",
+        input_schema: no_params,
+        handler: get_scene_state,
+    },
+    Tool {
+        name: "get_choreographies",
+        description: "List all choreographies in the current scene. Returns each choreography's \
+            trigger signal type, when-conditions, step count, step types, and wiring \
+            info (which signal sources feed into it). Use this to understand what \
+            animations are available before emitting signals.",
+        input_schema: no_params,
+        handler: get_choreographies,
+    },
+    Tool {
+        name: "list_themes",
+        description: "List available sajou themes. \
+            NOTE: Theme catalog is under development. Use get_scene_state and \
+            get_choreographies to interact with the currently loaded scene instead.",
+        input_schema: no_params,
+        handler: list_themes,
+    },
+    Tool {
+        name: "get_catalog",
+        description: "Get the entity catalog for a theme. \
+            NOTE: Theme catalog is under development. Use get_scene_state to see \
+            entities in the currently loaded scene instead.",
+        input_schema: get_catalog_schema,
+        handler: get_catalog,
+    },
+    Tool {
+        name: "map_signals",
+        description: "Map a signal type to a choreography — when this signal arrives, that choreography plays. \
+            This is a convenience shortcut. Under the hood it creates a wire from signal-type → choreographer zone. \
+            For more complex wiring (e.g. connecting a specific signal source first), use create_wire instead.",
+        input_schema: map_signals_schema,
+        handler: map_signals,
+    },
+    Tool {
+        name: "describe_scene",
+        description: "Get a comprehensive, human-readable description of the current sajou scene. \
+            Returns a structured summary of all placed entities, choreographies, signal \
+            sources, bindings, and wiring. This is the best first tool to call to \
+            understand what the scene contains and how it is configured.",
+        input_schema: no_params,
+        handler: describe_scene,
+    },
+    // --- Write tools ---
+    Tool {
+        name: "place_entity",
+        description: "Place an entity on the scene. Entities are visual objects defined in the theme's catalog. \
+            Use get_catalog to see available entity types for the current theme. \
+            Each placed entity gets a unique instance ID and can optionally have a semanticId \
+            to make it an 'actor' that choreographies can target. \
+            Example: place a 'peon' entity at (200, 300) with semanticId 'agent-1' — \
+            then choreographies can animate 'agent-1' to move, change state, etc.",
+        input_schema: place_entity_schema,
+        handler: place_entity,
+    },
+    Tool {
+        name: "create_choreography",
+        description: "Create a choreography — a sequence of animation steps triggered by a signal. \
+            Choreographies are the core of sajou's animation system. When a signal of the matching type \
+            arrives, the choreography's steps execute in order, animating entities on the scene. \
+            After creating a choreography, wire it to a signal type using create_wire (signal-type → choreographer). \
+            Example: a 'tool_call' signal triggers a choreography that moves an agent entity to a workstation, \
+            plays a working animation, then flashes a result indicator.",
+        input_schema: create_choreography_schema,
+        handler: create_choreography,
+    },
+    Tool {
+        name: "create_binding",
+        description: "Create a binding between a choreography and an entity property. \
+            Bindings connect choreography outputs to entity visual properties (position, rotation, opacity, animation state, etc.). \
+            Example: bind a 'tool_call' choreography's output to agent-1's 'animation.state' property, \
+            so when the choreography runs, the agent switches to a 'working' animation. \
+            The binding specifies which choreography provides the data, which entity receives it, and which property is controlled.",
+        input_schema: create_binding_schema,
+        handler: create_binding,
+    },
+    Tool {
+        name: "create_wire",
+        description: "Create a wire connection in the sajou patch bay. \
+            Wires define data flow through 3 layers: \
+            (1) signal → signal-type: connects a signal source to a signal channel, \
+            (2) signal-type → choreographer: triggers a choreography when that signal type arrives, \
+            (3) choreographer → theme: sends choreography output to the theme renderer. \
+            Example flow: wire 'local:claude-code' (signal source) → 'tool_call' (signal type) → choreography-id (choreographer). \
+            This means: when Claude Code emits a tool_call signal, trigger the choreography.",
+        input_schema: create_wire_schema,
+        handler: create_wire,
+    },
+    Tool {
+        name: "remove_item",
+        description: "Remove an item from the scene. Supports removing entities, choreographies, bindings, wires, and signal sources. \
+            Use get_scene_state, get_choreographies, or describe_scene to find item IDs. \
+            Removing a choreography also cleans up its connected wires. \
+            Removing a signal source also cleans up its wires.",
+        input_schema: remove_item_schema,
+        handler: remove_item,
+    },
+    // --- Shader tools ---
+    Tool {
+        name: "create_shader",
+        description: CREATE_SHADER_DESCRIPTION,
+        input_schema: create_shader_schema,
+        handler: create_shader,
+    },
+    Tool {
+        name: "update_shader",
+        description: "Update an existing GLSL shader. You can change the fragment/vertex source code, \
+            uniforms, name, or pass count. Only provided fields are updated — omitted fields \
+            keep their current values. Use get_shaders first to find the shader ID.",
+        input_schema: update_shader_schema,
+        handler: update_shader,
+    },
+    Tool {
+        name: "get_shaders",
+        description: "List all GLSL shaders in the current scene with full details: source code, \
+            uniforms (names, types, values, ranges, bindings), virtual objects, and pass count. \
+            Use this to inspect existing shaders before modifying them or setting uniforms.",
+        input_schema: no_params,
+        handler: get_shaders,
+    },
+    Tool {
+        name: "set_uniform",
+        description: "Set a uniform value on a GLSL shader in real-time. This is the primary way an AI agent \
+            controls shader visuals — tweak a float slider, change a color, toggle an effect, or move \
+            a 2D position.\n\n\
+            The value type must match the uniform's GLSL type:\n\
+            - float/int → number (e.g. 0.5)\n\
+            - bool → boolean (true/false)\n\
+            - vec2 → [x, y]\n\
+            - vec3 → [r, g, b] or [x, y, z]\n\
+            - vec4 → [r, g, b, a]\n\n\
+            Example: to make a shader pulse faster, set_uniform({ shaderId: '...', uniformName: 'uSpeed', value: 3.0 }).\n\n\
+            Use get_shaders first to discover available uniforms and their value ranges.",
+        input_schema: set_uniform_schema,
+        handler: set_uniform,
+    },
+];
+
+/// `tools/list` entries.
+pub(super) fn list() -> Vec<Value> {
+    TOOLS
+        .iter()
+        .map(|t| {
+            json!({
+                "name": t.name,
+                "description": t.description,
+                "inputSchema": (t.input_schema)(),
+            })
+        })
+        .collect()
+}
+
+/// `tools/call` — `None` if no tool has this name.
+pub(super) fn call(ctx: &ToolContext, name: &str, args: &Value) -> Option<Value> {
+    let tool = TOOLS.iter().find(|t| t.name == name)?;
+    if let Err(e) = validate(&(tool.input_schema)(), args, "") {
+        return Some(json!({
+            "content": [{ "type": "text", "text": format!("Invalid arguments for tool {name}: {e}") }],
+            "isError": true,
+        }));
+    }
+    let args = args.as_object().cloned().unwrap_or_default();
+    let text = (tool.handler)(ctx, &args);
+    Some(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+// ---------------------------------------------------------------------------
+// Schema helpers
+// ---------------------------------------------------------------------------
+
+fn string(description: &str) -> Value {
+    json!({ "type": "string", "description": description })
+}
+
+fn number(description: &str) -> Value {
+    json!({ "type": "number", "description": description })
+}
+
+fn boolean(description: &str) -> Value {
+    json!({ "type": "boolean", "description": description })
+}
+
+fn one_of(values: &[&str], description: &str) -> Value {
+    json!({ "type": "string", "enum": values, "description": description })
+}
+
+/// `z.record(z.unknown())`.
+fn any_object(description: &str) -> Value {
+    json!({ "type": "object", "additionalProperties": {}, "description": description })
+}
+
+/// `z.union([z.number(), z.boolean(), z.array(z.number())])`.
+fn uniform_value(description: &str) -> Value {
+    json!({
+        "anyOf": [
+            { "type": "number" },
+            { "type": "boolean" },
+            { "type": "array", "items": { "type": "number" } },
+        ],
+        "description": description,
+    })
+}
+
+fn object(properties: Value, required: &[&str]) -> Value {
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn no_params() -> Value {
+    object(json!({}), &[])
+}
+
+/// Check `value` against the subset of JSON Schema the catalog uses.
+fn validate(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let at = |msg: String| {
+        if path.is_empty() {
+            msg
+        } else {
+            format!("{path}: {msg}")
+        }
+    };
+
+    if let Some(variants) = schema.get("anyOf").and_then(Value::as_array) {
+        return if variants.iter().any(|s| validate(s, value, path).is_ok()) {
+            Ok(())
+        } else {
+            Err(at("value does not match any allowed type".into()))
+        };
+    }
+
+    let ok = match schema.get("type").and_then(Value::as_str) {
+        Some("string") => value.is_string(),
+        Some("number") => value.is_number(),
+        Some("boolean") => value.is_boolean(),
+        Some("array") => value.is_array(),
+        Some("object") => value.is_object(),
+        _ => true,
+    };
+    if !ok {
+        return Err(at(format!(
+            "expected {}",
+            schema["type"].as_str().unwrap_or("?")
+        )));
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            return Err(at(format!(
+                "expected one of {}",
+                Value::Array(allowed.clone())
+            )));
+        }
+    }
+
+    if let Value::Array(items) = value {
+        let min = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0);
+        if (items.len() as u64) < min {
+            return Err(at(format!("expected at least {min} item(s)")));
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                validate(item_schema, item, &format!("{path}[{i}]"))?;
+            }
+        }
+    }
+
+    if let (Value::Object(map), Some(properties)) = (value, schema.get("properties")) {
+        for key in schema["required"].as_array().into_iter().flatten() {
+            let key = key.as_str().unwrap_or_default();
+            if !map.contains_key(key) {
+                return Err(at(format!("missing required field '{key}'")));
+            }
+        }
+        for (key, v) in map {
+            let field = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            match properties.get(key) {
+                Some(s) => validate(s, v, &field)?,
+                None if schema["additionalProperties"] == false => {
+                    return Err(format!("unexpected field '{field}'"));
+                }
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Shared helpers
+// ---------------------------------------------------------------------------
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Apply a mutation as a backend change.
+fn mutate(ctx: &ToolContext, f: impl FnOnce(&mut ServerState)) {
+    ctx.store.mutate(ChangeOrigin::Mutation, f);
+}
+
+/// `section[key] ?? []`.
+fn items<'a>(section: &'a Map<String, Value>, key: &str) -> &'a [Value] {
+    section
+        .get(key)
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+/// String interpolation of a JS value (`${value}`).
+fn js(value: Option<&Value>) -> String {
+    match value {
+        None => "undefined".into(),
+        Some(Value::Null) => "null".into(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => match n.as_f64() {
+            Some(f) if f.fract() == 0.0 && f.abs() < 1e21 => format!("{}", f as i64),
+            _ => n.to_string(),
+        },
+        Some(Value::Array(a)) => a
+            .iter()
+            .map(|v| {
+                if v.is_null() {
+                    String::new()
+                } else {
+                    js(Some(v))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        Some(v @ Value::Bool(_)) => v.to_string(),
+        Some(Value::Object(_)) => "[object Object]".into(),
+    }
+}
+
+/// `Math.round(value)` as text.
+fn js_round(value: Option<&Value>) -> String {
+    match value.and_then(Value::as_f64) {
+        Some(f) => format!("{}", (f + 0.5).floor() as i64),
+        None => "NaN".into(),
+    }
+}
+
+/// Wiring info for a choreography: signal types wired into it, and the
+/// sources feeding those signal types.
+fn choreography_wiring(id: Option<&Value>, wires: &[Value]) -> (Vec<Value>, Vec<Value>) {
+    let signal_types: Vec<Value> = wires
+        .iter()
+        .filter(|w| w["toZone"] == "choreographer" && w.get("toId") == id)
+        .filter(|w| w["fromZone"] == "signal-type")
+        .map(|w| w["fromId"].clone())
+        .collect();
+
+    let mut sources = Vec::new();
+    for signal_type in &signal_types {
+        for sw in wires.iter().filter(|w| {
+            w["fromZone"] == "signal" && w["toZone"] == "signal-type" && &w["toId"] == signal_type
+        }) {
+            sources.push(json!({ "sourceId": sw["fromId"], "signalType": signal_type }));
+        }
+    }
+    (signal_types, sources)
+}
+
+/// Step action names of a choreography.
+fn step_types(choreography: &Value) -> Vec<Value> {
+    choreography["steps"]
+        .as_array()
+        .map(|steps| steps.iter().map(|s| s["action"].clone()).collect())
+        .unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// Read tools
+// ---------------------------------------------------------------------------
+
+fn emit_signal_schema() -> Value {
+    object(
+        json!({
+            "type": string("Signal type. Well-known: task_dispatch, tool_call, tool_result, token_usage, agent_state_change, error, completion, text_delta, thinking. Custom types are also accepted."),
+            "from": string("Entity ID of the signal sender (e.g. 'orchestrator', 'agent-1')."),
+            "to": string("Entity ID of the signal receiver, if applicable."),
+            "payload": any_object("Additional payload data for the signal."),
+        }),
+        &["type", "from"],
+    )
+}
+
+fn emit_signal(ctx: &ToolContext, args: &Args) -> String {
+    let mut payload = args
+        .get("payload")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    // Only a `task_dispatch` payload names its sender and receiver; for
+    // other types they go in `metadata`, which the schema leaves open.
+    let mut metadata = Map::new();
+    for key in ["from", "to"] {
+        if truthy(args.get(key)) {
+            let fields = if args["type"] == "task_dispatch" {
+                &mut payload
+            } else {
+                &mut metadata
+            };
+            fields.insert(key.into(), args[key].clone());
+        }
+    }
+
+    let mut envelope = normalize_http_post(json!({
+        "type": args["type"],
+        "source": "mcp",
+        "payload": payload,
+    }));
+    if !metadata.is_empty() {
+        envelope["metadata"] = Value::Object(metadata);
+    }
+    let id = envelope["id"].clone();
+    match (ctx.emit)(envelope) {
+        Ok(clients) => json!({ "signal_id": id, "ok": true, "clients": clients }),
+        Err(error) => json!({ "signal_id": id, "ok": false, "error": error }),
+    }
+    .to_string()
+}
+
+fn get_scene_state(ctx: &ToolContext, _: &Args) -> String {
+    ctx.store.read(|s| {
+        let scene = &s.scene;
+        let entities = items(scene, "entities");
+        let positions = items(scene, "positions");
+        let routes = items(scene, "routes");
+        Value::Object(record([
+            ("ok", Some(true.into())),
+            ("dimensions", scene.get("dimensions").cloned()),
+            ("background", scene.get("background").cloned()),
+            ("layers", scene.get("layers").cloned()),
+            ("entities", Some(entities.into())),
+            ("positions", Some(positions.into())),
+            ("routes", Some(routes.into())),
+            ("zoneTypes", scene.get("zoneTypes").cloned()),
+            (
+                "mode",
+                Some(s.editor.get("mode").cloned().unwrap_or(Value::Null)),
+            ),
+            (
+                "viewMode",
+                Some(s.editor.get("viewMode").cloned().unwrap_or(Value::Null)),
+            ),
+            ("entityCount", Some(entities.len().into())),
+            ("positionCount", Some(positions.len().into())),
+            ("routeCount", Some(routes.len().into())),
+        ]))
+        .to_string()
+    })
+}
+
+fn get_choreographies(ctx: &ToolContext, _: &Args) -> String {
+    ctx.store.read(|s| {
+        let wires = items(&s.wiring, "wires");
+        let choreographies: Vec<Value> = items(&s.choreographies, "choreographies")
+            .iter()
+            .map(|c| {
+                let (wired, sources) = choreography_wiring(c.get("id"), wires);
+                let or_null = |key: &str| c.get(key).cloned().unwrap_or(Value::Null);
+                let steps = step_types(c);
+                Value::Object(record([
+                    ("id", c.get("id").cloned()),
+                    ("on", c.get("on").cloned()),
+                    ("when", Some(or_null("when"))),
+                    (
+                        "interrupts",
+                        Some(c.get("interrupts").cloned().unwrap_or(false.into())),
+                    ),
+                    (
+                        "defaultTargetEntityId",
+                        Some(or_null("defaultTargetEntityId")),
+                    ),
+                    ("stepCount", Some(steps.len().into())),
+                    ("stepTypes", Some(steps.into())),
+                    ("wiredSignalTypes", Some(wired.into())),
+                    ("sources", Some(sources.into())),
+                ]))
+            })
+            .collect();
+        let count = choreographies.len();
+        json!({ "ok": true, "choreographies": choreographies, "count": count }).to_string()
+    })
+}
+
+fn list_themes(_: &ToolContext, _: &Args) -> String {
+    json!({
+        "status": "not_yet_available",
+        "message": "Theme catalog is under development. Use get_scene_state and \
+            get_choreographies to interact with the currently loaded scene.",
+        "themes": [],
+    })
+    .to_string()
+}
+
+fn get_catalog_schema() -> Value {
+    object(
+        json!({ "theme": string("Theme ID to get the catalog for.") }),
+        &["theme"],
+    )
+}
+
+fn get_catalog(_: &ToolContext, args: &Args) -> String {
+    json!({
+        "status": "not_yet_available",
+        "theme": args["theme"],
+        "message": "Theme catalog is under development. Use get_scene_state to see \
+            entities in the currently loaded scene.",
+        "catalog": {},
+    })
+    .to_string()
+}
+
+fn map_signals_schema() -> Value {
+    object(
+        json!({
+            "signal_type": string("The signal type to listen for (e.g. 'task_dispatch', 'tool_call', 'error')."),
+            "choreography_id": string("The choreography ID to trigger when the signal arrives."),
+        }),
+        &["signal_type", "choreography_id"],
+    )
+}
+
+fn map_signals(ctx: &ToolContext, args: &Args) -> String {
+    let wire = record([
+        ("fromZone", Some("signal-type".into())),
+        ("fromId", Some(args["signal_type"].clone())),
+        ("toZone", Some("choreographer".into())),
+        ("toId", Some(args["choreography_id"].clone())),
+    ]);
+    mutate(ctx, |s| mutations::add_wire(s, &wire));
+    json!({
+        "ok": true,
+        "message": format!(
+            "Mapped signal \"{}\" → choreography \"{}\"",
+            js(args.get("signal_type")),
+            js(args.get("choreography_id"))
+        ),
+    })
+    .to_string()
+}
+
+fn describe_entity(e: &Value) -> String {
+    let label = if truthy(e.get("semanticId")) {
+        format!(
+            "\"{}\" ({})",
+            js(e.get("semanticId")),
+            js(e.get("entityId"))
+        )
+    } else {
+        js(e.get("entityId"))
+    };
+    let pos = format!("at ({}, {})", js_round(e.get("x")), js_round(e.get("y")));
+    let vis = if truthy(e.get("visible")) {
+        ""
+    } else {
+        " [hidden]"
+    };
+    let lock = if truthy(e.get("locked")) {
+        " [locked]"
+    } else {
+        ""
+    };
+    let topo = match e.get("topology").filter(|t| truthy(Some(t))) {
+        Some(t) => format!(
+            ", topology: home={}, waypoints=[{}]",
+            t.get("home")
+                .filter(|h| !h.is_null())
+                .map_or("none".into(), |h| js(Some(h))),
+            t["waypoints"]
+                .as_array()
+                .map(|w| w.iter().map(|p| js(Some(p))).collect::<Vec<_>>().join(", "))
+                .unwrap_or_default()
+        ),
+        None => String::new(),
+    };
+    format!(
+        "  - {label} {pos}{vis}{lock}, layer={}, state={}{topo}",
+        js(e.get("layerId")),
+        js(e.get("activeState"))
+    )
+}
+
+fn describe_choreography(c: &Value, wires: &[Value]) -> String {
+    let (wired, sources) = choreography_wiring(c.get("id"), wires);
+    let trigger = if wired.is_empty() {
+        format!("on: \"{}\"", js(c.get("on")))
+    } else {
+        format!("wired to: [{}]", join(&wired, ", "))
+    };
+    let when = if truthy(c.get("when")) {
+        format!(", when: {}", c["when"])
+    } else {
+        String::new()
+    };
+    let target = if truthy(c.get("defaultTargetEntityId")) {
+        format!(
+            ", default target: \"{}\"",
+            js(c.get("defaultTargetEntityId"))
+        )
+    } else {
+        String::new()
+    };
+    let step_types = step_types(c);
+    let steps = if step_types.is_empty() {
+        ", no steps".to_string()
+    } else {
+        format!(
+            ", {} steps: [{}]",
+            step_types.len(),
+            join(&step_types, " → ")
+        )
+    };
+    let sources = if sources.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "\n    sources: {}",
+            sources
+                .iter()
+                .map(|s| format!(
+                    "{} via \"{}\"",
+                    js(s.get("sourceId")),
+                    js(s.get("signalType"))
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    format!(
+        "  - {}: {trigger}{when}{target}{steps}{sources}",
+        js(c.get("id"))
+    )
+}
+
+fn describe_binding(b: &Value) -> String {
+    let json_field = |key: &str, label: &str| {
+        if truthy(b.get(key)) {
+            format!(", {label}: {}", b[key])
+        } else {
+            String::new()
+        }
+    };
+    let field = if truthy(b.get("sourceField")) {
+        format!(", field: \"{}\"", js(b.get("sourceField")))
+    } else {
+        String::new()
+    };
+    format!(
+        "  - \"{}\".{} ← choreo \"{}\" ({}){field}{}{}{}",
+        js(b.get("targetEntityId")),
+        js(b.get("property")),
+        js(b.get("sourceChoreographyId")),
+        js(b.get("sourceType")),
+        json_field("mapping", "mapping"),
+        json_field("transition", "transition"),
+        json_field("action", "action"),
+    )
+}
+
+fn describe_source(s: &Value) -> String {
+    let status = js(s.get("status")).to_uppercase();
+    let err = if truthy(s.get("error")) {
+        format!(" (error: {})", js(s.get("error")))
+    } else {
+        String::new()
+    };
+    format!(
+        "  - {} [{}] {status}{err} — {}",
+        js(s.get("name")),
+        js(s.get("protocol")),
+        js(s.get("category"))
+    )
+}
+
+fn describe_wire(w: &Value) -> String {
+    format!(
+        "  - {}:{} → {}:{}",
+        js(w.get("fromZone")),
+        js(w.get("fromId")),
+        js(w.get("toZone")),
+        js(w.get("toId"))
+    )
+}
+
+fn join(values: &[Value], sep: &str) -> String {
+    values
+        .iter()
+        .map(|v| js(Some(v)))
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+/// Append a `## Title (n)` section, or `## Title: <empty>` if there are no items.
+fn section(
+    lines: &mut Vec<String>,
+    title: &str,
+    count_label: &str,
+    empty: &str,
+    items: &[Value],
+    describe: impl Fn(&Value) -> String,
+) {
+    if items.is_empty() {
+        lines.push(format!("## {title}: {empty}"));
+    } else {
+        lines.push(format!("## {title} ({}{count_label})", items.len()));
+        lines.extend(items.iter().map(describe));
+    }
+}
+
+fn describe_scene(ctx: &ToolContext, _: &Args) -> String {
+    ctx.store.read(|s| {
+        let scene = &s.scene;
+        let positions = items(scene, "positions");
+        let routes = items(scene, "routes");
+        let wires = items(&s.wiring, "wires");
+        let d = scene.get("dimensions").filter(|d| !d.is_null());
+        let dim = |key: &str| {
+            d.and_then(|d| d.get(key))
+                .filter(|v| !v.is_null())
+                .map_or("?".into(), |v| js(Some(v)))
+        };
+        let editor = |key: &str| {
+            s.editor
+                .get(key)
+                .filter(|v| !v.is_null())
+                .map_or("unknown".into(), |v| js(Some(v)))
+        };
+
+        let mut lines = vec!["# Scene Description".to_string(), String::new()];
+        lines.push(format!(
+            "## Canvas: {}×{} px, mode: {}, view: {}",
+            dim("width"),
+            dim("height"),
+            editor("mode"),
+            editor("viewMode")
+        ));
+        lines.push(String::new());
+
+        section(
+            &mut lines,
+            "Entities",
+            "",
+            "none placed",
+            items(scene, "entities"),
+            describe_entity,
+        );
+        lines.push(String::new());
+
+        if !positions.is_empty() {
+            lines.push(format!("## Positions ({})", positions.len()));
+            for p in positions {
+                let binding = if truthy(p.get("entityBinding")) {
+                    format!(", bound to \"{}\"", js(p.get("entityBinding")))
+                } else {
+                    String::new()
+                };
+                lines.push(format!(
+                    "  - \"{}\" at ({}, {}), type={}{binding}",
+                    js(p.get("name")),
+                    js_round(p.get("x")),
+                    js_round(p.get("y")),
+                    js(p.get("typeHint"))
+                ));
+            }
+            lines.push(String::new());
+        }
+
+        if !routes.is_empty() {
+            lines.push(format!("## Routes ({})", routes.len()));
+            for r in routes {
+                lines.push(format!(
+                    "  - \"{}\" ({} points, {}, {})",
+                    js(r.get("name")),
+                    r["points"].as_array().map_or(0, Vec::len),
+                    js(r.get("style")),
+                    if truthy(r.get("bidirectional")) {
+                        "bidirectional"
+                    } else {
+                        "one-way"
+                    }
+                ));
+            }
+            lines.push(String::new());
+        }
+
+        section(
+            &mut lines,
+            "Choreographies",
+            "",
+            "none defined",
+            items(&s.choreographies, "choreographies"),
+            |c| describe_choreography(c, wires),
+        );
+        lines.push(String::new());
+
+        section(
+            &mut lines,
+            "Signal Sources",
+            "",
+            "none configured",
+            items(&s.signal_sources, "sources"),
+            describe_source,
+        );
+        lines.push(String::new());
+
+        section(
+            &mut lines,
+            "Bindings",
+            "",
+            "none",
+            items(&s.bindings, "bindings"),
+            describe_binding,
+        );
+        lines.push(String::new());
+
+        section(
+            &mut lines,
+            "Wiring",
+            " connections",
+            "no connections",
+            wires,
+            describe_wire,
+        );
+
+        lines.join("\n")
+    })
+}
+
+fn get_shaders(ctx: &ToolContext, _: &Args) -> String {
+    ctx.store.read(|s| {
+        let shaders: Vec<Value> = items(&s.shaders, "shaders")
+            .iter()
+            .map(|sh| {
+                Value::Object(record([
+                    ("id", sh.get("id").cloned()),
+                    ("name", sh.get("name").cloned()),
+                    ("mode", sh.get("mode").cloned()),
+                    ("passes", sh.get("passes").cloned()),
+                    ("fragmentSource", sh.get("fragmentSource").cloned()),
+                    ("vertexSource", sh.get("vertexSource").cloned()),
+                    ("uniforms", sh.get("uniforms").cloned()),
+                    ("objects", sh.get("objects").cloned()),
+                ]))
+            })
+            .collect();
+        json!({ "ok": true, "shaderCount": shaders.len(), "shaders": shaders }).to_string()
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Write tools
+// ---------------------------------------------------------------------------
+
+fn place_entity_schema() -> Value {
+    object(
+        json!({
+            "entityId": string("The entity type ID from the theme catalog (e.g. 'peon', 'tree', 'building-townhall'). \
+                Use get_catalog to list available entity types."),
+            "x": number("X position on the scene (pixels from left). Scene is typically 960px wide."),
+            "y": number("Y position on the scene (pixels from top). Scene is typically 640px tall."),
+            "semanticId": string("Optional actor name for this entity (e.g. 'agent-1', 'door-kitchen', 'indicator-status'). \
+                When set, choreographies can target this entity by name. \
+                Must be unique across all placed entities. \
+                Omit for passive decoration entities."),
+            "layerId": string("Scene layer: 'background' (behind), 'midground' (default), 'foreground' (in front). \
+                Defaults to 'midground'."),
+            "scale": number("Uniform scale factor. 1 = normal size, 0.5 = half, 2 = double. Defaults to 1."),
+            "rotation": number("Rotation in degrees. Defaults to 0."),
+            "zIndex": number("Z-order within the layer. Higher values render on top. Defaults to 0."),
+            "activeState": string("Initial animation state (e.g. 'idle', 'walk', 'attack'). Defaults to 'idle'."),
+        }),
+        &["entityId", "x", "y"],
+    )
+}
+
+fn place_entity(ctx: &ToolContext, args: &Args) -> String {
+    let instance_id = new_id();
+    let field = |key: &str| args.get(key).cloned();
+    let data = record([
+        ("id", Some(instance_id.clone().into())),
+        ("entityId", field("entityId")),
+        ("x", field("x")),
+        ("y", field("y")),
+        ("semanticId", field("semanticId")),
+        ("layerId", field("layerId")),
+        ("scale", field("scale")),
+        ("rotation", field("rotation")),
+        ("zIndex", field("zIndex")),
+        ("activeState", field("activeState")),
+    ]);
+    mutate(ctx, |s| mutations::add_entity(s, &data));
+
+    let hint = args
+        .get("semanticId")
+        .filter(|v| truthy(Some(v)))
+        .map(|id| {
+            Value::from(format!(
+            "Entity placed with semanticId '{}'. Use this name in choreography steps and bindings.",
+            js(Some(id))
+        ))
+        });
+    Value::Object(record([
+        ("ok", Some(true.into())),
+        ("instanceId", Some(instance_id.into())),
+        ("hint", hint),
+    ]))
+    .to_string()
+}
+
+fn create_choreography_schema() -> Value {
+    let step = object(
+        json!({
+            "action": one_of(
+                &["move", "fly", "flash", "spawn", "destroy", "wait", "playSound", "setAnimation", "parallel", "onArrive", "onInterrupt"],
+                "The animation action to perform. \
+                'move' — animate entity along a path to target position. \
+                'fly' — instant arc movement to target. \
+                'flash' — brief visual highlight on the entity. \
+                'spawn' — create a new entity instance at a position. \
+                'destroy' — remove an entity from the scene. \
+                'wait' — pause the sequence for a duration. \
+                'playSound' — trigger an audio cue. \
+                'setAnimation' — change the entity's animation state (e.g. 'idle' → 'walk'). \
+                'parallel' — run child steps simultaneously. \
+                'onArrive' — execute child steps when entity reaches destination. \
+                'onInterrupt' — execute child steps if choreography is interrupted.",
+            ),
+            "entity": string("Target entity semanticId (e.g. 'agent-1', 'door-kitchen'). \
+                Can use signal references like 'signal.payload.from' to dynamically resolve. \
+                If omitted, uses the choreography's defaultTargetEntityId."),
+            "target": string("Target position or waypoint name for movement actions (e.g. 'workstation-1', 'patrol-route')."),
+            "delay": number("Delay in milliseconds before this step starts."),
+            "duration": number("Duration in milliseconds for timed actions (move, wait)."),
+            "easing": string("Easing function: 'linear', 'easeIn', 'easeOut', 'easeInOut', 'arc'."),
+            "params": any_object("Additional parameters specific to the action type. \
+                For 'flash': { color: '#ff0', intensity: 0.8 }. \
+                For 'setAnimation': { state: 'walk' }. \
+                For 'spawn': { entityId: 'arrow', x: 100, y: 200 }. \
+                For 'playSound': { sound: 'click' }."),
+        }),
+        &["action"],
+    );
+    object(
+        json!({
+            "on": string("Signal type that triggers this choreography (e.g. 'tool_call', 'agent_state_change', 'task_dispatch'). \
+                This is the default trigger — the actual wiring is done via create_wire."),
+            "steps": {
+                "type": "array",
+                "items": step,
+                "minItems": 1,
+                "description": "Ordered list of animation steps to execute when triggered.",
+            },
+            "defaultTargetEntityId": string("Default entity semanticId for steps that don't specify their own entity. \
+                Useful when most steps target the same actor."),
+            "when": any_object("Optional payload filter — choreography only triggers when signal payload matches. \
+                Example: { field: 'toolName', operator: 'eq', value: 'Read' } triggers only for Read tool calls."),
+            "interrupts": boolean("If true, this choreography can interrupt a running one on the same entity. Default: false."),
+        }),
+        &["on", "steps"],
+    )
+}
+
+fn create_choreography(ctx: &ToolContext, args: &Args) -> String {
+    let choreography_id = new_id();
+    let steps: Vec<Value> = args["steps"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|s| {
+            let field = |key: &str| s.get(key).cloned();
+            Value::Object(record([
+                ("action", field("action")),
+                ("entity", field("entity")),
+                ("target", field("target")),
+                ("delay", field("delay")),
+                ("duration", field("duration")),
+                ("easing", field("easing")),
+                ("params", Some(field("params").unwrap_or(json!({})))),
+            ]))
+        })
+        .collect();
+    let data = record([
+        ("id", Some(choreography_id.clone().into())),
+        ("on", args.get("on").cloned()),
+        ("steps", Some(steps.into())),
+        (
+            "defaultTargetEntityId",
+            args.get("defaultTargetEntityId").cloned(),
+        ),
+        ("when", args.get("when").cloned()),
+        (
+            "interrupts",
+            Some(args.get("interrupts").cloned().unwrap_or(false.into())),
+        ),
+    ]);
+    mutate(ctx, |s| mutations::add_choreography(s, &data));
+
+    json!({
+        "ok": true,
+        "choreographyId": choreography_id,
+        "hint": format!(
+            "Choreography created. Wire it to a signal type with create_wire: \
+             {{ fromZone: 'signal-type', fromId: '{}', toZone: 'choreographer', toId: '{choreography_id}' }}",
+            js(args.get("on"))
+        ),
+    })
+    .to_string()
+}
+
+fn create_binding_schema() -> Value {
+    object(
+        json!({
+            "targetEntityId": string("The semanticId of the target entity (e.g. 'agent-1', 'door-kitchen'). \
+                This must match a placed entity's semanticId in the scene."),
+            "property": string("The entity property to bind to. Available properties: \
+                'position' (point2D), 'position.x'/'position.y' (float), \
+                'rotation' (float, degrees), 'scale'/'scale.x'/'scale.y' (float), \
+                'opacity' (float, 0-1), 'visible' (bool), 'tint' (color), \
+                'animation.state' (enum — switches animation), 'animation.speed' (float), \
+                'zIndex' (int), \
+                'moveTo:waypoint' (event — move entity to a waypoint), \
+                'followRoute' (event — entity follows a route), \
+                'teleportTo' (event — instant move)."),
+            "sourceChoreographyId": string("The ID of the choreography that provides the data for this binding."),
+            "sourceType": one_of(
+                &["float", "point2D", "bool", "enum", "event", "color", "int"],
+                "The type of data the choreography outputs. Defaults to 'event'. \
+                Must be compatible with the target property's accepted types.",
+            ),
+            "sourceField": string("Specific field to extract from the signal payload (e.g. 'velocity', 'value'). \
+                If omitted, auto-detected from context."),
+            "mapping": any_object("Optional value mapping function. Example: { fn: 'linear', inputRange: [0, 100], outputRange: [0, 1] }."),
+            "action": any_object("Optional action config for event→action bindings. \
+                Example for moveTo: { waypoint: 'workstation-1', animationDuring: 'walk', animationOnArrival: 'idle', duration: 1000 }."),
+            "transition": any_object("Optional transition config for smooth property changes. \
+                Example: { targetValue: 0.5, durationMs: 300, easing: 'easeOut' }."),
+        }),
+        &["targetEntityId", "property", "sourceChoreographyId"],
+    )
+}
+
+fn create_binding(ctx: &ToolContext, args: &Args) -> String {
+    let field = |key: &str| args.get(key).cloned();
+    let data = record([
+        ("targetEntityId", field("targetEntityId")),
+        ("property", field("property")),
+        ("sourceChoreographyId", field("sourceChoreographyId")),
+        (
+            "sourceType",
+            Some(field("sourceType").unwrap_or("event".into())),
+        ),
+        ("sourceField", field("sourceField")),
+        ("mapping", field("mapping")),
+        ("action", field("action")),
+        ("transition", field("transition")),
+    ]);
+    mutate(ctx, |s| mutations::add_binding(s, &data));
+    json!({ "ok": true }).to_string()
+}
+
+fn create_wire_schema() -> Value {
+    object(
+        json!({
+            "fromZone": one_of(
+                &["signal", "signal-type", "choreographer"],
+                "Source zone. \
+                'signal' — a signal source (e.g. 'local:claude-code', a WebSocket source ID). \
+                'signal-type' — a signal type channel (e.g. 'tool_call', 'agent_state_change'). \
+                'choreographer' — a choreography node (use its ID).",
+            ),
+            "fromId": string("ID of the source endpoint. \
+                For 'signal' zone: the signal source ID (e.g. 'local:claude-code'). \
+                For 'signal-type' zone: the signal type name (e.g. 'tool_call'). \
+                For 'choreographer' zone: the choreography ID."),
+            "toZone": one_of(
+                &["signal-type", "choreographer", "theme", "shader"],
+                "Destination zone. \
+                'signal-type' — route a source to a signal type channel. \
+                'choreographer' — trigger a choreography from a signal type. \
+                'theme' — send choreography output to the theme renderer. \
+                'shader' — connect to a shader uniform (format: '{shaderId}:{uniformName}').",
+            ),
+            "toId": string("ID of the destination endpoint. \
+                For 'signal-type' zone: the signal type name. \
+                For 'choreographer' zone: the choreography ID. \
+                For 'theme' zone: the theme slot name. \
+                For 'shader' zone: '{shaderId}:{uniformName}'."),
+        }),
+        &["fromZone", "fromId", "toZone", "toId"],
+    )
+}
+
+fn create_wire(ctx: &ToolContext, args: &Args) -> String {
+    let from = args["fromZone"].as_str().unwrap_or_default();
+    let to = args["toZone"].as_str().unwrap_or_default();
+    let allowed: &[&str] = match from {
+        "signal" => &["signal-type"],
+        "signal-type" => &["choreographer"],
+        "choreographer" => &["theme", "shader"],
+        _ => &[],
+    };
+    if !allowed.contains(&to) {
+        return json!({
+            "ok": false,
+            "error": format!(
+                "Invalid wire direction: {from} → {to}. \
+                 Valid flows: signal → signal-type, signal-type → choreographer, choreographer → theme/shader."
+            ),
+        })
+        .to_string();
+    }
+
+    let data = record([
+        ("fromZone", args.get("fromZone").cloned()),
+        ("fromId", args.get("fromId").cloned()),
+        ("toZone", args.get("toZone").cloned()),
+        ("toId", args.get("toId").cloned()),
+    ]);
+    mutate(ctx, |s| mutations::add_wire(s, &data));
+    json!({ "ok": true }).to_string()
+}
+
+fn remove_item_schema() -> Value {
+    object(
+        json!({
+            "type": one_of(
+                &["entity", "choreography", "binding", "wire", "source"],
+                "What type of item to remove. \
+                'entity' — a placed entity instance (use the instance ID, not the entity type). \
+                'choreography' — a choreography definition (also removes connected wires). \
+                'binding' — an entity binding. \
+                'wire' — a wire connection. \
+                'source' — a signal source (local sources cannot be removed).",
+            ),
+            "id": string("The ID of the item to remove."),
+        }),
+        &["type", "id"],
+    )
+}
+
+fn remove_item(ctx: &ToolContext, args: &Args) -> String {
+    let id = args.get("id");
+    mutate(ctx, |s| match args["type"].as_str() {
+        Some("entity") => mutations::remove_entity(s, id),
+        Some("choreography") => mutations::remove_choreography(s, id),
+        Some("binding") => mutations::remove_binding(s, id),
+        Some("wire") => mutations::remove_wire(s, id),
+        Some("source") => mutations::remove_signal_source(s, id),
+        _ => {}
+    });
+    json!({ "ok": true }).to_string()
+}
+
+// ---------------------------------------------------------------------------
+// Shader tools
+// ---------------------------------------------------------------------------
+
+const CREATE_SHADER_DESCRIPTION: &str = "Create a GLSL shader — a visual layer that renders custom fragment/vertex shaders on the sajou scene. \
+Shaders are the most expressive visual primitive: they run per-pixel GPU code every frame.\n\n\
+**Uniforms** are the knobs the choreographer can tween. Declare them in the GLSL source and in the \
+`uniforms` array so the UI and choreographer know about them. Each uniform has a control type \
+(slider, color, toggle, xy) and value range.\n\n\
+**@object grouping**: Use the `objects` array to declare virtual objects (e.g. 'sphere', 'camera'). \
+Then set `objectId` on uniforms to group them under that object in the UI. This is purely organizational.\n\n\
+**@bind semantic**: Set `bind: { semantic: 'intensity' }` on a uniform to connect it to choreographer signals. \
+The choreographer can then tween that uniform in response to agent events.\n\n\
+**Multi-pass**: Set `passes: 2` (or more) for ping-pong feedback effects (e.g. reaction-diffusion, fluid sim). \
+The previous frame is available as `iChannel0`.\n\n\
+**Auto-injected uniforms** (do NOT declare these — they are always available):\n\
+- `iTime` (float) — elapsed time in seconds\n\
+- `iTimeDelta` (float) — time since last frame\n\
+- `iResolution` (vec3) — canvas width, height, pixel ratio\n\
+- `iMouse` (vec4) — mouse position\n\
+- `iFrame` (int) — frame counter\n\
+- `iChannel0` (sampler2D) — previous frame (multi-pass only)\n\n\
+**Minimal example** — animated gradient:\n\
+```glsl\n\
+#version 300 es\n\
+precision highp float;\n\
+in vec2 vUv;\n\
+out vec4 fragColor;\n\
+uniform float uSpeed; // @ui: slider\n\
+void main() {\n\
+\x20 vec3 col = 0.5 + 0.5 * cos(iTime * uSpeed + vUv.xyx + vec3(0,2,4));\n\
+\x20 fragColor = vec4(col, 1.0);\n\
+}\n\
+```\n\n\
+After creating a shader, use `set_uniform` to tweak parameters in real-time, \
+or wire it to the choreographer via `create_wire` (choreographer → shader).";
+
+/// Default passthrough vertex shader.
+const DEFAULT_VERTEX: &str = "#version 300 es
+precision highp float;
+
+in vec3 position;
+in vec2 uv;
+
+out vec2 vUv;
+
+void main() {
+  vUv = uv;
+  gl_Position = vec4(position, 1.0);
+}
+";
+
+fn uniform_schema(detailed: bool) -> Value {
+    let d = |long: &'static str, short: &'static str| if detailed { long } else { short };
+    object(
+        json!({
+            "name": string(d("Uniform name as declared in GLSL (e.g. 'uSpeed', 'uColor').", "Uniform name as declared in GLSL.")),
+            "type": one_of(
+                &["float", "int", "bool", "vec2", "vec3", "vec4"],
+                d("GLSL type of the uniform.", "GLSL type."),
+            ),
+            "control": one_of(
+                &["slider", "color", "toggle", "xy"],
+                d(
+                    "UI control widget. 'slider' for numeric, 'color' for vec3 RGB, \
+                     'toggle' for bool, 'xy' for vec2 position.",
+                    "UI control widget.",
+                ),
+            ),
+            "value": uniform_value(d(
+                "Initial value. number for float/int, boolean for bool, number[] for vecN.",
+                "Current value.",
+            )),
+            "min": number(d("Minimum value for slider controls. Default: 0.", "Minimum for slider. Default: 0.")),
+            "max": number(d("Maximum value for slider controls. Default: 1.", "Maximum for slider. Default: 1.")),
+            "step": number(d("Step increment for slider controls. Default: 0.01.", "Step for slider. Default: 0.01.")),
+            "objectId": string(d(
+                "Virtual object ID this uniform belongs to (matches an entry in the objects array).",
+                "Virtual object ID for grouping.",
+            )),
+            "bind": {
+                "type": "object",
+                "properties": {
+                    "semantic": if detailed {
+                        string("Semantic role: 'intensity', 'position', 'scale', 'rotation', etc.")
+                    } else {
+                        json!({ "type": "string" })
+                    },
+                },
+                "required": ["semantic"],
+                "additionalProperties": false,
+                "description": d(
+                    "Choreographer binding hint — connects this uniform to a signal semantic.",
+                    "Choreographer binding hint.",
+                ),
+            },
+        }),
+        &["name", "type", "control", "value"],
+    )
+}
+
+fn object_def_schema(detailed: bool) -> Value {
+    object(
+        json!({
+            "id": string(if detailed { "Object identifier (e.g. 'sphere', 'camera')." } else { "Object identifier." }),
+            "label": string(if detailed { "Display label in the UI panel." } else { "Display label." }),
+        }),
+        &["id", "label"],
+    )
+}
+
+fn array_of(items: Value, description: &str) -> Value {
+    json!({ "type": "array", "items": items, "description": description })
+}
+
+/// Normalise uniforms the way the create/update tools send them to mutations.
+fn uniforms_payload(uniforms: &Value) -> Value {
+    uniforms
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|u| {
+            let field = |key: &str| u.get(key).cloned();
+            Value::Object(record([
+                ("name", field("name")),
+                ("type", field("type")),
+                ("control", field("control")),
+                ("value", field("value")),
+                ("defaultValue", field("value")),
+                ("min", Some(field("min").unwrap_or(0.into()))),
+                ("max", Some(field("max").unwrap_or(1.into()))),
+                ("step", Some(field("step").unwrap_or(0.01.into()))),
+                ("objectId", field("objectId")),
+                ("bind", field("bind")),
+            ]))
+        })
+        .collect()
+}
+
+fn create_shader_schema() -> Value {
+    object(
+        json!({
+            "name": string("Display name for the shader (e.g. 'Plasma Background', 'Agent Glow')."),
+            "fragmentSource": string("GLSL fragment shader source code (ES 3.0). Must include #version 300 es, \
+                precision qualifier, and output to fragColor. Auto-injected uniforms (iTime, etc.) \
+                are available without declaring them."),
+            "vertexSource": string("GLSL vertex shader source. If omitted, a default passthrough vertex shader is used \
+                that passes UVs to the fragment shader via vUv."),
+            "uniforms": array_of(uniform_schema(true), "User-defined uniforms exposed in the editor and available to the choreographer."),
+            "objects": array_of(object_def_schema(true), "Virtual objects for grouping related uniforms in the UI."),
+            "passes": number("Number of render passes. 1 = single-pass (default), 2+ = ping-pong feedback."),
+        }),
+        &["name", "fragmentSource"],
+    )
+}
+
+fn create_shader(ctx: &ToolContext, args: &Args) -> String {
+    let shader_id = new_id();
+    let data = record([
+        ("id", Some(shader_id.clone().into())),
+        ("name", args.get("name").cloned()),
+        ("fragmentSource", args.get("fragmentSource").cloned()),
+        (
+            "vertexSource",
+            Some(
+                args.get("vertexSource")
+                    .cloned()
+                    .unwrap_or(DEFAULT_VERTEX.into()),
+            ),
+        ),
+        (
+            "uniforms",
+            Some(uniforms_payload(args.get("uniforms").unwrap_or(&json!([])))),
+        ),
+        (
+            "objects",
+            Some(args.get("objects").cloned().unwrap_or(json!([]))),
+        ),
+        (
+            "passes",
+            Some(args.get("passes").cloned().unwrap_or(1.into())),
+        ),
+    ]);
+    mutate(ctx, |s| mutations::add_shader(s, &data));
+
+    json!({
+        "ok": true,
+        "shaderId": shader_id,
+        "hint": format!(
+            "Shader '{}' created with ID {shader_id}. \
+             Use set_uniform to tweak parameters, or create_wire to connect it to the choreographer.",
+            js(args.get("name"))
+        ),
+    })
+    .to_string()
+}
+
+fn update_shader_schema() -> Value {
+    object(
+        json!({
+            "shaderId": string("ID of the shader to update (from create_shader or get_shaders)."),
+            "name": string("New display name."),
+            "fragmentSource": string("New fragment shader GLSL source."),
+            "vertexSource": string("New vertex shader GLSL source."),
+            "uniforms": array_of(uniform_schema(false), "Replace the full uniforms list."),
+            "objects": array_of(object_def_schema(false), "Replace the full objects list."),
+            "passes": number("New pass count."),
+        }),
+        &["shaderId"],
+    )
+}
+
+fn update_shader(ctx: &ToolContext, args: &Args) -> String {
+    let mut data = record([
+        ("id", args.get("shaderId").cloned()),
+        ("name", args.get("name").cloned()),
+        ("fragmentSource", args.get("fragmentSource").cloned()),
+        ("vertexSource", args.get("vertexSource").cloned()),
+        ("passes", args.get("passes").cloned()),
+        ("objects", args.get("objects").cloned()),
+    ]);
+    if let Some(uniforms) = args.get("uniforms") {
+        data.insert("uniforms".into(), uniforms_payload(uniforms));
+    }
+    mutate(ctx, |s| mutations::update_shader(s, &data));
+    json!({ "ok": true, "shaderId": args["shaderId"] }).to_string()
+}
+
+fn set_uniform_schema() -> Value {
+    object(
+        json!({
+            "shaderId": string("ID of the shader containing the uniform."),
+            "uniformName": string("Name of the uniform to set (e.g. 'uSpeed', 'uColor', 'uInvert')."),
+            "value": uniform_value("New value for the uniform. Must match the GLSL type: \
+                number for float/int, boolean for bool, number[] for vec2/vec3/vec4."),
+        }),
+        &["shaderId", "uniformName", "value"],
+    )
+}
+
+fn set_uniform(ctx: &ToolContext, args: &Args) -> String {
+    mutate(ctx, |s| {
+        mutations::set_uniform(
+            s,
+            args.get("shaderId"),
+            args.get("uniformName"),
+            args.get("value"),
+        )
+    });
+    json!({
+        "ok": true,
+        "shaderId": args["shaderId"],
+        "uniformName": args["uniformName"],
+        "value": args["value"],
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::StateStore;
+
+    fn call_text(ctx: &ToolContext, name: &str, args: Value) -> (String, bool) {
+        let result = call(ctx, name, &args).unwrap();
+        (
+            result["content"][0]["text"].as_str().unwrap().to_string(),
+            result["isError"] == true,
+        )
+    }
+
+    #[test]
+    fn catalog_matches_node_server() {
+        let names: Vec<_> = list().iter().map(|t| t["name"].clone()).collect();
+        assert_eq!(names.len(), 16);
+        for name in [
+            "describe_scene",
+            "place_entity",
+            "create_choreography",
+            "create_wire",
+            "emit_signal",
+            "set_uniform",
+        ] {
+            assert!(names.contains(&json!(name)), "missing {name}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        let store = StateStore::default();
+        let ctx = ToolContext {
+            store: &store,
+            emit: &|_| Ok(0),
+        };
+        let (text, is_error) = call_text(
+            &ctx,
+            "place_entity",
+            json!({ "entityId": "peon", "x": "1", "y": 0 }),
+        );
+        assert!(is_error);
+        assert!(text.contains("x: expected number"), "{text}");
+
+        let (_, is_error) = call_text(
+            &ctx,
+            "create_choreography",
+            json!({ "on": "tool_call", "steps": [{ "action": "teleport" }] }),
+        );
+        assert!(is_error);
+        assert_eq!(store.snapshot().version, 0);
+    }
+
+    #[test]
+    fn composes_and_describes_a_scene() {
+        let store = StateStore::default();
+        let emitted = std::cell::Cell::new(0);
+        let emit = |_: Value| {
+            emitted.set(emitted.get() + 1);
+            Ok(2)
+        };
+        let ctx = ToolContext {
+            store: &store,
+            emit: &emit,
+        };
+
+        call_text(
+            &ctx,
+            "place_entity",
+            json!({ "entityId": "peon", "x": 10.5, "y": 20, "semanticId": "agent-1" }),
+        );
+        let (text, _) = call_text(
+            &ctx,
+            "create_choreography",
+            json!({ "on": "tool_call", "steps": [{ "action": "move", "entity": "agent-1" }, { "action": "flash" }] }),
+        );
+        let id = serde_json::from_str::<Value>(&text).unwrap()["choreographyId"].clone();
+        call_text(
+            &ctx,
+            "map_signals",
+            json!({ "signal_type": "tool_call", "choreography_id": id }),
+        );
+        let (text, _) = call_text(
+            &ctx,
+            "emit_signal",
+            json!({ "type": "tool_call", "from": "agent-1" }),
+        );
+        assert_eq!(serde_json::from_str::<Value>(&text).unwrap()["clients"], 2);
+        assert_eq!(emitted.get(), 1);
+
+        let (text, _) = call_text(&ctx, "describe_scene", json!({}));
+        assert!(text.contains("## Canvas: 960×640 px, mode: unknown, view: unknown"));
+        assert!(text.contains("  - \"agent-1\" (peon) at (11, 20), layer=midground, state=idle"));
+        assert!(text.contains("wired to: [tool_call], 2 steps: [move → flash]"));
+        assert!(text.contains("## Wiring (1 connections)"));
+        assert_eq!(store.snapshot().version, 3);
+    }
+}
@@ -0,0 +1,300 @@
+//! OpenClaw gateway credentials.
+//!
+//! Port of `readOpenClawToken` in `packages/mcp-server/src/routes/discovery.ts`,
+//! extended to the layouts OpenClaw itself supports: the config path can be
+//! overridden with `OPENCLAW_CONFIG_PATH` / `OPENCLAW_STATE_DIR`, and every
+//! `--profile <name>` keeps its own `~/.openclaw-<name>/openclaw.json`. Each
+//! config file is watched so a rotated token reaches the webview as
+//! [`CONFIG_EVENT`] without a restart.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+/// Webview event sent when an OpenClaw config file changes.
+pub const CONFIG_EVENT: &str = "openclaw://config-changed";
+
+/// Name of the profile stored in `~/.openclaw` (no `--profile`).
+const DEFAULT_PROFILE: &str = "default";
+
+/// Config file name inside a state dir.
+const CONFIG_FILE: &str = "openclaw.json";
+
+/// Port the gateway listens on when the config does not say.
+const DEFAULT_GATEWAY_PORT: u64 = 18789;
+
+/// How often config files are checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Why credentials could not be read. Serialized as `{ kind, ... }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CredentialError {
+    /// The home directory cannot be resolved.
+    NoHome,
+    /// No config file belongs to the requested profile.
+    UnknownProfile { profile: String },
+    /// The config file cannot be read.
+    MissingFile { path: PathBuf, message: String },
+    /// The config file is not valid JSON.
+    InvalidJson { path: PathBuf, message: String },
+    /// The config has no token at the expected key.
+    MissingKey { path: PathBuf, key: String },
+}
+
+/// One gateway the user can connect to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayProfile {
+    pub name: String,
+    pub config_path: PathBuf,
+    /// Gateway WebSocket URL, when the config could be read.
+    pub url: Option<String>,
+    pub token: Option<String>,
+    pub error: Option<CredentialError>,
+}
+
+// ---------------------------------------------------------------------------
+// Config resolution
+// ---------------------------------------------------------------------------
+
+/// Config file of every profile, default first.
+///
+/// `env` looks up environment variables; `home` is the user's home dir.
+fn config_paths(home: &Path, env: impl Fn(&str) -> Option<String>) -> Vec<(String, PathBuf)> {
+    let default = env("OPENCLAW_CONFIG_PATH")
+        .map(PathBuf::from)
+        .or_else(|| env("OPENCLAW_STATE_DIR").map(|dir| Path::new(&dir).join(CONFIG_FILE)))
+        .unwrap_or_else(|| home.join(".openclaw").join(CONFIG_FILE));
+    let mut paths = vec![(DEFAULT_PROFILE.to_string(), default)];
+
+    let mut named: Vec<(String, PathBuf)> = std::fs::read_dir(home)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix(".openclaw-")?
+                .to_string();
+            let path = entry.path().join(CONFIG_FILE);
+            path.is_file().then_some((name, path))
+        })
+        .collect();
+    named.sort();
+    paths.extend(named);
+    paths
+}
+
+fn home_config_paths() -> Result<Vec<(String, PathBuf)>, CredentialError> {
+    let home = dirs::home_dir().ok_or(CredentialError::NoHome)?;
+    Ok(config_paths(&home, |key| std::env::var(key).ok()))
+}
+
+fn read_config(path: &Path) -> Result<Value, CredentialError> {
+    let raw = std::fs::read_to_string(path).map_err(|e| CredentialError::MissingFile {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    serde_json::from_str(&raw).map_err(|e| CredentialError::InvalidJson {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+/// Gateway URL and token from a parsed config. A `remote`-mode gateway
+/// uses `gateway.remote.{url,token}`, a local one its own port and token.
+fn gateway(config: &Value, path: &Path) -> (String, Result<String, CredentialError>) {
+    let remote = config.pointer("/gateway/mode").and_then(Value::as_str) == Some("remote");
+    let (url, key) = match config
+        .pointer("/gateway/remote/url")
+        .and_then(Value::as_str)
+    {
+        Some(url) if remote => (url.to_string(), "gateway.remote.token"),
+        _ => {
+            let port = config
+                .pointer("/gateway/port")
+                .and_then(Value::as_u64)
+                .unwrap_or(DEFAULT_GATEWAY_PORT);
+            (format!("ws://127.0.0.1:{port}"), "gateway.auth.token")
+        }
+    };
+    let pointer = format!("/{}", key.replace('.', "/"));
+    let token = config
+        .pointer(&pointer)
+        .and_then(Value::as_str)
+        .map(String::from)
+        .ok_or_else(|| CredentialError::MissingKey {
+            path: path.to_path_buf(),
+            key: key.to_string(),
+        });
+    (url, token)
+}
+
+fn load_profile(name: String, path: PathBuf) -> GatewayProfile {
+    let (url, result) = match read_config(&path) {
+        Ok(config) => {
+            let (url, token) = gateway(&config, &path);
+            (Some(url), token)
+        }
+        Err(e) => (None, Err(e)),
+    };
+    let (token, error) = match result {
+        Ok(token) => (Some(token), None),
+        Err(e) => (None, Some(e)),
+    };
+    GatewayProfile {
+        name,
+        config_path: path,
+        url,
+        token,
+        error,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Watcher
+// ---------------------------------------------------------------------------
+
+/// Payload of [`CONFIG_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigChanged {
+    profile: String,
+    path: PathBuf,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Poll every profile's config file and emit [`CONFIG_EVENT`] when one is
+/// created, modified or removed.
+pub fn watch(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut seen: HashMap<PathBuf, Option<SystemTime>> = HashMap::new();
+        let mut first = true;
+        loop {
+            for (profile, path) in home_config_paths().unwrap_or_default() {
+                let stamp = modified(&path);
+                let previous = seen.insert(path.clone(), stamp);
+                if !first && previous != Some(stamp) {
+                    let _ = app.emit(CONFIG_EVENT, ConfigChanged { profile, path });
+                }
+            }
+            first = false;
+            tokio::time::sleep(WATCH_INTERVAL).await;
+        }
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Read the OpenClaw gateway auth token.
+///
+/// `path` points at a config file directly; otherwise `profile` (default
+/// `"default"`) selects one of [`list_openclaw_profiles`].
+#[tauri::command]
+pub fn read_openclaw_token(
+    path: Option<PathBuf>,
+    profile: Option<String>,
+) -> Result<String, CredentialError> {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let profile = profile.unwrap_or_else(|| DEFAULT_PROFILE.into());
+            home_config_paths()?
+                .into_iter()
+                .find(|(name, _)| *name == profile)
+                .map(|(_, path)| path)
+                .ok_or(CredentialError::UnknownProfile { profile })?
+        }
+    };
+    let config = read_config(&path)?;
+    gateway(&config, &path).1
+}
+
+/// Every OpenClaw profile found on this machine, with its gateway URL and
+/// token, or why they could not be read.
+#[tauri::command]
+pub fn list_openclaw_profiles() -> Result<Vec<GatewayProfile>, CredentialError> {
+    Ok(home_config_paths()?
+        .into_iter()
+        .map(|(name, path)| load_profile(name, path))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, raw: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, raw).unwrap();
+    }
+
+    #[test]
+    fn enumerates_profiles_and_honours_env_overrides() {
+        let home = tempfile::tempdir().unwrap();
+        write(&home.path().join(".openclaw-work/openclaw.json"), "{}");
+        std::fs::create_dir_all(home.path().join(".openclaw-empty")).unwrap();
+
+        let paths = config_paths(home.path(), |_| None);
+        let names: Vec<&str> = paths.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["default", "work"]);
+        assert_eq!(paths[0].1, home.path().join(".openclaw/openclaw.json"));
+
+        let env = |key: &str| (key == "OPENCLAW_STATE_DIR").then(|| "/srv/claw".to_string());
+        let paths = config_paths(home.path(), env);
+        assert_eq!(paths[0].1, Path::new("/srv/claw/openclaw.json"));
+    }
+
+    #[test]
+    fn reports_structured_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+
+        let missing = load_profile("default".into(), path.clone());
+        assert!(matches!(
+            missing.error,
+            Some(CredentialError::MissingFile { .. })
+        ));
+
+        write(&path, "{ nope");
+        let invalid = load_profile("default".into(), path.clone());
+        assert!(matches!(
+            invalid.error,
+            Some(CredentialError::InvalidJson { .. })
+        ));
+
+        write(&path, r#"{ "gateway": { "port": 19001 } }"#);
+        let keyless = load_profile("default".into(), path.clone());
+        assert_eq!(keyless.url.as_deref(), Some("ws://127.0.0.1:19001"));
+        assert_eq!(
+            serde_json::to_value(keyless.error.unwrap()).unwrap()["kind"],
+            "missingKey"
+        );
+    }
+
+    #[test]
+    fn remote_gateways_use_the_remote_token() {
+        let config = serde_json::json!({
+            "gateway": {
+                "mode": "remote",
+                "auth": { "token": "local" },
+                "remote": { "url": "wss://claw.internal:443", "token": "remote" },
+            }
+        });
+        let (url, token) = gateway(&config, Path::new(CONFIG_FILE));
+        assert_eq!(url, "wss://claw.internal:443");
+        assert_eq!(token.unwrap(), "remote");
+    }
+}
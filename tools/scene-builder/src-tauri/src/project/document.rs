@@ -0,0 +1,662 @@
+//! `.sajou` documents — Open / Save / Save As on top of the working folder.
+//!
+//! Persistence always writes to the working folder ([`Project::dir`]). A
+//! document is another project folder holding a copy of it plus a
+//! `<name>.sajou` manifest: saving copies the working folder out, opening
+//! copies a document in, so several scenes can live side by side on disk.
+//!
+//! Writes since the last save or open make the document dirty. The window
+//! title shows it, and New, Open and closing the window offer to save
+//! first. Before a close the webview is asked to flush its debounced saves
+//! (`project://close-requested` → `project_close_ready`). Recently used
+//! documents are listed in `recent-projects.json` in the app config dir.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State, Window, WindowEvent};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogResult};
+use tokio::sync::oneshot;
+
+use super::snapshots::Snapshots;
+use super::{files, Project};
+use crate::signals::now_ms;
+
+/// File extension of the manifest.
+pub const EXTENSION: &str = "sajou";
+
+/// `format` field of every manifest.
+const FORMAT: &str = "sajou-project";
+
+/// Newest manifest version this build reads and the one it writes.
+const MANIFEST_VERSION: u32 = 1;
+
+/// Recently used documents, in the app config dir.
+pub const RECENTS_FILE: &str = "recent-projects.json";
+
+const MAX_RECENTS: usize = 10;
+
+/// Asks the webview to flush pending saves before the window closes.
+pub const CLOSE_EVENT: &str = "project://close-requested";
+
+/// How long a close waits for the webview before going ahead.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+const APP_TITLE: &str = "sajou";
+
+// ---------------------------------------------------------------------------
+// Manifest
+// ---------------------------------------------------------------------------
+
+/// `<name>.sajou`, at the root of a saved project folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    pub name: String,
+    /// Unix milliseconds.
+    pub created_at: u64,
+    pub saved_at: u64,
+    /// App version that last saved it.
+    pub saved_with: String,
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Where Save As writes when the user picks `chosen`: `Foo.sajou` becomes
+/// `Foo/Foo.sajou`, unless it is already inside a folder named `Foo`.
+pub fn manifest_path(chosen: &Path) -> PathBuf {
+    let chosen = chosen.with_extension(EXTENSION);
+    let name = stem(&chosen);
+    let parent = chosen.parent().unwrap_or(Path::new(""));
+    if parent.file_name().is_some_and(|dir| dir == name.as_str()) {
+        chosen
+    } else {
+        parent
+            .join(&name)
+            .join(chosen.file_name().unwrap_or_default())
+    }
+}
+
+/// Read and check a manifest.
+pub fn read_manifest(path: &Path) -> io::Result<Manifest> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let value = files::read_json(path)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, path.display().to_string()))?;
+    let manifest: Manifest = serde_json::from_value(value)
+        .map_err(|e| invalid(format!("{}: not a sajou project: {e}", path.display())))?;
+    if manifest.format != FORMAT {
+        return Err(invalid(format!("{}: not a sajou project", path.display())));
+    }
+    if manifest.version > MANIFEST_VERSION {
+        return Err(invalid(format!(
+            "{} was saved by a newer sajou (project version {})",
+            path.display(),
+            manifest.version
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Whether `dir` may hold a document: missing, empty (dotfiles aside), or
+/// already a sajou project.
+fn can_save_into(dir: &Path) -> io::Result<bool> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(true),
+        Err(e) => return Err(e),
+    };
+    let mut empty = true;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == EXTENSION) && read_manifest(&path).is_ok() {
+            return Ok(true);
+        }
+        empty &= path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    }
+    Ok(empty)
+}
+
+/// Copy the working folder to the document at `manifest` and (re)write the
+/// manifest, keeping its creation time. Refuses a folder holding other files
+/// that is not a sajou project.
+pub fn save(working: &Path, manifest: &Path) -> io::Result<Manifest> {
+    let dir = manifest
+        .parent()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no project folder"))?;
+    if !can_save_into(dir)? {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} is not empty and is not a sajou project", dir.display()),
+        ));
+    }
+    let created_at = read_manifest(manifest).map_or_else(|_| now_ms(), |m| m.created_at);
+    files::copy(working, dir)?;
+    let written = Manifest {
+        format: FORMAT.into(),
+        version: MANIFEST_VERSION,
+        name: stem(manifest),
+        created_at,
+        saved_at: now_ms(),
+        saved_with: env!("CARGO_PKG_VERSION").into(),
+    };
+    files::write_json(manifest, &serde_json::to_value(&written)?)?;
+    Ok(written)
+}
+
+/// Replace the working folder with the document at `manifest`.
+pub fn load(manifest: &Path, working: &Path) -> io::Result<Manifest> {
+    let read = read_manifest(manifest)?;
+    let dir = manifest.parent().unwrap_or(Path::new(""));
+    files::copy(dir, working)?;
+    Ok(read)
+}
+
+// ---------------------------------------------------------------------------
+// Recent documents
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recent {
+    /// The manifest.
+    pub path: PathBuf,
+    pub name: String,
+    pub opened_at: u64,
+}
+
+/// Recent documents, most recent first, leaving out those that are gone.
+pub fn read_recents(file: &Path) -> Vec<Recent> {
+    files::read_json(file)
+        .ok()
+        .flatten()
+        .and_then(|list| serde_json::from_value::<Vec<Recent>>(list).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|r| r.path.is_file())
+        .collect()
+}
+
+/// Move `manifest` to the top of the recent list.
+pub fn add_recent(file: &Path, manifest: &Path) -> io::Result<()> {
+    let mut recents = read_recents(file);
+    recents.retain(|r| r.path != manifest);
+    recents.insert(
+        0,
+        Recent {
+            path: manifest.to_path_buf(),
+            name: stem(manifest),
+            opened_at: now_ms(),
+        },
+    );
+    recents.truncate(MAX_RECENTS);
+    files::write_json(file, &serde_json::to_value(recents)?)
+}
+
+// ---------------------------------------------------------------------------
+// Document state
+// ---------------------------------------------------------------------------
+
+/// The document the working folder belongs to.
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    /// Its manifest; `None` until first saved.
+    pub path: Option<PathBuf>,
+    pub dirty: bool,
+}
+
+impl Document {
+    pub fn name(&self) -> String {
+        self.path.as_deref().map_or_else(|| "Untitled".into(), stem)
+    }
+}
+
+/// What the webview shows of the document.
+#[derive(Debug, Clone, Serialize)]
+pub struct Status {
+    pub path: Option<PathBuf>,
+    pub name: String,
+    pub dirty: bool,
+}
+
+impl From<&Document> for Status {
+    fn from(doc: &Document) -> Self {
+        Self {
+            path: doc.path.clone(),
+            name: doc.name(),
+            dirty: doc.dirty,
+        }
+    }
+}
+
+/// Window close handshake.
+#[derive(Default)]
+pub struct Closing {
+    /// A close is being negotiated.
+    pending: AtomicBool,
+    /// The next close request goes through.
+    allowed: AtomicBool,
+    /// ID of the last close request sent to the webview.
+    request: AtomicU64,
+    /// Told when the webview acknowledges the flush of the current request;
+    /// an ack for an earlier request finds no match and is ignored.
+    flushed: Mutex<Option<(u64, oneshot::Sender<()>)>>,
+    /// The last close saw the flush acknowledged, so the journal holds
+    /// nothing the working folder lacks.
+    settled: AtomicBool,
+}
+
+fn update_title(app: &AppHandle, doc: &Document) {
+    if let Some(window) = app.get_webview_window("main") {
+        let marker = if doc.dirty { " •" } else { "" };
+        let _ = window.set_title(&format!("{}{marker} — {APP_TITLE}", doc.name()));
+    }
+}
+
+fn set_document(app: &AppHandle, path: Option<PathBuf>, dirty: bool) -> Status {
+    let project = app.state::<Project>();
+    let doc = {
+        let mut doc = project.document.lock().unwrap_or_else(|e| e.into_inner());
+        *doc = Document { path, dirty };
+        doc.clone()
+    };
+    project.remember();
+    update_title(app, &doc);
+    Status::from(&doc)
+}
+
+/// Record a change to the working folder.
+pub fn mark_dirty(app: &AppHandle) {
+    let project = app.state::<Project>();
+    let path = {
+        let doc = project.document.lock().unwrap_or_else(|e| e.into_inner());
+        if doc.dirty {
+            return;
+        }
+        doc.path.clone()
+    };
+    set_document(app, path, true);
+}
+
+/// Show the title once the window exists.
+pub fn init(app: &AppHandle) {
+    let doc = app.state::<Project>().document();
+    update_title(app, &doc);
+}
+
+// ---------------------------------------------------------------------------
+// Dialogs
+// ---------------------------------------------------------------------------
+
+async fn pick_manifest(app: &AppHandle) -> Option<PathBuf> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Open project")
+        .add_filter("sajou project", &[EXTENSION])
+        .pick_file(move |file| {
+            let _ = tx.send(file.and_then(|f| f.into_path().ok()));
+        });
+    rx.await.ok().flatten()
+}
+
+async fn pick_save_path(app: &AppHandle, name: &str) -> Option<PathBuf> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Save project as")
+        .set_file_name(format!("{name}.{EXTENSION}"))
+        .add_filter("sajou project", &[EXTENSION])
+        .set_can_create_directories(true)
+        .save_file(move |file| {
+            let _ = tx.send(file.and_then(|f| f.into_path().ok()));
+        });
+    rx.await.ok().flatten()
+}
+
+enum Choice {
+    Save,
+    Discard,
+    Cancel,
+}
+
+async fn ask_to_save(app: &AppHandle, name: &str, before: &str) -> Choice {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(format!("Save changes to “{name}” before {before}?"))
+        .title("Unsaved changes")
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            "Save".into(),
+            "Don't Save".into(),
+            "Cancel".into(),
+        ))
+        .show_with_result(move |result| {
+            let _ = tx.send(result);
+        });
+    match rx.await.unwrap_or_default() {
+        MessageDialogResult::Yes => Choice::Save,
+        MessageDialogResult::No => Choice::Discard,
+        MessageDialogResult::Custom(label) if label == "Save" => Choice::Save,
+        MessageDialogResult::Custom(label) if label == "Don't Save" => Choice::Discard,
+        _ => Choice::Cancel,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Operations
+// ---------------------------------------------------------------------------
+
+/// Save to the current document, or to `target` / a picked path when
+/// `save_as` or untitled. `None` when the user cancelled.
+async fn save_document(
+    app: &AppHandle,
+    save_as: bool,
+    target: Option<PathBuf>,
+) -> Result<Option<Status>, String> {
+    let project = app.state::<Project>();
+    let doc = project.document();
+    let manifest = match (target, &doc.path) {
+        (Some(chosen), _) => manifest_path(&chosen),
+        (None, Some(path)) if !save_as => path.clone(),
+        (None, _) => match pick_save_path(app, &doc.name()).await {
+            Some(chosen) => manifest_path(&chosen),
+            None => return Ok(None),
+        },
+    };
+
+    save(&project.dir(), &manifest)
+        .map_err(|e| format!("cannot save {}: {e}", manifest.display()))?;
+    project.add_recent(&manifest);
+    super::journal::clear(app);
+    eprintln!("[sajou] project saved to {}", manifest.display());
+    Ok(Some(set_document(app, Some(manifest), false)))
+}
+
+/// Snapshot the working folder if it belongs to no document, so work
+/// about to be discarded can still be restored from the history. Errs
+/// (and the discard must not happen) when the snapshot cannot be taken.
+pub fn keep_untitled(app: &AppHandle) -> io::Result<()> {
+    let project = app.state::<Project>();
+    if project.document().path.is_none() {
+        app.state::<Snapshots>().take(&project)?;
+    }
+    Ok(())
+}
+
+/// Offer to save unsaved changes. `false` when the user cancelled.
+async fn confirm_discard(app: &AppHandle, before: &str) -> Result<bool, String> {
+    let doc = app.state::<Project>().document();
+    if !doc.dirty {
+        return Ok(true);
+    }
+    match ask_to_save(app, &doc.name(), before).await {
+        Choice::Save => Ok(save_document(app, false, None).await?.is_some()),
+        Choice::Discard => {
+            keep_untitled(app).map_err(|e| format!("cannot snapshot unsaved work: {e}"))?;
+            Ok(true)
+        }
+        Choice::Cancel => Ok(false),
+    }
+}
+
+/// Put the working folder back to the saved document, or empty it if
+/// untitled — only once a snapshot holds what it contained.
+fn revert(app: &AppHandle) -> io::Result<()> {
+    let project = app.state::<Project>();
+    let doc = project.document();
+    match &doc.path {
+        Some(manifest) => {
+            load(manifest, &project.dir())?;
+        }
+        None => {
+            keep_untitled(app)?;
+            files::clear(&project.dir())?;
+        }
+    }
+    set_document(app, doc.path, false);
+    Ok(())
+}
+
+async fn close(window: Window, flushed: oneshot::Receiver<()>) {
+    let app = window.app_handle().clone();
+    let project = app.state::<Project>();
+    let settled = matches!(
+        tokio::time::timeout(FLUSH_TIMEOUT, flushed).await,
+        Ok(Ok(()))
+    );
+    if !settled {
+        eprintln!("[sajou] pending saves were not flushed; keeping the journal");
+    }
+    project.closing.settled.store(settled, Ordering::SeqCst);
+
+    let go = match confirm_discard(&app, "closing").await {
+        Ok(go) => go,
+        Err(e) => {
+            eprintln!("[sajou] {e}");
+            app.dialog()
+                .message(format!("{e}\n\nThe window stays open."))
+                .title("Save failed")
+                .show(|_| {});
+            false
+        }
+    };
+    if !go {
+        project.closing.pending.store(false, Ordering::SeqCst);
+        return;
+    }
+    // "Don't Save" leaves the working folder as it was last saved.
+    if project.document().dirty {
+        if let Err(e) = revert(&app) {
+            eprintln!("[sajou] cannot revert the working folder: {e}");
+        }
+    }
+    project.closing.allowed.store(true, Ordering::SeqCst);
+    let _ = window.destroy();
+}
+
+/// On exit, truncate the journal only if the close flushed every save;
+/// otherwise the next launch offers to recover from it.
+pub fn on_exit(app: &AppHandle) {
+    if app
+        .state::<Project>()
+        .closing
+        .settled
+        .load(Ordering::SeqCst)
+    {
+        super::journal::clear(app);
+    }
+}
+
+/// Hold the window open until pending saves are flushed and unsaved
+/// changes are dealt with.
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::CloseRequested { api, .. } = event else {
+        return;
+    };
+    let project = window.state::<Project>();
+    if project.closing.allowed.load(Ordering::SeqCst) {
+        return;
+    }
+    api.prevent_close();
+    if project.closing.pending.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let request = project.closing.request.fetch_add(1, Ordering::SeqCst) + 1;
+    let (tx, rx) = oneshot::channel();
+    *project
+        .closing
+        .flushed
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = Some((request, tx));
+    let _ = window.emit(CLOSE_EVENT, request);
+    tauri::async_runtime::spawn(close(window.clone(), rx));
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// The open document.
+#[tauri::command]
+pub fn project_document(project: State<'_, Project>) -> Status {
+    Status::from(&project.document())
+}
+
+/// Recently opened or saved documents, most recent first.
+#[tauri::command]
+pub fn project_recent(project: State<'_, Project>) -> Vec<Recent> {
+    project
+        .recents
+        .as_deref()
+        .map(read_recents)
+        .unwrap_or_default()
+}
+
+/// Start an untitled document with an empty working folder, after offering
+/// to save. `false` when the user cancelled.
+#[tauri::command]
+pub async fn project_new(app: AppHandle) -> Result<bool, String> {
+    if !confirm_discard(&app, "starting a new scene").await? {
+        return Ok(false);
+    }
+    let dir = app.state::<Project>().dir();
+    files::clear(&dir).map_err(|e| e.to_string())?;
+    super::journal::clear(&app);
+    set_document(&app, None, false);
+    Ok(true)
+}
+
+/// Open the document at `path` (or a picked one) into the working folder,
+/// after offering to save. `None` when the user cancelled; the webview then
+/// reloads its stores.
+#[tauri::command]
+pub async fn project_open(app: AppHandle, path: Option<PathBuf>) -> Result<Option<Status>, String> {
+    let manifest = match path {
+        Some(path) => path,
+        None => match pick_manifest(&app).await {
+            Some(path) => path,
+            None => return Ok(None),
+        },
+    };
+    read_manifest(&manifest).map_err(|e| e.to_string())?;
+    if !confirm_discard(&app, "opening another project").await? {
+        return Ok(None);
+    }
+
+    let project = app.state::<Project>();
+    load(&manifest, &project.dir())
+        .map_err(|e| format!("cannot open {}: {e}", manifest.display()))?;
+    project.add_recent(&manifest);
+    super::journal::clear(&app);
+    eprintln!("[sajou] project opened from {}", manifest.display());
+    Ok(Some(set_document(&app, Some(manifest), false)))
+}
+
+/// Save the working folder to the open document (Save As when untitled).
+#[tauri::command]
+pub async fn project_save(app: AppHandle) -> Result<Option<Status>, String> {
+    save_document(&app, false, None).await
+}
+
+/// Save the working folder as a new document at `path`, or a picked one.
+#[tauri::command]
+pub async fn project_save_as(
+    app: AppHandle,
+    path: Option<PathBuf>,
+) -> Result<Option<Status>, String> {
+    save_document(&app, true, path).await
+}
+
+/// The webview flushed its pending saves for close `request`; the window
+/// close can go on.
+#[tauri::command]
+pub fn project_close_ready(project: State<'_, Project>, request: u64) {
+    let mut flushed = project
+        .closing
+        .flushed
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if flushed.as_ref().is_some_and(|(id, _)| *id == request) {
+        if let Some((_, tx)) = flushed.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn saves_and_opens_documents() {
+        let root = tempfile::tempdir().unwrap();
+        let working = root.path().join("working");
+        let scene = json!({ "version": 1, "data": { "mode": "run" } });
+        files::write_section(&working, "scene", &scene).unwrap();
+        files::write_asset(
+            &working,
+            json!({ "path": "units/peon.png" })
+                .as_object()
+                .cloned()
+                .unwrap(),
+            b"png",
+        )
+        .unwrap();
+
+        let manifest = manifest_path(&root.path().join("Village.sajou"));
+        assert_eq!(manifest, root.path().join("Village/Village.sajou"));
+        assert_eq!(manifest_path(&manifest), manifest);
+        let saved = save(&working, &manifest).unwrap();
+        assert_eq!(saved.name, "Village");
+        assert_eq!(read_manifest(&manifest).unwrap(), saved);
+
+        files::clear(&working).unwrap();
+        load(&manifest, &working).unwrap();
+        assert_eq!(files::read_section(&working, "scene").unwrap(), Some(scene));
+        assert_eq!(files::read_assets(&working).unwrap()[0].bytes, b"png");
+
+        files::write_json(&manifest, &json!({ "format": "other" })).unwrap();
+        assert!(load(&manifest, &working).is_err());
+
+        // Someone else's folder is left alone.
+        let foreign = root.path().join("Photos");
+        std::fs::create_dir_all(foreign.join("assets")).unwrap();
+        std::fs::write(foreign.join("assets/cat.png"), "cat").unwrap();
+        std::fs::write(foreign.join("scene.json"), "{}").unwrap();
+        assert!(save(&working, &foreign.join("Photos.sajou")).is_err());
+        assert!(foreign.join("assets/cat.png").exists());
+        assert_eq!(std::fs::read(foreign.join("scene.json")).unwrap(), b"{}");
+    }
+
+    #[test]
+    fn keeps_recent_documents_in_order() {
+        let root = tempfile::tempdir().unwrap();
+        let list = root.path().join(RECENTS_FILE);
+        let [a, b] = ["a.sajou", "b.sajou"].map(|n| root.path().join(n));
+        for path in [&a, &b] {
+            std::fs::write(path, "{}").unwrap();
+        }
+        add_recent(&list, &a).unwrap();
+        add_recent(&list, &b).unwrap();
+        add_recent(&list, &a).unwrap();
+        std::fs::remove_file(&b).unwrap();
+        add_recent(&list, &root.path().join("gone.sajou")).unwrap();
+
+        let names: Vec<_> = read_recents(&list).into_iter().map(|r| r.name).collect();
+        assert_eq!(names, ["a"]);
+    }
+}
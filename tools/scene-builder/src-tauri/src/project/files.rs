@@ -0,0 +1,352 @@
+//! On-disk layout of a project folder.
+//!
+//! One JSON file per persistence store, holding the same versioned
+//! `{ version, data }` envelope `persistence.ts` writes to IndexedDB:
+//!
+//! ```text
+//! scene.json  entities.json  choreographies.json  wires.json  bindings.json
+//! timeline.json  shaders.json  p5.json
+//! assets.json          asset metadata, `{ version, data: [{ path, name, … }] }`
+//! assets/<path>        asset bytes
+//! ```
+//!
+//! Every write goes through [`write_atomic`], so a crash leaves either the
+//! old or the new file, never a torn one.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde_json::{json, Map, Value};
+
+/// Persistence stores, named as in `persistence-db.ts`.
+pub const SECTIONS: [&str; 8] = [
+    "scene",
+    "entities",
+    "choreographies",
+    "wires",
+    "bindings",
+    "timeline",
+    "shaders",
+    "p5",
+];
+
+/// Asset metadata file.
+const ASSET_INDEX: &str = "assets.json";
+
+/// Asset bytes directory.
+const ASSET_DIR: &str = "assets";
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.into())
+}
+
+/// Replace `path` with `bytes` atomically: write a sibling temp file, sync
+/// it, then rename it over the target.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    replace(path, bytes, &options)
+}
+
+/// [`write_atomic`] for secrets: on Unix the file is readable by its owner
+/// only, from the moment the temp file exists.
+pub fn write_atomic_private(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    replace(path, bytes, &options)
+}
+
+/// Write a temp file opened with `options`, then rename it over `path`.
+fn replace(path: &Path, bytes: &[u8], options: &OpenOptions) -> io::Result<()> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| invalid(format!("no parent directory: {}", path.display())))?;
+    fs::create_dir_all(dir)?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    // Unique per write, so concurrent writers never share a temp file.
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let tmp = dir.join(format!(".{name}.{}-{n}.tmp", std::process::id()));
+
+    let result = (|| {
+        let mut file = options.open(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Write `value` as pretty JSON (diff-friendly under git), atomically.
+pub fn write_json(path: &Path, value: &Value) -> io::Result<()> {
+    let mut bytes = serde_json::to_vec_pretty(value).map_err(io::Error::other)?;
+    bytes.push(b'\n');
+    write_atomic(path, &bytes)
+}
+
+/// Read a JSON file; `None` when it does not exist.
+pub fn read_json(path: &Path) -> io::Result<Option<Value>> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map(Some).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {e}", path.display()),
+            )
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn section_path(dir: &Path, section: &str) -> io::Result<PathBuf> {
+    if !SECTIONS.contains(&section) {
+        return Err(invalid(format!("unknown section: {section}")));
+    }
+    Ok(dir.join(format!("{section}.json")))
+}
+
+/// Whether `record` is a `{ version, data }` envelope.
+pub fn is_envelope(record: &Value) -> bool {
+    record.get("version").is_some_and(Value::is_u64) && record.get("data").is_some()
+}
+
+/// The stored envelope of `section`, if saved.
+pub fn read_section(dir: &Path, section: &str) -> io::Result<Option<Value>> {
+    read_json(&section_path(dir, section)?)
+}
+
+/// Save the envelope of `section`.
+pub fn write_section(dir: &Path, section: &str, record: &Value) -> io::Result<()> {
+    let path = section_path(dir, section)?;
+    if !is_envelope(record) {
+        return Err(invalid(format!(
+            "{section}: expected a {{ version, data }} envelope"
+        )));
+    }
+    write_json(&path, record)
+}
+
+// ---------------------------------------------------------------------------
+// Assets
+// ---------------------------------------------------------------------------
+
+/// `assets/<path>`, refusing paths that would leave the folder.
+pub fn asset_file(dir: &Path, path: &str) -> io::Result<PathBuf> {
+    let relative = Path::new(path);
+    let safe = !path.is_empty()
+        && relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+    if !safe {
+        return Err(invalid(format!("invalid asset path: {path}")));
+    }
+    Ok(dir.join(ASSET_DIR).join(relative))
+}
+
+/// Asset metadata records, in save order.
+pub fn asset_index(dir: &Path) -> io::Result<Vec<Map<String, Value>>> {
+    let index = read_json(&dir.join(ASSET_INDEX))?;
+    Ok(index
+        .and_then(|index| index.get("data").and_then(Value::as_array).cloned())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Value::Object(meta) => Some(meta),
+            _ => None,
+        })
+        .collect())
+}
+
+fn asset_key(meta: &Map<String, Value>) -> Option<&str> {
+    meta.get("path").and_then(Value::as_str)
+}
+
+/// Paths of the saved assets.
+pub fn asset_keys(dir: &Path) -> io::Result<Vec<String>> {
+    Ok(asset_index(dir)?
+        .iter()
+        .filter_map(asset_key)
+        .map(String::from)
+        .collect())
+}
+
+/// Save an asset: `meta` (with its `path`) in the index, `bytes` under `assets/`.
+/// Callers serialise writes; the index is read, updated and rewritten.
+pub fn write_asset(dir: &Path, meta: Map<String, Value>, bytes: &[u8]) -> io::Result<()> {
+    let key = asset_key(&meta)
+        .ok_or_else(|| invalid("asset has no path"))?
+        .to_string();
+    write_atomic(&asset_file(dir, &key)?, bytes)?;
+
+    let mut index = asset_index(dir)?;
+    match index
+        .iter_mut()
+        .find(|m| asset_key(m) == Some(key.as_str()))
+    {
+        Some(existing) => *existing = meta,
+        None => index.push(meta),
+    }
+    write_json(
+        &dir.join(ASSET_INDEX),
+        &json!({ "version": 1, "data": index }),
+    )
+}
+
+/// A saved asset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredAsset {
+    /// `StoredAsset` of `persistence.ts` without its buffer.
+    pub meta: Map<String, Value>,
+    pub bytes: Vec<u8>,
+}
+
+/// Every saved asset with its bytes. Assets whose file is missing are skipped.
+pub fn read_assets(dir: &Path) -> io::Result<Vec<StoredAsset>> {
+    let mut assets = Vec::new();
+    for meta in asset_index(dir)? {
+        let Some(key) = asset_key(&meta) else {
+            continue;
+        };
+        match fs::read(asset_file(dir, key)?) {
+            Ok(bytes) => assets.push(StoredAsset { meta, bytes }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                eprintln!("[sajou] project asset missing: {key}");
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(assets)
+}
+
+/// Remove every store and the assets listed in the folder's own index,
+/// leaving unrelated files in the folder (and under `assets/`) alone.
+pub fn clear(dir: &Path) -> io::Result<()> {
+    let root = dir.join(ASSET_DIR);
+    for key in asset_keys(dir)? {
+        let Ok(file) = asset_file(dir, &key) else {
+            continue;
+        };
+        match fs::remove_file(&file) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        // Drop the folders this emptied, up to and including `assets/`.
+        for parent in file.ancestors().skip(1) {
+            if !parent.starts_with(&root) || fs::remove_dir(parent).is_err() {
+                break;
+            }
+        }
+    }
+    // The index goes last, so a failed clear can be retried.
+    let files = SECTIONS
+        .iter()
+        .map(|s| dir.join(format!("{s}.json")))
+        .chain([dir.join(ASSET_INDEX)]);
+    for file in files {
+        match fs::remove_file(&file) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Copy every store and indexed asset of `from` into `to`, replacing what
+/// `to` held.
+pub fn copy(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    if fs::canonicalize(from)? == fs::canonicalize(to)? {
+        return Ok(());
+    }
+    clear(to)?;
+    for key in asset_keys(from)? {
+        let (Ok(source), Ok(target)) = (asset_file(from, &key), asset_file(to, &key)) else {
+            continue;
+        };
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match fs::copy(source, target) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    // Stores and the index last, so they never list assets not yet copied.
+    let names = SECTIONS
+        .iter()
+        .map(|s| format!("{s}.json"))
+        .chain([ASSET_INDEX.to_string()]);
+    for name in names {
+        match fs::read(from.join(&name)) {
+            Ok(bytes) => write_atomic(&to.join(&name), &bytes)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_sections_and_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        assert_eq!(read_section(dir, "scene").unwrap(), None);
+
+        let scene = json!({ "version": 1, "data": { "dimensions": { "width": 960 } } });
+        write_section(dir, "scene", &scene).unwrap();
+        assert_eq!(read_section(dir, "scene").unwrap(), Some(scene));
+        assert!(write_section(dir, "scene", &json!({ "width": 960 })).is_err());
+        assert!(write_section(dir, "../escape", &json!({ "version": 1, "data": {} })).is_err());
+
+        let meta = |category: &str| {
+            json!({ "path": "sprites/peon.png", "name": "peon.png", "category": category })
+                .as_object()
+                .cloned()
+                .unwrap()
+        };
+        write_asset(dir, meta("units"), b"png-1").unwrap();
+        write_asset(dir, meta("heroes"), b"png-2").unwrap();
+        assert_eq!(asset_keys(dir).unwrap(), ["sprites/peon.png"]);
+        let assets = read_assets(dir).unwrap();
+        assert_eq!(
+            assets,
+            [StoredAsset {
+                meta: meta("heroes"),
+                bytes: b"png-2".to_vec()
+            }]
+        );
+        assert!(write_asset(
+            dir,
+            json!({ "path": "../x" }).as_object().cloned().unwrap(),
+            b""
+        )
+        .is_err());
+
+        // No temp files are left behind.
+        let names: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert!(names.iter().all(|n| !n.ends_with(".tmp")), "{names:?}");
+
+        fs::write(dir.join("README.md"), "notes").unwrap();
+        fs::write(dir.join("assets/mine.png"), "not indexed").unwrap();
+        clear(dir).unwrap();
+        assert_eq!(read_section(dir, "scene").unwrap(), None);
+        assert!(asset_keys(dir).unwrap().is_empty());
+        assert!(dir.join("README.md").exists());
+        assert!(dir.join("assets/mine.png").exists());
+        assert!(!dir.join("assets/sprites").exists());
+    }
+}
@@ -0,0 +1,320 @@
+//! Crash-recovery journal of edits not yet saved to the working folder.
+//!
+//! The webview pushes its state to the [`StateStore`] every 300 ms, but
+//! saves each store to the working folder only after a 500 ms debounce, so
+//! a crash can lose the last edits. Every state change is compared with the
+//! previous one and the changed sections are appended to `.journal.jsonl`
+//! in the working folder, one `{ at, section, data }` line each. Entities
+//! and the signal timeline are not mirrored in the state store, so the
+//! webview journals them itself through [`project_journal`] as they change.
+//! Asset bytes are not journaled.
+//!
+//! On the next launch (`project_recover`, called by `restoreState`) the
+//! latest entry per section is compared with what the folder holds; if any
+//! differ the user is asked whether to recover them. The journal is
+//! truncated after Save, Open, New, snapshot restore and an exit whose close
+//! saw the webview flush its saves, and compacted once it grows past a few
+//! megabytes.
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+
+use super::{document, files, Project};
+use crate::signals::now_ms;
+use crate::state::store::ServerState;
+use crate::state::StateStore;
+
+/// Journal file, in the working folder.
+const JOURNAL_FILE: &str = ".journal.jsonl";
+
+/// Size past which the journal is compacted.
+const COMPACT_BYTES: u64 = 4 << 20;
+
+/// State store sections that are saved to the project, and their store.
+const SECTIONS: [(&str, &str); 6] = [
+    ("scene", "scene"),
+    ("choreographies", "choreographies"),
+    ("wiring", "wires"),
+    ("bindings", "bindings"),
+    ("shaders", "shaders"),
+    ("p5", "p5"),
+];
+
+/// Project stores the webview journals itself, not mirrored in the state
+/// store.
+const WEBVIEW_SECTIONS: [&str; 2] = ["entities", "timeline"];
+
+/// One journaled change: the new `data` of a project store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub at: u64,
+    /// Project store name (`wires`, not `wiring`).
+    pub section: String,
+    pub data: Value,
+}
+
+fn journal_path(working: &Path) -> PathBuf {
+    working.join(JOURNAL_FILE)
+}
+
+fn sections(state: &ServerState) -> [(&'static str, &Map<String, Value>); 6] {
+    [
+        (SECTIONS[0].1, &state.scene),
+        (SECTIONS[1].1, &state.choreographies),
+        (SECTIONS[2].1, &state.wiring),
+        (SECTIONS[3].1, &state.bindings),
+        (SECTIONS[4].1, &state.shaders),
+        (SECTIONS[5].1, &state.p5),
+    ]
+}
+
+/// Entries for the sections that differ between `before` and `after`.
+pub fn changes(before: &ServerState, after: &ServerState) -> Vec<Entry> {
+    let at = now_ms();
+    sections(before)
+        .into_iter()
+        .zip(sections(after))
+        .filter(|((_, a), (_, b))| a != b)
+        .map(|(_, (section, data))| Entry {
+            at,
+            section: section.to_string(),
+            data: Value::Object(data.clone()),
+        })
+        .collect()
+}
+
+/// Append `entries` and flush them to disk.
+pub fn append(working: &Path, entries: &[Entry]) -> io::Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    fs::create_dir_all(working)?;
+    let mut lines = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut lines, entry)?;
+        lines.push(b'\n');
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal_path(working))?;
+    file.write_all(&lines)?;
+    file.sync_data()
+}
+
+/// Journaled entries, oldest first. A torn last line (crash mid-write) is
+/// ignored.
+pub fn read(working: &Path) -> io::Result<Vec<Entry>> {
+    let file = match fs::File::open(journal_path(working)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        match serde_json::from_str(&line?) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => eprintln!("[sajou] skipping journal line: {e}"),
+        }
+    }
+    Ok(entries)
+}
+
+/// The latest journaled entry of each store that differs from the folder.
+pub fn pending(working: &Path) -> io::Result<Vec<Entry>> {
+    let mut latest = BTreeMap::new();
+    for entry in read(working)? {
+        latest.insert(entry.section.clone(), entry);
+    }
+    let mut pending = Vec::new();
+    for (section, entry) in latest {
+        let saved = files::read_section(working, &section)?;
+        if saved.as_ref().map(|r| &r["data"]) != Some(&entry.data) {
+            pending.push(entry);
+        }
+    }
+    Ok(pending)
+}
+
+/// Write `entries` to the working folder.
+pub fn replay(working: &Path, entries: &[Entry]) -> io::Result<()> {
+    for entry in entries {
+        let record = json!({ "version": 1, "data": entry.data });
+        files::write_section(working, &entry.section, &record)?;
+    }
+    Ok(())
+}
+
+/// Forget everything journaled.
+pub fn truncate(working: &Path) -> io::Result<()> {
+    match fs::remove_file(journal_path(working)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Once the journal is large, keep only the entries not yet saved.
+fn compact(working: &Path) -> io::Result<()> {
+    let size = fs::metadata(journal_path(working)).map_or(0, |m| m.len());
+    if size < COMPACT_BYTES {
+        return Ok(());
+    }
+    let pending = pending(working)?;
+    let mut lines = Vec::new();
+    for entry in &pending {
+        serde_json::to_writer(&mut lines, entry)?;
+        lines.push(b'\n');
+    }
+    files::write_atomic(&journal_path(working), &lines)
+}
+
+// ---------------------------------------------------------------------------
+// Tauri wiring
+// ---------------------------------------------------------------------------
+
+/// Journal state store changes for as long as the app runs. The first
+/// change after launch is the state the webview loaded from disk, so it
+/// only sets the baseline.
+pub fn start(app: &AppHandle, store: &StateStore) {
+    let mut rx = store.subscribe();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last: Option<ServerState> = None;
+        loop {
+            match rx.recv().await {
+                Ok(_) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+            let state = app.state::<StateStore>().read(Clone::clone);
+            if let Some(before) = &last {
+                let working = app.state::<Project>().dir();
+                let entries = changes(before, &state);
+                if let Err(e) = append(&working, &entries).and_then(|()| compact(&working)) {
+                    eprintln!("[sajou] journal write failed: {e}");
+                }
+            }
+            last = Some(state);
+        }
+    });
+}
+
+/// Truncate the journal of the working folder, logging failures.
+pub fn clear(app: &AppHandle) {
+    if let Err(e) = truncate(&app.state::<Project>().dir()) {
+        eprintln!("[sajou] cannot truncate the journal: {e}");
+    }
+}
+
+/// Journal the new `data` of a store the state store does not mirror.
+#[tauri::command]
+pub fn project_journal(app: AppHandle, section: String, data: Value) -> Result<(), String> {
+    if !WEBVIEW_SECTIONS.contains(&section.as_str()) {
+        return Err(format!("not a journaled store: {section}"));
+    }
+    let working = app.state::<Project>().dir();
+    let entry = Entry {
+        at: now_ms(),
+        section,
+        data,
+    };
+    append(&working, &[entry])
+        .and_then(|()| compact(&working))
+        .map_err(|e| e.to_string())
+}
+
+/// Offer to replay edits journaled before a crash into the working folder.
+/// Returns the recovered stores; the webview then reads them as usual.
+#[tauri::command]
+pub async fn project_recover(app: AppHandle) -> Result<Vec<String>, String> {
+    let working = app.state::<Project>().dir();
+    let pending = pending(&working).map_err(|e| e.to_string())?;
+    if pending.is_empty() {
+        clear(&app);
+        return Ok(Vec::new());
+    }
+
+    let names: Vec<String> = pending.iter().map(|e| e.section.clone()).collect();
+    let last_at = pending.iter().map(|e| e.at).max().unwrap_or(0);
+    let minutes = now_ms().saturating_sub(last_at) / 60_000;
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(format!(
+            "sajou did not shut down cleanly. Edits to {} made {} were not saved.\n\nRecover them?",
+            names.join(", "),
+            match minutes {
+                0 => "just before".to_string(),
+                1 => "1 minute earlier".to_string(),
+                n => format!("{n} minutes earlier"),
+            }
+        ))
+        .title("Recover unsaved changes?")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Recover".into(),
+            "Discard".into(),
+        ))
+        .show(move |recover| {
+            let _ = tx.send(recover);
+        });
+    let recover = rx.await.unwrap_or(false);
+
+    let recovered = if recover {
+        replay(&working, &pending).map_err(|e| e.to_string())?;
+        document::mark_dirty(&app);
+        eprintln!("[sajou] recovered {} from the journal", names.join(", "));
+        names
+    } else {
+        Vec::new()
+    };
+    clear(&app);
+    Ok(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journals_changes_and_finds_what_was_not_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let working = dir.path();
+        let before = ServerState::default();
+        let mut after = before.clone();
+        after.wiring.insert("wires".into(), json!([{ "id": "w1" }]));
+        after.p5.insert("sketches".into(), json!([{ "id": "s1" }]));
+
+        let entries = changes(&before, &after);
+        let names: Vec<_> = entries.iter().map(|e| e.section.as_str()).collect();
+        assert_eq!(names, ["wires", "p5"]);
+        append(working, &entries).unwrap();
+        // A torn line from a crash mid-append is skipped.
+        OpenOptions::new()
+            .append(true)
+            .open(journal_path(working))
+            .unwrap()
+            .write_all(b"{\"at\":1,\"sect")
+            .unwrap();
+
+        // p5 reached the folder before the crash, wires did not.
+        let p5 = json!({ "version": 1, "data": after.p5 });
+        files::write_section(working, "p5", &p5).unwrap();
+        let pending = pending(working).unwrap();
+        assert_eq!(pending, [entries[0].clone()]);
+
+        replay(working, &pending).unwrap();
+        assert_eq!(
+            files::read_section(working, "wires").unwrap().unwrap()["data"],
+            json!({ "wires": [{ "id": "w1" }] })
+        );
+        assert!(super::pending(working).unwrap().is_empty());
+        truncate(working).unwrap();
+        assert!(read(working).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+//! One-time import of the webview's IndexedDB into a project folder.
+//!
+//! The webview dumps every store of the `sajou-scene-builder` database
+//! (any `DB_VERSION` from 1 to 3 — older ones simply lack the `shaders` and
+//! `p5` stores) with asset `ArrayBuffer`s as base64, and [`import`] writes
+//! them out in the [`files`](super::files) layout. Whatever cannot be
+//! converted is skipped and listed in the [`MigrationReport`] instead of
+//! failing the whole import.
+
+use std::io;
+use std::path::Path;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::files::{self, SECTIONS};
+
+/// Newest IndexedDB schema version the importer knows.
+const LATEST_DB_VERSION: u32 = 3;
+
+/// Key of the single record in each non-asset store.
+const CURRENT: &str = "current";
+
+/// Mirrors `detectFormat` in `assets/asset-import.ts`.
+const IMAGE_EXTENSIONS: [(&str, &str); 6] = [
+    (".png", "png"),
+    (".svg", "svg"),
+    (".webp", "webp"),
+    (".gif", "gif"),
+    (".jpg", "jpeg"),
+    (".jpeg", "jpeg"),
+];
+
+/// Asset format of a file name, by extension: `png`, `jpeg`… or `unknown`.
+pub fn image_format(name: &str) -> &'static str {
+    let lower = name.to_lowercase();
+    IMAGE_EXTENSIONS
+        .iter()
+        .find(|(ext, _)| lower.ends_with(ext))
+        .map_or("unknown", |(_, format)| format)
+}
+
+/// The webview's dump of the database.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dump {
+    pub db_version: u32,
+    /// Store name → its `{ key, value }` records.
+    pub stores: Map<String, Value>,
+}
+
+/// One record of a store.
+#[derive(Debug, Deserialize)]
+struct Entry {
+    key: Value,
+    value: Value,
+}
+
+/// Something the importer skipped or changed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Issue {
+    pub store: String,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+/// What an import wrote and what it could not convert.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub db_version: u32,
+    /// Stores written as `<store>.json`.
+    pub sections: Vec<String>,
+    /// Assets written under `assets/`.
+    pub assets: usize,
+    pub issues: Vec<Issue>,
+}
+
+impl MigrationReport {
+    fn issue(&mut self, store: &str, key: Option<&str>, message: impl Into<String>) {
+        self.issues.push(Issue {
+            store: store.to_string(),
+            key: key.map(String::from),
+            message: message.into(),
+        });
+    }
+}
+
+fn key_text(key: &Value) -> String {
+    key.as_str().map_or_else(|| key.to_string(), String::from)
+}
+
+/// Write `dump` into the project folder `dir`, which must not hold a scene.
+pub fn import(dir: &Path, dump: Dump) -> io::Result<MigrationReport> {
+    if files::read_section(dir, "scene")?.is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already contains a project", dir.display()),
+        ));
+    }
+
+    let mut report = MigrationReport {
+        db_version: dump.db_version,
+        ..MigrationReport::default()
+    };
+    if dump.db_version > LATEST_DB_VERSION {
+        report.issue(
+            "",
+            None,
+            format!(
+                "database version {} is newer than {LATEST_DB_VERSION}; imported what was recognised",
+                dump.db_version
+            ),
+        );
+    }
+
+    for (store, records) in dump.stores {
+        let entries = match serde_json::from_value::<Vec<Entry>>(records) {
+            Ok(entries) => entries,
+            Err(e) => {
+                report.issue(&store, None, format!("unreadable store: {e}"));
+                continue;
+            }
+        };
+        if store == "assets" {
+            for entry in entries {
+                import_asset(dir, entry, &mut report)?;
+            }
+        } else if SECTIONS.contains(&store.as_str()) {
+            import_section(dir, &store, entries, &mut report)?;
+        } else if !entries.is_empty() {
+            report.issue(&store, None, "unknown store, not imported");
+        }
+    }
+
+    if !report.sections.iter().any(|s| s == "scene") {
+        report.issue(
+            "scene",
+            None,
+            "no scene was saved; the project starts empty",
+        );
+    }
+    Ok(report)
+}
+
+fn import_section(
+    dir: &Path,
+    store: &str,
+    entries: Vec<Entry>,
+    report: &mut MigrationReport,
+) -> io::Result<()> {
+    for Entry { key, value } in entries {
+        let key = key_text(&key);
+        if key != CURRENT {
+            report.issue(store, Some(&key), "unexpected record, not imported");
+            continue;
+        }
+        let record = if files::is_envelope(&value) {
+            value
+        } else {
+            report.issue(
+                store,
+                Some(&key),
+                "record had no { version, data } envelope; wrapped as version 1",
+            );
+            json!({ "version": 1, "data": value })
+        };
+        files::write_section(dir, store, &record)?;
+        report.sections.push(store.to_string());
+    }
+    Ok(())
+}
+
+fn import_asset(dir: &Path, entry: Entry, report: &mut MigrationReport) -> io::Result<()> {
+    let key = key_text(&entry.key);
+    let Value::Object(mut meta) = entry.value else {
+        report.issue("assets", Some(&key), "not an asset record");
+        return Ok(());
+    };
+
+    let buffer = meta.remove("buffer");
+    let Some(bytes) = buffer
+        .as_ref()
+        .and_then(Value::as_str)
+        .and_then(|b| base64::engine::general_purpose::STANDARD.decode(b).ok())
+    else {
+        report.issue("assets", Some(&key), "missing or unreadable file data");
+        return Ok(());
+    };
+
+    let path = meta
+        .get("path")
+        .and_then(Value::as_str)
+        .unwrap_or(&key)
+        .to_string();
+    meta.insert("path".into(), path.clone().into());
+    let name = match meta.get("name").and_then(Value::as_str) {
+        Some(name) => name.to_string(),
+        None => {
+            let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+            meta.insert("name".into(), name.clone().into());
+            name
+        }
+    };
+    if !meta.get("category").is_some_and(Value::is_string) {
+        meta.insert("category".into(), "".into());
+    }
+    let detected = (!meta.get("format").is_some_and(Value::is_string)).then(|| image_format(&name));
+    if let Some(format) = detected {
+        meta.insert("format".into(), format.into());
+    }
+
+    match files::write_asset(dir, meta, &bytes) {
+        Ok(()) => report.assets += 1,
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {
+            report.issue("assets", Some(&key), e.to_string());
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    }
+    if let Some(format) = detected {
+        report.issue(
+            "assets",
+            Some(&key),
+            format!("no format recorded; detected `{format}` from the name"),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_stores_and_reports_what_it_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let png = base64::engine::general_purpose::STANDARD.encode(b"\x89PNG");
+        let dump: Dump = serde_json::from_value(json!({
+            "dbVersion": 1,
+            "stores": {
+                "scene": [{ "key": "current", "value": { "version": 1, "data": { "mode": "run" } } }],
+                "entities": [{ "key": "current", "value": { "peon": { "id": "peon" } } }],
+                "timeline": [{ "key": "other", "value": {} }],
+                "assets": [
+                    { "key": "units/peon.png", "value": { "path": "units/peon.png", "name": "peon.png", "category": "units", "format": "png", "buffer": png } },
+                    { "key": "tiles/grass.JPG", "value": { "path": "tiles/grass.JPG", "buffer": png } },
+                    { "key": "../evil.png", "value": { "path": "../evil.png", "buffer": png } },
+                    { "key": "broken.png", "value": { "path": "broken.png" } },
+                ],
+                "legacy": [{ "key": "current", "value": {} }],
+            },
+        }))
+        .unwrap();
+
+        let report = import(dir, dump).unwrap();
+        assert_eq!(report.sections, ["scene", "entities"]);
+        assert_eq!(report.assets, 2);
+        let skipped: Vec<_> = report
+            .issues
+            .iter()
+            .map(|i| (i.store.as_str(), i.key.as_deref()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                ("entities", Some("current")),
+                ("timeline", Some("other")),
+                ("assets", Some("tiles/grass.JPG")),
+                ("assets", Some("../evil.png")),
+                ("assets", Some("broken.png")),
+                ("legacy", None),
+            ]
+        );
+
+        assert_eq!(
+            files::read_section(dir, "entities").unwrap(),
+            Some(json!({ "version": 1, "data": { "peon": { "id": "peon" } } }))
+        );
+        let assets = files::read_assets(dir).unwrap();
+        assert_eq!(assets[1].meta["format"], "jpeg");
+        assert_eq!(assets[1].meta["name"], "grass.JPG");
+        assert_eq!(assets[1].bytes, b"\x89PNG");
+
+        let again = Dump {
+            db_version: 3,
+            stores: Map::new(),
+        };
+        assert!(import(dir, again).is_err());
+    }
+}
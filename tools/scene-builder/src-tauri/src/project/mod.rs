@@ -0,0 +1,236 @@
+//! Project folders — scene persistence on disk for the desktop app.
+//!
+//! Replaces the `sajou-scene-builder` IndexedDB when running under Tauri:
+//! `persistence.ts` reads and writes the stores of the open project folder
+//! through these commands (layout in [`files`]). The folder is plain JSON
+//! plus asset files, so it can live in git. Until another folder is opened
+//! the project is `projects/default` in the app data dir; the last opened
+//! folder is remembered in `project.json` in the app config dir.
+//!
+//! Editor preferences and remote sources (which carry API keys) stay in
+//! the webview's localStorage.
+
+pub mod document;
+pub mod files;
+pub mod journal;
+pub mod migrate;
+pub mod snapshots;
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use base64::Engine;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager, State};
+
+use document::{Closing, Document};
+
+/// Remembers the last opened folder and document, in the app config dir.
+const SETTINGS_FILE: &str = "project.json";
+
+/// The open project folder.
+pub struct Project {
+    dir: Mutex<PathBuf>,
+    /// Held while the asset index is rewritten.
+    assets: Mutex<()>,
+    /// Where the open folder is remembered.
+    settings: Option<PathBuf>,
+    /// The `.sajou` document the folder was opened from or saved to.
+    document: Mutex<Document>,
+    /// Recent documents list.
+    recents: Option<PathBuf>,
+    closing: Closing,
+}
+
+impl Project {
+    /// The last opened folder, or `projects/default` in the app data dir.
+    pub fn for_app(app: &AppHandle) -> Self {
+        let config = app.path().app_config_dir().ok();
+        let settings = config.as_ref().map(|d| d.join(SETTINGS_FILE));
+        let remembered = settings
+            .as_deref()
+            .and_then(|p| files::read_json(p).ok().flatten())
+            .unwrap_or_default();
+        let dir = remembered["dir"]
+            .as_str()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                app.path()
+                    .app_data_dir()
+                    .unwrap_or_default()
+                    .join("projects")
+                    .join("default")
+            });
+        let document = Document {
+            path: remembered["document"].as_str().map(PathBuf::from),
+            dirty: remembered["dirty"].as_bool().unwrap_or(false),
+        };
+        Self {
+            dir: Mutex::new(dir),
+            assets: Mutex::new(()),
+            settings,
+            document: Mutex::new(document),
+            recents: config.map(|d| d.join(document::RECENTS_FILE)),
+            closing: Closing::default(),
+        }
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        self.dir.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn document(&self) -> Document {
+        self.document
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Make `dir` the open project, creating it if needed.
+    pub fn open(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        *self.dir.lock().unwrap_or_else(|e| e.into_inner()) = dir.to_path_buf();
+        self.remember();
+        Ok(())
+    }
+
+    /// Persist the open folder and document for the next launch.
+    fn remember(&self) {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        let doc = self.document();
+        let record = json!({ "dir": self.dir(), "document": doc.path, "dirty": doc.dirty });
+        if let Err(e) = files::write_json(settings, &record) {
+            eprintln!("[sajou] cannot save {}: {e}", settings.display());
+        }
+    }
+
+    fn add_recent(&self, manifest: &Path) {
+        if let Some(recents) = &self.recents {
+            if let Err(e) = document::add_recent(recents, manifest) {
+                eprintln!("[sajou] cannot save {}: {e}", recents.display());
+            }
+        }
+    }
+}
+
+fn base64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+//
+// File I/O runs off the main thread (`async`), assets can be large.
+
+/// The open project folder.
+#[tauri::command]
+pub fn project_dir(project: State<'_, Project>) -> PathBuf {
+    project.dir()
+}
+
+/// Switch to the project folder `dir` (created if missing).
+#[tauri::command(async)]
+pub fn project_open_dir(project: State<'_, Project>, dir: PathBuf) -> Result<PathBuf, String> {
+    project.open(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// The `{ version, data }` envelope of a store, or `null` if never saved.
+#[tauri::command(async)]
+pub fn project_read(project: State<'_, Project>, section: String) -> Result<Option<Value>, String> {
+    files::read_section(&project.dir(), &section).map_err(|e| e.to_string())
+}
+
+/// Save the `{ version, data }` envelope of a store. Rewriting what is
+/// already there (e.g. after a reload) leaves the document clean.
+#[tauri::command(async)]
+pub fn project_write(
+    app: AppHandle,
+    project: State<'_, Project>,
+    section: String,
+    record: Value,
+) -> Result<(), String> {
+    let dir = project.dir();
+    if files::read_section(&dir, &section).ok().flatten().as_ref() == Some(&record) {
+        return Ok(());
+    }
+    files::write_section(&dir, &section, &record).map_err(|e| e.to_string())?;
+    document::mark_dirty(&app);
+    Ok(())
+}
+
+/// Paths of the saved assets.
+#[tauri::command(async)]
+pub fn project_asset_keys(project: State<'_, Project>) -> Result<Vec<String>, String> {
+    files::asset_keys(&project.dir()).map_err(|e| e.to_string())
+}
+
+/// Save an asset: its metadata (with `path`) and base64 `data`.
+#[tauri::command(async)]
+pub fn project_write_asset(
+    app: AppHandle,
+    project: State<'_, Project>,
+    asset: Map<String, Value>,
+    data: String,
+) -> Result<(), String> {
+    let bytes = base64().decode(data).map_err(|e| e.to_string())?;
+    {
+        let _guard = project.assets.lock().unwrap_or_else(|e| e.into_inner());
+        files::write_asset(&project.dir(), asset, &bytes).map_err(|e| e.to_string())?;
+    }
+    document::mark_dirty(&app);
+    Ok(())
+}
+
+/// Every saved asset: its metadata plus base64 `data`.
+#[tauri::command(async)]
+pub fn project_read_assets(project: State<'_, Project>) -> Result<Vec<Value>, String> {
+    let assets = files::read_assets(&project.dir()).map_err(|e| e.to_string())?;
+    Ok(assets
+        .into_iter()
+        .map(|asset| {
+            let mut record = asset.meta;
+            record.insert("data".into(), base64().encode(asset.bytes).into());
+            Value::Object(record)
+        })
+        .collect())
+}
+
+/// Import the webview's IndexedDB dump into `dir` (default: the open
+/// project), then open it. Refused when the folder already holds a scene.
+#[tauri::command(async)]
+pub fn project_import_indexeddb(
+    app: AppHandle,
+    project: State<'_, Project>,
+    dir: Option<PathBuf>,
+    dump: migrate::Dump,
+) -> Result<migrate::MigrationReport, String> {
+    let dir = dir.unwrap_or_else(|| project.dir());
+    let report = {
+        let _guard = project.assets.lock().unwrap_or_else(|e| e.into_inner());
+        migrate::import(&dir, dump).map_err(|e| e.to_string())?
+    };
+    project.open(&dir).map_err(|e| e.to_string())?;
+    eprintln!(
+        "[sajou] imported IndexedDB v{} into {}: {} sections, {} assets, {} issues",
+        report.db_version,
+        dir.display(),
+        report.sections.len(),
+        report.assets,
+        report.issues.len()
+    );
+    document::mark_dirty(&app);
+    Ok(report)
+}
+
+/// Remove every store and asset from the project folder ("New Scene").
+#[tauri::command(async)]
+pub fn project_clear(app: AppHandle, project: State<'_, Project>) -> Result<(), String> {
+    document::keep_untitled(&app).map_err(|e| format!("cannot snapshot unsaved work: {e}"))?;
+    files::clear(&project.dir()).map_err(|e| e.to_string())?;
+    document::mark_dirty(&app);
+    Ok(())
+}
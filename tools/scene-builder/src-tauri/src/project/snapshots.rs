@@ -0,0 +1,654 @@
+//! Autosave snapshots — on-disk version history of the working folder.
+//!
+//! Every `intervalSecs` the stores and asset index of the working folder
+//! are written as one JSON snapshot, unless their content hash matches the
+//! latest one. Asset bytes go to a shared content-addressed `blobs/` dir,
+//! so unchanged assets cost nothing per snapshot. Only the newest `count`
+//! snapshots are kept. History is per document:
+//!
+//! ```text
+//! <app data>/history/<document id | untitled>/snapshots/<createdAt>-<hash>.json
+//! <app data>/history/<document id | untitled>/blobs/<sha256>
+//! ```
+//!
+//! Settings live in `snapshots.json` in the app config dir.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, State};
+
+use super::files::{self, SECTIONS};
+use super::{document, journal, Project};
+use crate::signals::now_ms;
+
+/// Snapshot settings, in the app config dir.
+const SETTINGS_FILE: &str = "snapshots.json";
+
+/// Shortest interval between snapshots.
+const MIN_INTERVAL_SECS: u64 = 10;
+
+/// At most this many changes are listed by a diff.
+const MAX_CHANGES: usize = 200;
+
+/// How many snapshots to keep and how often to take them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub count: usize,
+    pub interval_secs: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            count: 20,
+            interval_secs: 300,
+        }
+    }
+}
+
+/// A snapshot as listed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub created_at: u64,
+    pub hash: String,
+    /// Stores it holds.
+    pub sections: Vec<String>,
+    pub assets: usize,
+}
+
+/// One difference between two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    /// A store, or `assets`.
+    pub section: String,
+    /// JSON pointer inside the store's `data`, or the asset path.
+    pub path: String,
+    /// `added`, `removed` or `changed`.
+    pub kind: &'static str,
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// `value` with object keys sorted, so equal content hashes equally.
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<_, _> = map.iter().collect();
+            Value::Object(
+                sorted
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), canonical(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        other => other.clone(),
+    }
+}
+
+fn snapshot_dir(history: &Path) -> PathBuf {
+    history.join("snapshots")
+}
+
+/// Asset hashes keyed on (path, modified time, length), so a capture only
+/// rereads the assets that changed since the previous one.
+#[derive(Default)]
+pub struct AssetHashes(Mutex<HashMap<PathBuf, (SystemTime, u64, String)>>);
+
+impl AssetHashes {
+    /// SHA-256 of the file at `path`, reread only when it changed.
+    fn hash(&self, path: &Path) -> io::Result<String> {
+        let meta = fs::metadata(path)?;
+        let (modified, len) = (meta.modified()?, meta.len());
+        let mut cache = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match cache.get(path) {
+            Some((m, l, hash)) if (*m, *l) == (modified, len) => Ok(hash.clone()),
+            _ => {
+                let hash = sha256(&fs::read(path)?);
+                cache.insert(path.to_path_buf(), (modified, len, hash.clone()));
+                Ok(hash)
+            }
+        }
+    }
+}
+
+fn blob_dir(history: &Path) -> PathBuf {
+    history.join("blobs")
+}
+
+/// The history folder of a document (or of the untitled one).
+pub fn history_dir(root: &Path, manifest: Option<&Path>) -> PathBuf {
+    let id = manifest.map_or_else(
+        || "untitled".to_string(),
+        |path| sha256(path.to_string_lossy().as_bytes())[..16].to_string(),
+    );
+    root.join(id)
+}
+
+// ---------------------------------------------------------------------------
+// Taking and reading snapshots
+// ---------------------------------------------------------------------------
+
+/// Stores and assets of `working` as snapshot content. Asset bytes are saved
+/// as blobs of `history` when given, else only hashed.
+fn capture(working: &Path, history: Option<&Path>, hashes: &AssetHashes) -> io::Result<Value> {
+    let mut sections = Map::new();
+    for section in SECTIONS {
+        if let Some(record) = files::read_section(working, section)? {
+            sections.insert(section.to_string(), record);
+        }
+    }
+    let mut assets = Vec::new();
+    for mut meta in files::asset_index(working)? {
+        let Some(key) = meta.get("path").and_then(Value::as_str) else {
+            continue;
+        };
+        let file = files::asset_file(working, key)?;
+        let hash = match hashes.hash(&file) {
+            Ok(hash) => hash,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                eprintln!("[sajou] project asset missing: {key}");
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(history) = history {
+            let blob = blob_dir(history).join(&hash);
+            if !blob.exists() {
+                files::write_atomic(&blob, &fs::read(&file)?)?;
+            }
+        }
+        meta.insert("blob".into(), hash.into());
+        assets.push(Value::Object(meta));
+    }
+    Ok(json!({ "sections": sections, "assets": assets }))
+}
+
+fn read_snapshot(history: &Path, id: &str) -> io::Result<Value> {
+    let name = format!("{id}.json");
+    if Path::new(&name).components().count() != 1 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid snapshot id",
+        ));
+    }
+    files::read_json(&snapshot_dir(history).join(name))?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no snapshot {id}")))
+}
+
+fn info(id: String, snapshot: &Value) -> SnapshotInfo {
+    SnapshotInfo {
+        id,
+        created_at: snapshot["createdAt"].as_u64().unwrap_or(0),
+        hash: snapshot["hash"].as_str().unwrap_or_default().to_string(),
+        sections: snapshot["sections"]
+            .as_object()
+            .map(|s| s.keys().cloned().collect())
+            .unwrap_or_default(),
+        assets: snapshot["assets"].as_array().map_or(0, Vec::len),
+    }
+}
+
+/// Snapshots in `history`, newest first.
+pub fn list(history: &Path) -> io::Result<Vec<SnapshotInfo>> {
+    let entries = match fs::read_dir(snapshot_dir(history)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut ids: Vec<String> = entries
+        .filter_map(|e| e.ok()?.file_name().into_string().ok())
+        .filter_map(|name| name.strip_suffix(".json").map(String::from))
+        .collect();
+    // Ids start with the zero-padded creation time.
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    let mut snapshots = Vec::new();
+    for id in ids {
+        match read_snapshot(history, &id) {
+            Ok(snapshot) => snapshots.push(info(id, &snapshot)),
+            Err(e) => eprintln!("[sajou] skipping snapshot {id}: {e}"),
+        }
+    }
+    Ok(snapshots)
+}
+
+/// Snapshot `working` into `history`, keeping the newest `keep`. `None`
+/// when the folder is empty or unchanged since the latest snapshot.
+pub fn take(
+    working: &Path,
+    history: &Path,
+    keep: usize,
+    hashes: &AssetHashes,
+) -> io::Result<Option<SnapshotInfo>> {
+    let content = capture(working, Some(history), hashes)?;
+    let empty = |key: &str| match &content[key] {
+        Value::Object(map) => map.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => true,
+    };
+    if empty("sections") && empty("assets") {
+        return Ok(None);
+    }
+    let hash = sha256(&serde_json::to_vec(&canonical(&content))?);
+    let existing = list(history)?;
+    if existing.first().is_some_and(|latest| latest.hash == hash) {
+        return Ok(None);
+    }
+
+    // Strictly increasing, so ids sort by age even within a millisecond.
+    let created_at = now_ms().max(existing.first().map_or(0, |s| s.created_at + 1));
+    let id = format!("{created_at:015}-{}", &hash[..8]);
+    let mut snapshot = json!({ "version": 1, "createdAt": created_at, "hash": hash });
+    if let (Value::Object(snapshot), Value::Object(content)) = (&mut snapshot, content) {
+        snapshot.extend(content);
+    }
+    files::write_json(&snapshot_dir(history).join(format!("{id}.json")), &snapshot)?;
+
+    for old in existing.iter().skip(keep.max(1).saturating_sub(1)) {
+        fs::remove_file(snapshot_dir(history).join(format!("{}.json", old.id)))?;
+    }
+    collect_blobs(history)?;
+    Ok(Some(info(id, &snapshot)))
+}
+
+/// Delete blobs no snapshot refers to.
+fn collect_blobs(history: &Path) -> io::Result<()> {
+    let mut used = HashSet::new();
+    for snapshot in list(history)? {
+        let snapshot = read_snapshot(history, &snapshot.id)?;
+        for asset in snapshot["assets"].as_array().into_iter().flatten() {
+            if let Some(blob) = asset["blob"].as_str() {
+                used.insert(blob.to_string());
+            }
+        }
+    }
+    let Ok(blobs) = fs::read_dir(blob_dir(history)) else {
+        return Ok(());
+    };
+    for blob in blobs {
+        let blob = blob?;
+        if !used.contains(blob.file_name().to_string_lossy().as_ref()) {
+            fs::remove_file(blob.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Replace the working folder with snapshot `id`.
+pub fn restore(history: &Path, id: &str, working: &Path) -> io::Result<()> {
+    let snapshot = read_snapshot(history, id)?;
+    files::clear(working)?;
+    for (section, record) in snapshot["sections"].as_object().into_iter().flatten() {
+        files::write_section(working, section, record)?;
+    }
+    for asset in snapshot["assets"].as_array().into_iter().flatten() {
+        let Value::Object(mut meta) = asset.clone() else {
+            continue;
+        };
+        let blob = meta.remove("blob");
+        let Some(blob) = blob.as_ref().and_then(Value::as_str) else {
+            continue;
+        };
+        let bytes = fs::read(blob_dir(history).join(blob))?;
+        files::write_asset(working, meta, &bytes)?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Diff
+// ---------------------------------------------------------------------------
+
+fn push(out: &mut Vec<Change>, section: &str, path: String, kind: &'static str) {
+    if out.len() < MAX_CHANGES {
+        out.push(Change {
+            section: section.to_string(),
+            path,
+            kind,
+        });
+    }
+}
+
+fn diff_values(section: &str, path: &str, a: &Value, b: &Value, out: &mut Vec<Change>) {
+    if out.len() >= MAX_CHANGES || a == b {
+        return;
+    }
+    let key = |k: &str| format!("{path}/{}", k.replace('~', "~0").replace('/', "~1"));
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (k, va) in a {
+                match b.get(k) {
+                    Some(vb) => diff_values(section, &key(k), va, vb, out),
+                    None => push(out, section, key(k), "removed"),
+                }
+            }
+            for k in b.keys().filter(|k| !a.contains_key(*k)) {
+                push(out, section, key(k), "added");
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let path = format!("{path}/{i}");
+                match (a.get(i), b.get(i)) {
+                    (Some(va), Some(vb)) => diff_values(section, &path, va, vb, out),
+                    (Some(_), None) => push(out, section, path, "removed"),
+                    (None, Some(_)) => push(out, section, path, "added"),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => push(out, section, path.to_string(), "changed"),
+    }
+}
+
+fn asset_blobs(snapshot: &Value) -> BTreeMap<String, Value> {
+    snapshot["assets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|a| Some((a["path"].as_str()?.to_string(), a.clone())))
+        .collect()
+}
+
+/// What changed from snapshot content `a` to `b` (at most 200 changes).
+fn diff(a: &Value, b: &Value) -> Vec<Change> {
+    let mut out = Vec::new();
+    for section in SECTIONS {
+        let (va, vb) = (&a["sections"][section], &b["sections"][section]);
+        match (va.is_null(), vb.is_null()) {
+            (true, true) => {}
+            (false, true) => push(&mut out, section, String::new(), "removed"),
+            (true, false) => push(&mut out, section, String::new(), "added"),
+            (false, false) => diff_values(section, "", &va["data"], &vb["data"], &mut out),
+        }
+    }
+    let (aa, ab) = (asset_blobs(a), asset_blobs(b));
+    let paths: std::collections::BTreeSet<_> = aa.keys().chain(ab.keys()).collect();
+    for path in paths {
+        let kind = match (aa.get(path), ab.get(path)) {
+            (Some(x), Some(y)) if x == y => continue,
+            (Some(_), Some(_)) => "changed",
+            (Some(_), None) => "removed",
+            _ => "added",
+        };
+        push(&mut out, "assets", path.clone(), kind);
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tauri state
+// ---------------------------------------------------------------------------
+
+/// Snapshot settings and the history root.
+pub struct Snapshots {
+    root: PathBuf,
+    path: Option<PathBuf>,
+    settings: Mutex<Settings>,
+    /// Held while a snapshot is taken or restored.
+    busy: Mutex<()>,
+    hashes: AssetHashes,
+}
+
+impl Snapshots {
+    pub fn for_app(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|d| d.join(SETTINGS_FILE));
+        let settings = path
+            .as_deref()
+            .and_then(|p| files::read_json(p).ok().flatten())
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        Self {
+            root: app
+                .path()
+                .app_data_dir()
+                .unwrap_or_default()
+                .join("history"),
+            path,
+            settings: Mutex::new(settings),
+            busy: Mutex::new(()),
+            hashes: AssetHashes::default(),
+        }
+    }
+
+    pub fn settings(&self) -> Settings {
+        *self.settings.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// History folder of the open document.
+    fn history(&self, project: &Project) -> PathBuf {
+        history_dir(&self.root, project.document().path.as_deref())
+    }
+
+    /// Snapshot the working folder now.
+    pub fn take(&self, project: &Project) -> io::Result<Option<SnapshotInfo>> {
+        let _busy = self.busy.lock().unwrap_or_else(|e| e.into_inner());
+        let _assets = project.assets.lock().unwrap_or_else(|e| e.into_inner());
+        take(
+            &project.dir(),
+            &self.history(project),
+            self.settings().count,
+            &self.hashes,
+        )
+    }
+}
+
+/// Take snapshots in the background for as long as the app runs.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let interval = app.state::<Snapshots>().settings().interval_secs;
+            tokio::time::sleep(Duration::from_secs(interval.max(MIN_INTERVAL_SECS))).await;
+            let app = app.clone();
+            let taken = tauri::async_runtime::spawn_blocking(move || {
+                app.state::<Snapshots>().take(&app.state::<Project>())
+            })
+            .await;
+            match taken {
+                Ok(Ok(Some(snapshot))) => eprintln!("[sajou] snapshot {}", snapshot.id),
+                Ok(Err(e)) => eprintln!("[sajou] snapshot failed: {e}"),
+                _ => {}
+            }
+        }
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// How many snapshots are kept and how often they are taken.
+#[tauri::command]
+pub fn project_snapshot_settings(snapshots: State<'_, Snapshots>) -> Settings {
+    snapshots.settings()
+}
+
+/// Change the snapshot settings; persisted for the next launch.
+#[tauri::command]
+pub fn set_project_snapshot_settings(
+    snapshots: State<'_, Snapshots>,
+    settings: Settings,
+) -> Result<(), String> {
+    if settings.count == 0 {
+        return Err("count must be at least 1".into());
+    }
+    if settings.interval_secs < MIN_INTERVAL_SECS {
+        return Err(format!("intervalSecs must be at least {MIN_INTERVAL_SECS}"));
+    }
+    if let Some(path) = &snapshots.path {
+        let value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+        files::write_json(path, &value).map_err(|e| e.to_string())?;
+    }
+    *snapshots.settings.lock().unwrap_or_else(|e| e.into_inner()) = settings;
+    Ok(())
+}
+
+/// Snapshots of the open document, newest first.
+#[tauri::command(async)]
+pub fn project_snapshots(
+    project: State<'_, Project>,
+    snapshots: State<'_, Snapshots>,
+) -> Result<Vec<SnapshotInfo>, String> {
+    list(&snapshots.history(&project)).map_err(|e| e.to_string())
+}
+
+/// Snapshot the working folder now. `null` when nothing changed.
+#[tauri::command(async)]
+pub fn project_snapshot_now(
+    project: State<'_, Project>,
+    snapshots: State<'_, Snapshots>,
+) -> Result<Option<SnapshotInfo>, String> {
+    snapshots.take(&project).map_err(|e| e.to_string())
+}
+
+/// Changes from snapshot `from` to snapshot `to`, or to the working folder.
+#[tauri::command(async)]
+pub fn project_snapshot_diff(
+    project: State<'_, Project>,
+    snapshots: State<'_, Snapshots>,
+    from: String,
+    to: Option<String>,
+) -> Result<Vec<Change>, String> {
+    let history = snapshots.history(&project);
+    let a = read_snapshot(&history, &from).map_err(|e| e.to_string())?;
+    let b = match to {
+        Some(to) => read_snapshot(&history, &to),
+        // Hashed only: blobs are written under `busy`, by `take`.
+        None => capture(&project.dir(), None, &snapshots.hashes),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(diff(&a, &b))
+}
+
+/// Restore snapshot `id` into the working folder, snapshotting the current
+/// state first so the restore can be undone. The webview then reloads.
+#[tauri::command(async)]
+pub fn project_snapshot_restore(
+    app: AppHandle,
+    project: State<'_, Project>,
+    snapshots: State<'_, Snapshots>,
+    id: String,
+) -> Result<Option<SnapshotInfo>, String> {
+    let before = snapshots.take(&project).map_err(|e| e.to_string())?;
+    {
+        let _busy = snapshots.busy.lock().unwrap_or_else(|e| e.into_inner());
+        let _assets = project.assets.lock().unwrap_or_else(|e| e.into_inner());
+        restore(&snapshots.history(&project), &id, &project.dir())
+            .map_err(|e| format!("cannot restore snapshot {id}: {e}"))?;
+    }
+    journal::clear(&app);
+    document::mark_dirty(&app);
+    eprintln!("[sajou] restored snapshot {id}");
+    Ok(before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_dedups_diffs_and_restores() {
+        let root = tempfile::tempdir().unwrap();
+        let working = root.path().join("working");
+        let history = history_dir(root.path(), None);
+        let hashes = AssetHashes::default();
+        assert_eq!(take(&working, &history, 2, &hashes).unwrap(), None);
+
+        let scene = |w: u64| json!({ "version": 1, "data": { "width": w, "layers": ["a"] } });
+        let peon = || json!({ "path": "peon.png" }).as_object().cloned().unwrap();
+        files::write_section(&working, "scene", &scene(960)).unwrap();
+        files::write_asset(&working, peon(), b"v1").unwrap();
+        let first = take(&working, &history, 2, &hashes).unwrap().unwrap();
+        assert_eq!(
+            take(&working, &history, 2, &hashes).unwrap(),
+            None,
+            "unchanged"
+        );
+
+        files::write_section(&working, "scene", &scene(1280)).unwrap();
+        files::write_asset(&working, peon(), b"v2").unwrap();
+        files::write_section(&working, "wires", &json!({ "version": 1, "data": {} })).unwrap();
+        let second = take(&working, &history, 2, &hashes).unwrap().unwrap();
+
+        let a = read_snapshot(&history, &first.id).unwrap();
+        let b = read_snapshot(&history, &second.id).unwrap();
+        let changes: Vec<_> = diff(&a, &b)
+            .into_iter()
+            .map(|c| (c.kind, c.section, c.path))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("changed", "scene".into(), "/width".into()),
+                ("added", "wires".into(), String::new()),
+                ("changed", "assets".into(), "peon.png".into()),
+            ]
+        );
+
+        restore(&history, &first.id, &working).unwrap();
+        assert_eq!(
+            files::read_section(&working, "scene").unwrap(),
+            Some(scene(960))
+        );
+        assert_eq!(files::read_section(&working, "wires").unwrap(), None);
+        assert_eq!(files::read_assets(&working).unwrap()[0].bytes, b"v1");
+
+        // Diffing against the working folder writes no blobs.
+        files::write_asset(&working, peon(), b"v3").unwrap();
+        let current = capture(&working, None, &hashes).unwrap();
+        assert_eq!(diff(&a, &current).len(), 1);
+        assert_eq!(fs::read_dir(blob_dir(&history)).unwrap().count(), 2);
+
+        // Rotation keeps two snapshots and drops the blobs of older ones.
+        files::write_section(&working, "scene", &scene(640)).unwrap();
+        take(&working, &history, 2, &hashes).unwrap().unwrap();
+        let ids: Vec<_> = list(&history).unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(!ids.contains(&first.id));
+        assert_eq!(fs::read_dir(blob_dir(&history)).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn asset_hashes_are_reused_until_the_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("peon.png");
+        let hashes = AssetHashes::default();
+        fs::write(&path, b"v1").unwrap();
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        assert_eq!(hashes.hash(&path).unwrap(), sha256(b"v1"));
+
+        // Same time and length: the cached hash stands.
+        fs::write(&path, b"v2").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        assert_eq!(hashes.hash(&path).unwrap(), sha256(b"v1"));
+
+        fs::write(&path, b"v3!").unwrap();
+        assert_eq!(hashes.hash(&path).unwrap(), sha256(b"v3!"));
+    }
+}
@@ -0,0 +1,271 @@
+//! `sajou scene …` — headless checks and packing of scene archives, for CI.
+//!
+//! ```text
+//! sajou scene validate <archive>...            exit 1 if any is invalid
+//! sajou scene info <archive> [--json]          contents and reference problems
+//! sajou scene pack <dir> [-o <archive>]        archive a project folder
+//! ```
+//!
+//! `validate` applies the import checks strictly: an invalid optional file,
+//! a broken reference or an asset missing from the archive all fail it.
+//! `pack` takes a project folder (or its `.sajou` manifest), writes the
+//! archive the editor would export with every section ticked, then
+//! validates it. Dispatched from `main` before Tauri starts, so no window
+//! is created.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use super::import::Archive;
+use super::info::{info, Info};
+use super::{export_to, Selection};
+
+const USAGE: &str = "usage: sajou scene validate <archive>...
+       sajou scene info <archive> [--json]
+       sajou scene pack <dir> [-o <archive>]";
+
+/// Default `pack` output, as the editor names exports.
+const PACK_FILE_NAME: &str = "scene-export.sajou";
+
+fn usage() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
+}
+
+/// Run `sajou scene <args>`.
+pub fn run(args: &[String]) -> ExitCode {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return usage(),
+    };
+    match (command, rest) {
+        ("validate", paths) if !paths.is_empty() => validate_all(paths),
+        ("info", [path]) => show_info(Path::new(path), false),
+        ("info", [path, flag]) | ("info", [flag, path]) if flag == "--json" => {
+            show_info(Path::new(path), true)
+        }
+        ("pack", [dir]) => pack(Path::new(dir), Path::new(PACK_FILE_NAME)),
+        ("pack", [dir, flag, out]) | ("pack", [flag, out, dir])
+            if flag == "-o" || flag == "--out" =>
+        {
+            pack(Path::new(dir), Path::new(out))
+        }
+        _ => usage(),
+    }
+}
+
+/// Open `path` and gather its info, or the error that refused it.
+fn inspect(path: &Path) -> Result<(Archive<File>, Info), String> {
+    let archive = File::open(path)
+        .and_then(Archive::open)
+        .map_err(|e| e.to_string())?;
+    let info = info(&archive);
+    Ok((archive, info))
+}
+
+/// Everything `validate` fails on.
+fn problems(archive: &Archive<File>, info: &Info) -> Vec<String> {
+    let missing = info
+        .missing_assets
+        .iter()
+        .map(|path| format!("missing asset: {path}"));
+    archive
+        .warnings
+        .iter()
+        .chain(&info.broken_references)
+        .cloned()
+        .chain(missing)
+        .collect()
+}
+
+/// Print the problems of `path`; true when there are none.
+fn validate(path: &Path) -> bool {
+    let problems = match inspect(path) {
+        Ok((archive, info)) => problems(&archive, &info),
+        Err(e) => e.lines().map(String::from).collect(),
+    };
+    if problems.is_empty() {
+        println!("ok {}", path.display());
+        return true;
+    }
+    println!("invalid {}", path.display());
+    for problem in problems {
+        println!("  {problem}");
+    }
+    false
+}
+
+fn validate_all(paths: &[String]) -> ExitCode {
+    // Every archive is checked, not just up to the first failure.
+    let invalid = paths
+        .iter()
+        .filter(|path| !validate(Path::new(path)))
+        .count();
+    if invalid == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn list(label: &str, items: &[String]) {
+    if items.is_empty() {
+        println!("{label}: none");
+        return;
+    }
+    println!("{label}:");
+    for item in items {
+        println!("  {item}");
+    }
+}
+
+fn show_info(path: &Path, json: bool) -> ExitCode {
+    let (archive, info) = match inspect(path) {
+        Ok(inspected) => inspected,
+        Err(e) => {
+            eprintln!("sajou: {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    if json {
+        match serde_json::to_string_pretty(&info) {
+            Ok(out) => println!("{out}"),
+            Err(e) => {
+                eprintln!("sajou: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let s = &info.summary;
+    println!("{}", path.display());
+    println!(
+        "  {} placements, {} entity definitions, {} assets",
+        s.entity_placements, s.entity_definitions, s.asset_files
+    );
+    println!(
+        "  {} choreographies, {} wires, {} bindings, {} shaders, {} sketches",
+        s.choreographies, s.wires, s.bindings, s.shaders, s.p5_sketches
+    );
+    list("entities", &info.entities);
+    let choreographies: Vec<String> = info
+        .choreographies
+        .iter()
+        .map(|c| format!("{} (on {}, {} steps)", c.id, c.on, c.steps))
+        .collect();
+    list("choreographies", &choreographies);
+    list("unreferenced assets", &info.unreferenced_assets);
+    list("missing assets", &info.missing_assets);
+    list("broken references", &info.broken_references);
+    list("skipped files", &archive.warnings);
+    ExitCode::SUCCESS
+}
+
+/// The project folder of `path`: itself, or the folder of a `.sajou` manifest.
+fn project_dir(path: &Path) -> PathBuf {
+    if path.is_file() {
+        path.parent().unwrap_or(Path::new(".")).to_path_buf()
+    } else {
+        path.to_path_buf()
+    }
+}
+
+fn pack(path: &Path, out: &Path) -> ExitCode {
+    let dir = project_dir(path);
+    if !dir.join("scene.json").is_file() {
+        eprintln!(
+            "sajou: {}: not a project folder (no scene.json)",
+            dir.display()
+        );
+        return ExitCode::FAILURE;
+    }
+    match export_to(&dir, &Selection::all(), out, |_| {}) {
+        Ok(report) => println!(
+            "packed {} entries ({} bytes) into {}",
+            report.entries.len(),
+            report.bytes,
+            out.display()
+        ),
+        Err(e) => {
+            eprintln!("sajou: {}: {e}", out.display());
+            return ExitCode::FAILURE;
+        }
+    }
+    if validate(out) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::files;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn packs_a_project_folder_and_reports_broken_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("demo");
+        let section = |name: &str, data: Value| {
+            files::write_section(&project, name, &json!({ "version": 1, "data": data })).unwrap();
+        };
+        section(
+            "scene",
+            json!({
+                "dimensions": { "width": 960, "height": 640 },
+                "layers": [{ "id": "base" }],
+                "entities": [
+                    { "id": "peon-01", "entityId": "peon", "x": 0, "y": 0, "layerId": "base" },
+                    { "id": "ghost-01", "entityId": "ghost", "x": 0, "y": 0, "layerId": "top" },
+                ],
+                "positions": [{ "id": "p1", "name": "forge", "x": 1, "y": 1 }],
+                "routes": [{ "id": "r1", "name": "road", "fromPositionId": "p1", "toPositionId": "p9",
+                             "points": [{ "x": 0, "y": 0 }, { "x": 1, "y": 1 }] }],
+            }),
+        );
+        section(
+            "entities",
+            json!({ "peon": { "visual": { "type": "sprite", "source": "sprites/peon.png" } } }),
+        );
+        section(
+            "choreographies",
+            json!({ "choreographies": [{ "id": "c1", "on": "task_dispatch", "steps": [
+                { "action": "move", "params": { "to": "forge" } },
+                { "action": "parallel", "params": {}, "children": [
+                    { "action": "move", "params": { "to": "mine" } },
+                    { "action": "followRoute", "params": { "route": "road" } },
+                ] },
+            ] }] }),
+        );
+        let meta = json!({ "path": "sprites/peon.png", "name": "peon.png" });
+        files::write_asset(&project, meta.as_object().unwrap().clone(), b"png").unwrap();
+
+        let out = dir.path().join("demo.sajou");
+        assert_eq!(pack(&project, &out), ExitCode::FAILURE);
+        // Written through a temp file, which is gone.
+        let mut names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["demo", "demo.sajou"]);
+
+        let (archive, info) = inspect(&out).unwrap();
+        assert_eq!(info.entities, ["peon"]);
+        assert_eq!(info.choreographies[0].steps, 4);
+        assert!(info.unreferenced_assets.is_empty());
+        assert_eq!(
+            problems(&archive, &info),
+            [
+                "scene.json /entities/1/entityId: no entity definition \"ghost\"",
+                "scene.json /entities/1/layerId: no layer \"top\"",
+                "scene.json /routes/0/toPositionId: no position with id \"p9\"",
+                "choreographies.json /choreographies/0/steps/1/children/0/params/to: no position named \"mine\"",
+            ]
+        );
+    }
+}
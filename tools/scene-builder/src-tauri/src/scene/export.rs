@@ -0,0 +1,428 @@
+//! Scene archive export.
+//!
+//! Builds the ZIP described in `docs/reference/scene-format.md` straight
+//! from the stores of a project folder, the same archive `export-scene.ts`
+//! builds with fflate in the browser. Assets are streamed from disk one at
+//! a time, so a large sprite pack never sits in memory.
+//!
+//! Only assets referenced by an entity (`visual.source` or a sound) are
+//! included, under `assets/sprites|spritesheets|gifs/<name>` by the visual
+//! type of the first entity using them; a name already taken gets a `-2`,
+//! `-3`… suffix. `entities.json` is rewritten to those ZIP-relative paths.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufReader, Seek, Write};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use super::zip::{Method, ZipWriter};
+use super::Selection;
+use crate::project::files;
+
+/// `scene.json` keys, in `SceneExportJson` order.
+const SCENE_KEYS: [&str; 10] = [
+    "dimensions",
+    "background",
+    "layers",
+    "entities",
+    "positions",
+    "routes",
+    "zoneTypes",
+    "zoneGrid",
+    "lighting",
+    "particles",
+];
+
+/// Extensions of formats that are already compressed; stored as-is.
+const COMPRESSED: [&str; 8] = ["png", "jpg", "jpeg", "gif", "webp", "mp3", "ogg", "m4a"];
+
+/// Sent after each archive entry is written.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Progress {
+    /// ZIP path of the entry just written.
+    pub entry: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// What went into the archive.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub entries: Vec<String>,
+    /// Uncompressed bytes written.
+    pub bytes: u64,
+    /// Referenced asset paths with no saved asset, left unrewritten.
+    pub missing_assets: Vec<String>,
+}
+
+/// Saved asset path → ZIP path, in archive order.
+type Mapping = Vec<(String, String)>;
+
+/// An archive entry, written in order.
+enum Entry {
+    Json(Value),
+    /// A saved asset, by its path in the project folder.
+    Asset(String),
+}
+
+/// The `data` of a saved store, or null.
+fn section(dir: &Path, name: &str) -> io::Result<Value> {
+    Ok(files::read_section(dir, name)?
+        .map(|mut record| record["data"].take())
+        .unwrap_or(Value::Null))
+}
+
+/// `data[key]` as an array, empty when absent.
+fn list(data: &Value, key: &str) -> Vec<Value> {
+    data[key].as_array().cloned().unwrap_or_default()
+}
+
+fn folder_for_visual_type(visual_type: &str) -> &'static str {
+    match visual_type {
+        "spritesheet" => "assets/spritesheets",
+        "gif" => "assets/gifs",
+        _ => "assets/sprites",
+    }
+}
+
+/// Referenced asset paths, in first-use order, each with the visual type of
+/// the first entity showing it (sounds have none).
+fn referenced_assets(entities: &Map<String, Value>) -> Vec<(String, Option<String>)> {
+    let mut referenced: Vec<(String, Option<String>)> = Vec::new();
+    for entity in entities.values() {
+        let visual = &entity["visual"];
+        let sounds = entity["sounds"]
+            .as_object()
+            .into_iter()
+            .flat_map(|s| s.values());
+        let source = visual["source"]
+            .as_str()
+            .map(|s| (s, visual["type"].as_str()));
+        let uses = source
+            .into_iter()
+            .chain(sounds.filter_map(Value::as_str).map(|s| (s, None)));
+        for (path, visual_type) in uses {
+            match referenced.iter_mut().find(|(p, _)| p == path) {
+                Some((_, known @ None)) => *known = visual_type.map(String::from),
+                Some(_) => {}
+                None => referenced.push((path.to_string(), visual_type.map(String::from))),
+            }
+        }
+    }
+    referenced
+}
+
+/// `folder/name`, or `folder/stem-N.ext` for the first free `N` from 2.
+fn unique_zip_path(folder: &str, name: &str, used: &mut HashSet<String>) -> String {
+    let (stem, ext) = match name.rfind('.') {
+        Some(dot) => name.split_at(dot),
+        None => (name, ""),
+    };
+    let mut zip_path = format!("{folder}/{name}");
+    let mut counter = 2;
+    while used.contains(&zip_path) {
+        zip_path = format!("{folder}/{stem}-{counter}{ext}");
+        counter += 1;
+    }
+    used.insert(zip_path.clone());
+    zip_path
+}
+
+/// File name of an asset in the archive: its `name`, or the last segment of
+/// its path when the name is missing or is itself a path.
+fn asset_name<'a>(meta: &'a Map<String, Value>, path: &'a str) -> &'a str {
+    meta.get("name")
+        .and_then(Value::as_str)
+        .filter(|n| !n.is_empty() && !n.contains(['/', '\\']))
+        .unwrap_or_else(|| path.rsplit(['/', '\\']).next().unwrap_or(path))
+}
+
+/// Map each referenced asset that is saved to its ZIP path. Also returns
+/// the referenced paths with no saved asset.
+fn asset_mapping(dir: &Path, entities: &Map<String, Value>) -> io::Result<(Mapping, Vec<String>)> {
+    let index = files::asset_index(dir)?;
+    let mut used = HashSet::new();
+    let mut mapping = Vec::new();
+    let mut missing = Vec::new();
+    for (path, visual_type) in referenced_assets(entities) {
+        let Some(meta) = index
+            .iter()
+            .find(|m| m.get("path").and_then(Value::as_str) == Some(path.as_str()))
+        else {
+            missing.push(path);
+            continue;
+        };
+        let folder = folder_for_visual_type(visual_type.as_deref().unwrap_or("sprite"));
+        let zip_path = unique_zip_path(folder, asset_name(meta, &path), &mut used);
+        mapping.push((path, zip_path));
+    }
+    Ok((mapping, missing))
+}
+
+/// Entity definitions with asset paths rewritten through `mapping`.
+fn rewrite_entity_paths(
+    entities: &Map<String, Value>,
+    mapping: &[(String, String)],
+) -> Map<String, Value> {
+    let lookup = |path: &Value| -> Option<Value> {
+        let path = path.as_str()?;
+        let (_, zip_path) = mapping.iter().find(|(p, _)| p == path)?;
+        Some(Value::String(zip_path.clone()))
+    };
+    let mut rewritten = entities.clone();
+    for entity in rewritten.values_mut() {
+        if let Some(source) = lookup(&entity["visual"]["source"]) {
+            entity["visual"]["source"] = source;
+        }
+        if let Some(sounds) = entity.get_mut("sounds").and_then(Value::as_object_mut) {
+            for sound in sounds.values_mut() {
+                if let Some(path) = lookup(sound) {
+                    *sound = path;
+                }
+            }
+        }
+    }
+    rewritten
+}
+
+/// What to write, in order.
+struct Plan {
+    entries: Vec<(String, Entry)>,
+    /// Referenced assets that are not saved.
+    missing: Vec<String>,
+}
+
+/// The archive entries for `selection`, from the stores saved in `dir`.
+fn plan(dir: &Path, selection: &Selection) -> io::Result<Plan> {
+    let mut entries = Vec::new();
+    let mut missing = Vec::new();
+
+    if selection.visual_layout {
+        let scene = section(dir, "scene")?;
+        let mut json = Map::new();
+        json.insert("version".into(), json!(1));
+        for key in SCENE_KEYS {
+            if let Some(value) = scene.get(key) {
+                json.insert(key.into(), value.clone());
+            }
+        }
+        entries.push(("scene.json".into(), Entry::Json(Value::Object(json))));
+    }
+
+    if selection.entities_and_assets {
+        let entities = match section(dir, "entities")? {
+            Value::Object(entities) => entities,
+            _ => Map::new(),
+        };
+        let (mapping, unsaved) = asset_mapping(dir, &entities)?;
+        missing = unsaved;
+        let rewritten = rewrite_entity_paths(&entities, &mapping);
+        entries.push((
+            "entities.json".into(),
+            Entry::Json(json!({ "version": 1, "entities": rewritten })),
+        ));
+        for (path, zip_path) in mapping {
+            entries.push((zip_path, Entry::Asset(path)));
+        }
+    }
+
+    if selection.choreographies_and_wiring {
+        // Signal → signal-type wires are dropped: sources are session-ephemeral.
+        let wires: Vec<Value> = list(&section(dir, "wires")?, "wires")
+            .into_iter()
+            .filter(|w| w["fromZone"] != "signal")
+            .collect();
+        let json = json!({
+            "version": 1,
+            "choreographies": list(&section(dir, "choreographies")?, "choreographies"),
+            "wires": wires,
+            "bindings": list(&section(dir, "bindings")?, "bindings"),
+        });
+        entries.push(("choreographies.json".into(), Entry::Json(json)));
+    }
+
+    if selection.shaders {
+        let shaders = list(&section(dir, "shaders")?, "shaders");
+        if !shaders.is_empty() {
+            let json = json!({ "version": 1, "shaders": shaders });
+            entries.push(("shaders.json".into(), Entry::Json(json)));
+        }
+    }
+
+    if selection.p5_sketches {
+        let sketches = list(&section(dir, "p5")?, "sketches");
+        if !sketches.is_empty() {
+            let json = json!({ "version": 1, "sketches": sketches });
+            entries.push(("p5.json".into(), Entry::Json(json)));
+        }
+    }
+
+    Ok(Plan { entries, missing })
+}
+
+fn method_for(zip_path: &str) -> Method {
+    let ext = zip_path
+        .rsplit_once('.')
+        .map(|(_, e)| e.to_ascii_lowercase());
+    match ext {
+        Some(ext) if COMPRESSED.contains(&ext.as_str()) => Method::Stored,
+        _ => Method::Deflated,
+    }
+}
+
+/// Write the scene archive of the project folder `dir` to `out`, calling
+/// `progress` after each entry.
+pub fn export<W: Write + Seek>(
+    dir: &Path,
+    selection: &Selection,
+    out: W,
+    mut progress: impl FnMut(Progress),
+) -> io::Result<Report> {
+    let Plan {
+        entries,
+        missing: missing_assets,
+    } = plan(dir, selection)?;
+    let total = entries.len();
+    let mut zip = ZipWriter::new(out);
+    let mut report = Report {
+        missing_assets,
+        ..Report::default()
+    };
+    for (done, (zip_path, entry)) in entries.into_iter().enumerate() {
+        report.bytes += match entry {
+            Entry::Json(value) => {
+                let bytes = serde_json::to_vec_pretty(&value).map_err(io::Error::other)?;
+                zip.add_bytes(&zip_path, Method::Deflated, &bytes)?
+            }
+            Entry::Asset(path) => {
+                let mut file = BufReader::new(File::open(files::asset_file(dir, &path)?)?);
+                zip.add(&zip_path, method_for(&zip_path), &mut file)?
+            }
+        };
+        report.entries.push(zip_path.clone());
+        progress(Progress {
+            entry: zip_path,
+            done: done + 1,
+            total,
+        });
+    }
+    zip.finish()?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn asset(dir: &Path, path: &str, name: &str) {
+        let meta = json!({ "path": path, "name": name, "category": "sprites" });
+        let Value::Object(meta) = meta else {
+            unreachable!()
+        };
+        files::write_asset(dir, meta, path.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn exports_referenced_assets_under_zip_relative_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let section = |name: &str, data: Value| {
+            files::write_section(dir, name, &json!({ "version": 1, "data": data })).unwrap();
+        };
+        section(
+            "scene",
+            json!({ "dimensions": { "width": 10, "height": 5 }, "selection": ["x"] }),
+        );
+        section(
+            "entities",
+            json!({
+                "peon": { "id": "peon", "visual": { "type": "spritesheet", "source": "a/peon.png" },
+                          "sounds": { "spawn": "sfx/hi.ogg" } },
+                "orc": { "id": "orc", "visual": { "type": "sprite", "source": "b/peon.png" } },
+                "ghost": { "id": "ghost", "visual": { "type": "gif", "source": "gone.gif" } },
+            }),
+        );
+        section(
+            "wires",
+            json!({ "wires": [{ "id": "w1", "fromZone": "signal" }, { "id": "w2", "fromZone": "choreographer" }] }),
+        );
+        asset(dir, "a/peon.png", "peon.png");
+        asset(dir, "b/peon.png", "peon.png");
+        asset(dir, "sfx/hi.ogg", "hi.ogg");
+        asset(dir, "unused.png", "unused.png");
+
+        let Plan { entries, missing } = plan(dir, &Selection::all()).unwrap();
+        let names: Vec<_> = entries.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "scene.json",
+                "entities.json",
+                "assets/spritesheets/peon.png",
+                "assets/sprites/hi.ogg",
+                "assets/sprites/peon.png",
+                "choreographies.json",
+            ]
+        );
+        assert_eq!(missing, ["gone.gif"]);
+        let json = |name: &str| match &entries.iter().find(|(n, _)| n == name).unwrap().1 {
+            Entry::Json(value) => value.clone(),
+            Entry::Asset(_) => unreachable!(),
+        };
+        assert_eq!(
+            json("scene.json"),
+            json!({ "version": 1, "dimensions": { "width": 10, "height": 5 } })
+        );
+        let entities = json("entities.json");
+        assert_eq!(
+            entities["entities"]["peon"]["visual"]["source"],
+            "assets/spritesheets/peon.png"
+        );
+        assert_eq!(
+            entities["entities"]["peon"]["sounds"]["spawn"],
+            "assets/sprites/hi.ogg"
+        );
+        assert_eq!(
+            entities["entities"]["orc"]["visual"]["source"],
+            "assets/sprites/peon.png"
+        );
+        assert_eq!(
+            entities["entities"]["ghost"]["visual"]["source"],
+            "gone.gif"
+        );
+        assert_eq!(
+            json("choreographies.json")["wires"],
+            json!([{ "id": "w2", "fromZone": "choreographer" }])
+        );
+
+        // Collisions within a folder get a numeric suffix.
+        let mut used = HashSet::new();
+        assert_eq!(
+            unique_zip_path("assets/sprites", "a.png", &mut used),
+            "assets/sprites/a.png"
+        );
+        assert_eq!(
+            unique_zip_path("assets/sprites", "a.png", &mut used),
+            "assets/sprites/a-2.png"
+        );
+        assert_eq!(
+            unique_zip_path("assets/sprites", "a.png", &mut used),
+            "assets/sprites/a-3.png"
+        );
+
+        let mut seen = Vec::new();
+        let out = Cursor::new(Vec::new());
+        let report = export(dir, &Selection::all(), out, |p| {
+            seen.push((p.done, p.total))
+        })
+        .unwrap();
+        assert_eq!(report.entries, names);
+        assert_eq!(seen.last(), Some(&(6, 6)));
+    }
+}
@@ -0,0 +1,291 @@
+//! Scene archive import.
+//!
+//! Reads an uploaded scene ZIP the way `import-scene.ts` does with fflate,
+//! but without trusting it: [`ZipReader`] refuses unsafe entry names and
+//! archives that would expand past its [`Limits`], and each JSON file is
+//! checked by [`validate`]. `scene.json` and `entities.json` are required
+//! and must be valid; an optional file that fails its checks is left out
+//! and reported as a warning, as the browser import skips malformed ones.
+//!
+//! [`Archive::summary`] gives the counts the import dialog shows; only the
+//! sections the user then ticks are read out with [`Archive::read`].
+
+use std::io::{self, Read, Seek};
+
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::validate;
+use super::zip::{Limits, ZipReader};
+use super::Selection;
+use crate::project::migrate::image_format;
+
+/// JSON files of the archive format, required ones first.
+const JSON_FILES: [&str; 5] = [
+    "scene.json",
+    "entities.json",
+    "choreographies.json",
+    "shaders.json",
+    "p5.json",
+];
+
+/// Files every archive must have.
+const REQUIRED: [&str; 2] = ["scene.json", "entities.json"];
+
+/// Counts shown by the import dialog. Mirrors `ZipSummary` in `import-dialog.ts`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub entity_placements: usize,
+    pub entity_definitions: usize,
+    pub asset_files: usize,
+    pub choreographies: usize,
+    pub wires: usize,
+    pub bindings: usize,
+    pub shaders: usize,
+    pub p5_sketches: usize,
+}
+
+/// An asset file of the archive, base64-encoded for the webview.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Asset {
+    /// ZIP path, e.g. `assets/sprites/peon.png`.
+    pub path: String,
+    pub data: String,
+}
+
+/// The ticked sections, as the archive holds them. `shaders` and `p5` are
+/// the definition arrays; the other files are returned whole.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Sections {
+    pub scene: Option<Value>,
+    pub entities: Option<Value>,
+    pub choreographies: Option<Value>,
+    pub shaders: Option<Value>,
+    pub p5: Option<Value>,
+    pub assets: Vec<Asset>,
+}
+
+/// A scene archive whose JSON files are parsed and checked.
+pub struct Archive<R: Read + Seek> {
+    zip: ZipReader<R>,
+    /// Valid JSON files, by name.
+    files: Vec<(&'static str, Value)>,
+    /// Asset entries the editor can load (images, by extension).
+    assets: Vec<String>,
+    /// Optional files left out, and why.
+    pub warnings: Vec<String>,
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn len(value: Option<&Value>) -> usize {
+    value.and_then(Value::as_array).map_or(0, Vec::len)
+}
+
+impl<R: Read + Seek> Archive<R> {
+    /// Open and check the archive in `input`.
+    pub fn open(input: R) -> io::Result<Self> {
+        Self::with_limits(input, &Limits::default())
+    }
+
+    pub fn with_limits(input: R, limits: &Limits) -> io::Result<Self> {
+        let mut zip = ZipReader::new(input, limits)?;
+        let mut files = Vec::new();
+        let mut warnings = Vec::new();
+        for name in JSON_FILES {
+            let required = REQUIRED.contains(&name);
+            if zip.entry(name).is_none() {
+                if required {
+                    return Err(invalid(format!("invalid scene archive: missing {name}")));
+                }
+                continue;
+            }
+            let parsed: Result<Value, String> =
+                serde_json::from_slice(&zip.read(name)?).map_err(|e| format!("{name}: {e}"));
+            let errors = match &parsed {
+                Ok(value) => validate::check(name, value),
+                Err(e) => vec![e.clone()],
+            };
+            match parsed {
+                Ok(value) if errors.is_empty() => files.push((name, value)),
+                _ if required => return Err(invalid(errors.join("\n"))),
+                _ => warnings.extend(errors),
+            }
+        }
+        let assets = zip
+            .entries()
+            .iter()
+            .filter(|e| e.name.starts_with("assets/") && !e.is_dir() && e.size > 0)
+            .filter(|e| image_format(&e.name) != "unknown")
+            .map(|e| e.name.clone())
+            .collect();
+        Ok(Self {
+            zip,
+            files,
+            assets,
+            warnings,
+        })
+    }
+
+    /// A valid JSON file of the archive.
+    pub fn file(&self, name: &str) -> Option<&Value> {
+        self.files.iter().find(|(n, _)| *n == name).map(|(_, v)| v)
+    }
+
+    /// Every file under `assets/`, loadable or not, in archive order.
+    pub fn asset_entries(&self) -> Vec<&str> {
+        self.zip
+            .entries()
+            .iter()
+            .filter(|e| e.name.starts_with("assets/") && !e.is_dir())
+            .map(|e| e.name.as_str())
+            .collect()
+    }
+
+    pub fn summary(&self) -> Summary {
+        let choreographies = self.file("choreographies.json");
+        Summary {
+            entity_placements: len(self.file("scene.json").and_then(|s| s.get("entities"))),
+            entity_definitions: self
+                .file("entities.json")
+                .and_then(|e| e["entities"].as_object())
+                .map_or(0, |e| e.len()),
+            asset_files: self.assets.len(),
+            choreographies: len(choreographies.and_then(|c| c.get("choreographies"))),
+            wires: len(choreographies.and_then(|c| c.get("wires"))),
+            bindings: len(choreographies.and_then(|c| c.get("bindings"))),
+            shaders: len(self.file("shaders.json").and_then(|s| s.get("shaders"))),
+            p5_sketches: len(self.file("p5.json").and_then(|p| p.get("sketches"))),
+        }
+    }
+
+    /// The sections ticked in `selection`.
+    pub fn read(&mut self, selection: &Selection) -> io::Result<Sections> {
+        let pick = |ticked: bool, name: &str| self.file(name).filter(|_| ticked).cloned();
+        let mut sections = Sections {
+            scene: pick(selection.visual_layout, "scene.json"),
+            entities: pick(selection.entities_and_assets, "entities.json"),
+            choreographies: pick(selection.choreographies_and_wiring, "choreographies.json"),
+            shaders: pick(selection.shaders, "shaders.json").map(|mut s| s["shaders"].take()),
+            p5: pick(selection.p5_sketches, "p5.json").map(|mut p| p["sketches"].take()),
+            assets: Vec::new(),
+        };
+        if selection.entities_and_assets {
+            for path in &self.assets {
+                let data = base64::engine::general_purpose::STANDARD.encode(self.zip.read(path)?);
+                sections.assets.push(Asset {
+                    path: path.clone(),
+                    data,
+                });
+            }
+        }
+        Ok(sections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::zip::{Method, ZipWriter};
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn archive(files: &[(&str, Value)], assets: &[&str]) -> Cursor<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, value) in files {
+            let bytes = serde_json::to_vec(value).unwrap();
+            zip.add_bytes(name, Method::Deflated, &bytes).unwrap();
+        }
+        for name in assets {
+            zip.add_bytes(name, Method::Stored, b"bytes").unwrap();
+        }
+        let mut out = zip.finish().unwrap();
+        out.set_position(0);
+        out
+    }
+
+    #[test]
+    fn summarises_validates_and_reads_ticked_sections() {
+        let scene = json!({
+            "version": 1,
+            "dimensions": { "width": 960, "height": 640 },
+            "layers": [{ "id": "base" }],
+            "entities": [{ "id": "peon-01", "entityId": "peon", "x": 1, "y": 2 }],
+            "routes": [{ "id": "r1", "points": [{ "x": 0, "y": 0 }] }],
+        });
+        let entities = json!({ "version": 1, "entities": {
+            "peon": { "visual": { "type": "sprite", "source": "assets/sprites/peon.png" } },
+        }});
+        let choreographies = json!({
+            "version": 1,
+            "choreographies": [{ "id": "c1" }],
+            "wires": [{ "id": "w1" }, { "id": "w2" }],
+        });
+        let shaders = json!({ "version": 1, "shaders": [{ "id": "s1" }] });
+        let files = [
+            ("scene.json", scene),
+            ("entities.json", entities.clone()),
+            ("choreographies.json", choreographies),
+            ("shaders.json", shaders),
+        ];
+        let assets = ["assets/sprites/peon.png", "assets/readme.txt"];
+
+        // A route needs two points: scene.json is rejected with its pointer.
+        let Err(e) = Archive::open(archive(&files, &assets)) else {
+            panic!("invalid scene.json accepted");
+        };
+        assert_eq!(
+            e.to_string(),
+            "scene.json /routes/0/points: a route needs at least 2 points"
+        );
+
+        let mut files = files;
+        files[0].1["routes"][0]["points"] = json!([{ "x": 0, "y": 0 }, { "x": 5, "y": 5 }]);
+        let mut opened = Archive::open(archive(&files, &assets)).unwrap();
+        // The shader lacks its source, so shaders.json is left out.
+        assert_eq!(
+            opened.warnings,
+            ["shaders.json /shaders/0/fragmentSource: missing, expected a string"]
+        );
+        assert_eq!(
+            opened.summary(),
+            Summary {
+                entity_placements: 1,
+                entity_definitions: 1,
+                asset_files: 1,
+                choreographies: 1,
+                wires: 2,
+                ..Summary::default()
+            }
+        );
+
+        let selection = Selection {
+            entities_and_assets: true,
+            shaders: true,
+            ..Selection::default()
+        };
+        let sections = opened.read(&selection).unwrap();
+        assert_eq!(sections.scene, None);
+        assert_eq!(sections.entities, Some(entities));
+        assert_eq!(sections.shaders, None);
+        assert_eq!(
+            sections.assets,
+            [Asset {
+                path: "assets/sprites/peon.png".into(),
+                data: "Ynl0ZXM=".into(),
+            }]
+        );
+
+        let Err(e) = Archive::open(archive(&files[..1], &[])) else {
+            panic!("archive without entities.json accepted");
+        };
+        assert_eq!(
+            e.to_string(),
+            "invalid scene archive: missing entities.json"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! What a scene archive holds, and which of its references do not resolve.
+//!
+//! References follow how the runtime resolves them: placed entities point
+//! at a definition (`entityId`) and a layer, positions may bind an entity
+//! definition, routes join positions by id, and choreography steps name
+//! positions (`params.to` / `params.at`) and routes (`followRoute`'s
+//! `params.route`). `signal.*` references are resolved at run time and
+//! are not checked.
+
+use std::collections::HashSet;
+use std::io::{Read, Seek};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::import::{Archive, Summary};
+
+/// A choreography, as listed by `sajou scene info`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Choreography {
+    pub id: String,
+    /// Triggering signal type.
+    pub on: String,
+    /// Steps, nested ones included.
+    pub steps: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Info {
+    pub summary: Summary,
+    /// Entity definition ids.
+    pub entities: Vec<String>,
+    pub choreographies: Vec<Choreography>,
+    /// Archive assets no entity or particle emitter uses.
+    pub unreferenced_assets: Vec<String>,
+    /// Assets entities use that the archive lacks.
+    pub missing_assets: Vec<String>,
+    /// `file /json/pointer: problem`, as validation errors read.
+    pub broken_references: Vec<String>,
+}
+
+fn str_at<'v>(value: &'v Value, key: &str) -> Option<&'v str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+fn array<'v>(value: Option<&'v Value>, key: &str) -> &'v [Value] {
+    value
+        .and_then(|v| v.get(key))
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+/// `field` of every item, as a set.
+fn ids<'v>(items: &'v [Value], field: &str) -> HashSet<&'v str> {
+    items
+        .iter()
+        .filter_map(|item| str_at(item, field))
+        .collect()
+}
+
+/// Scene-side names choreography steps may refer to.
+struct Targets<'v> {
+    positions: HashSet<&'v str>,
+    routes: HashSet<&'v str>,
+}
+
+/// Count `steps` (and their children), reporting unresolved names.
+fn walk_steps(steps: &[Value], path: &str, targets: &Targets, broken: &mut Vec<String>) -> usize {
+    let mut count = 0;
+    for (i, step) in steps.iter().enumerate() {
+        let at = format!("{path}/{i}");
+        count += 1;
+        let params = step.get("params");
+        for key in ["to", "at"] {
+            let name = params.and_then(|p| str_at(p, key));
+            if let Some(name) = name.filter(|n| !n.is_empty() && !n.starts_with("signal.")) {
+                if !targets.positions.contains(name) {
+                    broken.push(format!(
+                        "choreographies.json {at}/params/{key}: no position named \"{name}\""
+                    ));
+                }
+            }
+        }
+        if str_at(step, "action") == Some("followRoute") {
+            let route = params.and_then(|p| str_at(p, "route")).unwrap_or_default();
+            if !route.starts_with("signal.") && !targets.routes.contains(route) {
+                broken.push(format!(
+                    "choreographies.json {at}/params/route: no route named \"{route}\""
+                ));
+            }
+        }
+        let children = array(Some(step), "children");
+        count += walk_steps(children, &format!("{at}/children"), targets, broken);
+    }
+    count
+}
+
+/// Contents and reference problems of `archive`.
+pub fn info<R: Read + Seek>(archive: &Archive<R>) -> Info {
+    let scene = archive.file("scene.json");
+    let definitions = archive
+        .file("entities.json")
+        .and_then(|e| e["entities"].as_object());
+    let choreographies = archive.file("choreographies.json");
+    let mut broken = Vec::new();
+
+    let layers = ids(array(scene, "layers"), "id");
+    for (i, placed) in array(scene, "entities").iter().enumerate() {
+        let entity_id = str_at(placed, "entityId").unwrap_or_default();
+        if !definitions.is_some_and(|d| d.contains_key(entity_id)) {
+            broken.push(format!(
+                "scene.json /entities/{i}/entityId: no entity definition \"{entity_id}\""
+            ));
+        }
+        if let Some(layer) = str_at(placed, "layerId").filter(|l| !layers.contains(l)) {
+            broken.push(format!(
+                "scene.json /entities/{i}/layerId: no layer \"{layer}\""
+            ));
+        }
+    }
+
+    let positions = array(scene, "positions");
+    for (i, position) in positions.iter().enumerate() {
+        let binding = str_at(position, "entityBinding").filter(|b| !b.is_empty());
+        if let Some(binding) = binding.filter(|b| !definitions.is_some_and(|d| d.contains_key(*b)))
+        {
+            broken.push(format!(
+                "scene.json /positions/{i}/entityBinding: no entity definition \"{binding}\""
+            ));
+        }
+    }
+    let position_ids = ids(positions, "id");
+    let routes = array(scene, "routes");
+    for (i, route) in routes.iter().enumerate() {
+        for key in ["fromPositionId", "toPositionId"] {
+            if let Some(id) = str_at(route, key).filter(|id| !position_ids.contains(id)) {
+                broken.push(format!(
+                    "scene.json /routes/{i}/{key}: no position with id \"{id}\""
+                ));
+            }
+        }
+    }
+
+    let targets = Targets {
+        positions: ids(positions, "name"),
+        routes: ids(routes, "name"),
+    };
+    let choreographies = array(choreographies, "choreographies")
+        .iter()
+        .enumerate()
+        .map(|(i, choreography)| Choreography {
+            id: str_at(choreography, "id").unwrap_or_default().to_string(),
+            on: str_at(choreography, "on").unwrap_or_default().to_string(),
+            steps: walk_steps(
+                array(Some(choreography), "steps"),
+                &format!("/choreographies/{i}/steps"),
+                &targets,
+                &mut broken,
+            ),
+        })
+        .collect();
+
+    // Assets in use: entity visuals and sounds, particle sprites.
+    let mut used: Vec<&str> = Vec::new();
+    for entry in definitions.into_iter().flat_map(|d| d.values()) {
+        used.extend(str_at(&entry["visual"], "source"));
+        let sounds = entry["sounds"]
+            .as_object()
+            .into_iter()
+            .flat_map(|s| s.values());
+        used.extend(sounds.filter_map(Value::as_str));
+    }
+    let sprites = array(scene, "particles")
+        .iter()
+        .filter_map(|p| str_at(p, "sprite"));
+    used.extend(sprites.filter(|s| !s.is_empty()));
+    let in_archive = archive.asset_entries();
+    let mut missing_assets: Vec<String> = Vec::new();
+    for path in &used {
+        if !in_archive.contains(path) && !missing_assets.iter().any(|m| m == path) {
+            missing_assets.push(path.to_string());
+        }
+    }
+
+    Info {
+        summary: archive.summary(),
+        entities: definitions
+            .map(|d| d.keys().cloned().collect())
+            .unwrap_or_default(),
+        choreographies,
+        unreferenced_assets: in_archive
+            .iter()
+            .filter(|a| !used.contains(a))
+            .map(|a| a.to_string())
+            .collect(),
+        missing_assets,
+        broken_references: broken,
+    }
+}
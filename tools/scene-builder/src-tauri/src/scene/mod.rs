@@ -0,0 +1,207 @@
+//! Scene archives — the portable ZIP format of `docs/reference/scene-format.md`.
+//!
+//! In the desktop app, export and import run here instead of in the
+//! webview. Export builds the archive from the open project folder and
+//! writes it straight to the file the user picked, with a progress event
+//! per entry. Import checks the archive, reports what it holds for the
+//! import dialog, then hands the webview only the ticked sections. The same
+//! code backs the headless `sajou scene` subcommands ([`cli`]).
+
+pub mod cli;
+pub mod export;
+pub mod import;
+pub mod info;
+pub mod validate;
+pub mod zip;
+
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::project::Project;
+use export::{Progress, Report};
+use import::{Archive, Sections, Summary};
+
+/// Emitted after each archive entry, with a [`Progress`].
+pub const EXPORT_PROGRESS_EVENT: &str = "scene://export-progress";
+
+/// Default archive name, as in the browser download.
+const EXPORT_FILE_NAME: &str = "scene-export.sajou";
+
+/// Archive sections, as ticked in the export and import dialogs.
+/// Mirrors `ExportSelection` in `export-dialog.ts` and `ImportSelection`
+/// in `import-dialog.ts`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Selection {
+    pub visual_layout: bool,
+    pub entities_and_assets: bool,
+    pub choreographies_and_wiring: bool,
+    pub shaders: bool,
+    pub p5_sketches: bool,
+}
+
+impl Selection {
+    /// Every section.
+    pub fn all() -> Self {
+        Self {
+            visual_layout: true,
+            entities_and_assets: true,
+            choreographies_and_wiring: true,
+            shaders: true,
+            p5_sketches: true,
+        }
+    }
+}
+
+async fn pick_export_path(app: &AppHandle) -> Option<PathBuf> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Export scene")
+        .set_file_name(EXPORT_FILE_NAME)
+        .add_filter("sajou scene", &["sajou", "zip"])
+        .set_can_create_directories(true)
+        .save_file(move |file| {
+            let _ = tx.send(file.and_then(|f| f.into_path().ok()));
+        });
+    rx.await.ok().flatten()
+}
+
+/// Export `dir` to `path` through a sibling temp file, so a failed export
+/// never leaves a truncated archive behind.
+pub(crate) fn export_to(
+    dir: &Path,
+    selection: &Selection,
+    path: &Path,
+    progress: impl FnMut(Progress),
+) -> std::io::Result<Report> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+    let result = (|| {
+        let out = export::export(
+            dir,
+            selection,
+            BufWriter::new(File::create(&tmp)?),
+            progress,
+        )?;
+        File::open(&tmp)?.sync_all()?;
+        fs::rename(&tmp, path)?;
+        Ok(out)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Export the open project as a scene archive to `path`, or to a file
+/// picked in a save dialog. Returns `None` if the user cancelled.
+/// The webview flushes its pending saves first.
+#[tauri::command]
+pub async fn scene_export(
+    app: AppHandle,
+    selection: Selection,
+    path: Option<String>,
+) -> Result<Option<Report>, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => match pick_export_path(&app).await {
+            Some(path) => path,
+            None => return Ok(None),
+        },
+    };
+    let dir = app.state::<Project>().dir();
+    let emitter = app.clone();
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        export_to(&dir, &selection, &path, |progress| {
+            let _ = emitter.emit(EXPORT_PROGRESS_EVENT, progress);
+        })
+        .map_err(|e| format!("export to {} failed: {e}", path.display()))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    eprintln!(
+        "[sajou] exported {} entries ({} bytes)",
+        report.entries.len(),
+        report.bytes
+    );
+    Ok(Some(report))
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+/// An archive checked for import.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Inspection {
+    pub path: PathBuf,
+    pub summary: Summary,
+    /// Optional files left out because they failed validation.
+    pub warnings: Vec<String>,
+}
+
+async fn pick_import_path(app: &AppHandle) -> Option<PathBuf> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Import scene")
+        .add_filter("sajou scene", &["sajou", "zip"])
+        .pick_file(move |file| {
+            let _ = tx.send(file.and_then(|f| f.into_path().ok()));
+        });
+    rx.await.ok().flatten()
+}
+
+fn open_archive(path: &Path) -> Result<Archive<File>, String> {
+    File::open(path)
+        .and_then(Archive::open)
+        .map_err(|e| format!("cannot import {}: {e}", path.display()))
+}
+
+/// Check the archive at `path`, or one picked in a file dialog, and count
+/// what it holds. Returns `None` if the user cancelled.
+#[tauri::command]
+pub async fn scene_import_inspect(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<Option<Inspection>, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => match pick_import_path(&app).await {
+            Some(path) => path,
+            None => return Ok(None),
+        },
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let archive = open_archive(&path)?;
+        for warning in &archive.warnings {
+            eprintln!("[sajou] import: skipping {warning}");
+        }
+        Ok(Some(Inspection {
+            summary: archive.summary(),
+            warnings: archive.warnings,
+            path,
+        }))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// The sections of the archive at `path` ticked in `selection`.
+#[tauri::command]
+pub async fn scene_import(path: PathBuf, selection: Selection) -> Result<Sections, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        open_archive(&path)?
+            .read(&selection)
+            .map_err(|e| format!("cannot import {}: {e}", path.display()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,285 @@
+//! Structural checks of the JSON files in a scene archive.
+//!
+//! The rules follow `docs/reference/scene-format.md`: required fields must
+//! be present with the right type, optional ones (absent from older
+//! exports) only need the right type when present. Unknown fields pass, so
+//! newer exports still import. Errors read `file /json/pointer: problem`.
+//!
+//! `packages/schema`'s `stage-scene` and `entity-visual` schemas describe
+//! Stage scenes and theme entities (`board`, `states`), not this archive,
+//! so they are not applied here.
+
+use serde_json::Value;
+
+/// Visual types an entity definition may use.
+const VISUAL_TYPES: [&str; 3] = ["sprite", "spritesheet", "gif"];
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Object,
+    Array,
+    String,
+    Number,
+}
+
+impl Kind {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Kind::Object => value.is_object(),
+            Kind::Array => value.is_array(),
+            Kind::String => value.is_string(),
+            Kind::Number => value.is_number(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Object => "an object",
+            Kind::Array => "an array",
+            Kind::String => "a string",
+            Kind::Number => "a number",
+        }
+    }
+}
+
+/// JSON pointer segment for `key` (RFC 6901 escaping).
+fn segment(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+struct Check<'f> {
+    file: &'f str,
+    errors: Vec<String>,
+}
+
+impl Check<'_> {
+    fn error(&mut self, path: &str, problem: impl std::fmt::Display) {
+        let path = if path.is_empty() { "/" } else { path };
+        self.errors.push(format!("{} {path}: {problem}", self.file));
+    }
+
+    /// `value[key]` when it is of `kind`. A missing (or null) field is an
+    /// error only when `required`.
+    fn field<'v>(
+        &mut self,
+        value: &'v Value,
+        path: &str,
+        key: &str,
+        kind: Kind,
+        required: bool,
+    ) -> Option<&'v Value> {
+        let at = format!("{path}/{}", segment(key));
+        match value.get(key) {
+            None | Some(Value::Null) if !required => None,
+            None | Some(Value::Null) => {
+                self.error(&at, format!("missing, expected {}", kind.name()));
+                None
+            }
+            Some(v) if kind.matches(v) => Some(v),
+            Some(_) => {
+                self.error(&at, format!("expected {}", kind.name()));
+                None
+            }
+        }
+    }
+
+    /// Check several fields of one kind.
+    fn fields(&mut self, value: &Value, path: &str, keys: &[&str], kind: Kind, required: bool) {
+        for key in keys {
+            self.field(value, path, key, kind, required);
+        }
+    }
+
+    /// The objects of the array `value[key]`, with their paths.
+    fn items<'v>(
+        &mut self,
+        value: &'v Value,
+        path: &str,
+        key: &str,
+        required: bool,
+    ) -> Vec<(String, &'v Value)> {
+        let Some(array) = self.field(value, path, key, Kind::Array, required) else {
+            return Vec::new();
+        };
+        let path = format!("{path}/{}", segment(key));
+        let mut items = Vec::new();
+        for (i, item) in array.as_array().into_iter().flatten().enumerate() {
+            let at = format!("{path}/{i}");
+            if item.is_object() {
+                items.push((at, item));
+            } else {
+                self.error(&at, "expected an object");
+            }
+        }
+        items
+    }
+
+    fn root(&mut self, value: &Value) -> bool {
+        if !value.is_object() {
+            self.error("", "expected an object");
+        }
+        value.is_object()
+    }
+}
+
+fn scene(check: &mut Check, scene: &Value) {
+    if !check.root(scene) {
+        return;
+    }
+    if let Some(dimensions) = check.field(scene, "", "dimensions", Kind::Object, true) {
+        check.fields(
+            dimensions,
+            "/dimensions",
+            &["width", "height"],
+            Kind::Number,
+            true,
+        );
+    }
+    check.field(scene, "", "background", Kind::Object, false);
+    for (at, layer) in check.items(scene, "", "layers", true) {
+        check.field(layer, &at, "id", Kind::String, true);
+    }
+    for (at, placed) in check.items(scene, "", "entities", true) {
+        check.fields(placed, &at, &["id", "entityId"], Kind::String, true);
+        check.fields(placed, &at, &["x", "y"], Kind::Number, true);
+        check.field(placed, &at, "layerId", Kind::String, false);
+        let optional = ["scale", "rotation", "zIndex", "opacity"];
+        check.fields(placed, &at, &optional, Kind::Number, false);
+    }
+    for (at, position) in check.items(scene, "", "positions", false) {
+        check.field(position, &at, "id", Kind::String, true);
+        check.fields(position, &at, &["x", "y"], Kind::Number, true);
+    }
+    for (at, route) in check.items(scene, "", "routes", false) {
+        check.field(route, &at, "id", Kind::String, true);
+        let points = check.items(route, &at, "points", true);
+        if route["points"].as_array().is_some_and(|p| p.len() < 2) {
+            check.error(&format!("{at}/points"), "a route needs at least 2 points");
+        }
+        for (at, point) in points {
+            check.fields(point, &at, &["x", "y"], Kind::Number, true);
+        }
+    }
+    for (at, zone_type) in check.items(scene, "", "zoneTypes", false) {
+        check.field(zone_type, &at, "id", Kind::String, true);
+    }
+    if let Some(grid) = check.field(scene, "", "zoneGrid", Kind::Object, false) {
+        let sizes = ["cellSize", "cols", "rows"];
+        check.fields(grid, "/zoneGrid", &sizes, Kind::Number, true);
+        check.field(grid, "/zoneGrid", "cells", Kind::Array, true);
+    }
+    check.field(scene, "", "lighting", Kind::Object, false);
+    for (at, emitter) in check.items(scene, "", "particles", false) {
+        check.field(emitter, &at, "id", Kind::String, true);
+        check.fields(emitter, &at, &["x", "y"], Kind::Number, true);
+    }
+}
+
+fn entities(check: &mut Check, file: &Value) {
+    if !check.root(file) {
+        return;
+    }
+    let Some(entities) = check.field(file, "", "entities", Kind::Object, true) else {
+        return;
+    };
+    for (id, entry) in entities.as_object().into_iter().flatten() {
+        let at = format!("/entities/{}", segment(id));
+        if !entry.is_object() {
+            check.error(&at, "expected an object");
+            continue;
+        }
+        let size = ["displayWidth", "displayHeight"];
+        check.fields(entry, &at, &size, Kind::Number, false);
+        check.field(entry, &at, "tags", Kind::Array, false);
+        if let Some(sounds) = check.field(entry, &at, "sounds", Kind::Object, false) {
+            let keys: Vec<&str> = sounds
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(k, _)| k.as_str())
+                .collect();
+            check.fields(sounds, &format!("{at}/sounds"), &keys, Kind::String, true);
+        }
+        let Some(visual) = check.field(entry, &at, "visual", Kind::Object, true) else {
+            continue;
+        };
+        let at = format!("{at}/visual");
+        check.field(visual, &at, "source", Kind::String, true);
+        let kind = check.field(visual, &at, "type", Kind::String, true);
+        match kind.and_then(Value::as_str) {
+            Some("spritesheet") => {
+                let frame = ["frameWidth", "frameHeight"];
+                check.fields(visual, &at, &frame, Kind::Number, true);
+                let animations = check.field(visual, &at, "animations", Kind::Object, true);
+                for (name, animation) in animations.and_then(Value::as_object).into_iter().flatten()
+                {
+                    let at = format!("{at}/animations/{}", segment(name));
+                    check.field(animation, &at, "frames", Kind::Array, true);
+                    check.field(animation, &at, "fps", Kind::Number, true);
+                }
+            }
+            Some(other) if !VISUAL_TYPES.contains(&other) => {
+                check.error(
+                    &format!("{at}/type"),
+                    format!("unknown visual type \"{other}\""),
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+fn choreographies(check: &mut Check, file: &Value) {
+    if !check.root(file) {
+        return;
+    }
+    for (at, choreography) in check.items(file, "", "choreographies", true) {
+        check.field(choreography, &at, "id", Kind::String, true);
+        check.field(choreography, &at, "steps", Kind::Array, false);
+    }
+    for (at, wire) in check.items(file, "", "wires", false) {
+        check.field(wire, &at, "id", Kind::String, true);
+    }
+    for (at, binding) in check.items(file, "", "bindings", false) {
+        check.field(binding, &at, "id", Kind::String, false);
+    }
+}
+
+fn shaders(check: &mut Check, file: &Value) {
+    if !check.root(file) {
+        return;
+    }
+    for (at, shader) in check.items(file, "", "shaders", true) {
+        check.fields(shader, &at, &["id", "fragmentSource"], Kind::String, true);
+        let optional = ["name", "vertexSource"];
+        check.fields(shader, &at, &optional, Kind::String, false);
+        check.field(shader, &at, "uniforms", Kind::Array, false);
+    }
+}
+
+fn sketches(check: &mut Check, file: &Value) {
+    if !check.root(file) {
+        return;
+    }
+    for (at, sketch) in check.items(file, "", "sketches", true) {
+        check.field(sketch, &at, "id", Kind::String, true);
+    }
+}
+
+/// Problems with the archive file `name` holding `value`; empty when it is
+/// valid or not a file the archive format defines.
+pub fn check(name: &str, value: &Value) -> Vec<String> {
+    let mut check = Check {
+        file: name,
+        errors: Vec::new(),
+    };
+    match name {
+        "scene.json" => scene(&mut check, value),
+        "entities.json" => entities(&mut check, value),
+        "choreographies.json" => choreographies(&mut check, value),
+        "shaders.json" => shaders(&mut check, value),
+        "p5.json" => sketches(&mut check, value),
+        _ => {}
+    }
+    check.errors
+}
@@ -0,0 +1,554 @@
+//! Minimal ZIP archive writer and reader.
+//!
+//! Just what scene archives need: stored or deflated entries, no ZIP64, no
+//! encryption. The writer streams each entry from a reader, writing its
+//! local header with placeholder sizes and patching it afterwards, so the
+//! output needs no data descriptors and opens in every reader (fflate
+//! included).
+//!
+//! The reader trusts nothing in an uploaded archive: entry names that could
+//! escape a folder are refused, and [`Limits`] caps the entry count, the
+//! total and per-entry sizes and the compression ratio before anything is
+//! inflated. Inflation stops at the declared size, so a header that lies
+//! cannot be used to inflate more.
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+
+/// Version needed to extract: 2.0 (deflate).
+const VERSION: u16 = 20;
+
+/// General purpose flag: names are UTF-8.
+const FLAG_UTF8: u16 = 1 << 11;
+
+/// General purpose flag: the entry is encrypted.
+const FLAG_ENCRYPTED: u16 = 1;
+
+/// How an entry's bytes are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Stored,
+    Deflated,
+}
+
+impl Method {
+    fn code(self) -> u16 {
+        match self {
+            Method::Stored => 0,
+            Method::Deflated => 8,
+        }
+    }
+
+    fn from_code(code: u16) -> Option<Self> {
+        match code {
+            0 => Some(Method::Stored),
+            8 => Some(Method::Deflated),
+            _ => None,
+        }
+    }
+}
+
+struct CentralEntry {
+    name: String,
+    method: Method,
+    crc: u32,
+    compressed: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Streams entries into a ZIP archive.
+pub struct ZipWriter<W: Write + Seek> {
+    out: W,
+    entries: Vec<CentralEntry>,
+    /// MS-DOS `(time, date)` stamped on every entry.
+    modified: (u16, u16),
+}
+
+/// Counts and checksums what passes through to `inner`.
+struct Tally<W> {
+    inner: W,
+    crc: crc32fast::Hasher,
+    bytes: u64,
+}
+
+impl<W: Write> Write for Tally<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.crc.update(&buf[..n]);
+        self.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn too_large(what: &str) -> io::Error {
+    io::Error::other(format!("{what} is too large for a ZIP archive (4 GiB)"))
+}
+
+fn to_u32(n: u64, what: &str) -> io::Result<u32> {
+    u32::try_from(n).map_err(|_| too_large(what))
+}
+
+impl<W: Write + Seek> ZipWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            entries: Vec::new(),
+            modified: dos_time(SystemTime::now()),
+        }
+    }
+
+    /// Stream `reader` into a new entry. Returns the uncompressed size.
+    pub fn add(&mut self, name: &str, method: Method, reader: &mut impl Read) -> io::Result<u64> {
+        let offset = to_u32(self.out.stream_position()?, "archive")?;
+        self.local_header(name, method, 0, 0, 0)?;
+        let start = self.out.stream_position()?;
+
+        let (crc, size) = match method {
+            Method::Stored => {
+                let mut tally = Tally {
+                    inner: &mut self.out,
+                    crc: crc32fast::Hasher::new(),
+                    bytes: 0,
+                };
+                io::copy(reader, &mut tally)?;
+                (tally.crc.finalize(), tally.bytes)
+            }
+            Method::Deflated => {
+                let mut tally = Tally {
+                    inner: DeflateEncoder::new(&mut self.out, Compression::default()),
+                    crc: crc32fast::Hasher::new(),
+                    bytes: 0,
+                };
+                io::copy(reader, &mut tally)?;
+                tally.inner.finish()?;
+                (tally.crc.finalize(), tally.bytes)
+            }
+        };
+        let end = self.out.stream_position()?;
+        let compressed = to_u32(end - start, name)?;
+        let size = to_u32(size, name)?;
+
+        self.out.seek(SeekFrom::Start(u64::from(offset)))?;
+        self.local_header(name, method, crc, compressed, size)?;
+        self.out.seek(SeekFrom::Start(end))?;
+
+        self.entries.push(CentralEntry {
+            name: name.to_string(),
+            method,
+            crc,
+            compressed,
+            size,
+            offset,
+        });
+        Ok(u64::from(size))
+    }
+
+    /// Add an entry from memory.
+    pub fn add_bytes(&mut self, name: &str, method: Method, bytes: &[u8]) -> io::Result<u64> {
+        self.add(name, method, &mut &bytes[..])
+    }
+
+    fn local_header(
+        &mut self,
+        name: &str,
+        method: Method,
+        crc: u32,
+        compressed: u32,
+        size: u32,
+    ) -> io::Result<()> {
+        let (time, date) = self.modified;
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend(LOCAL_HEADER.to_le_bytes());
+        header.extend(VERSION.to_le_bytes());
+        header.extend(FLAG_UTF8.to_le_bytes());
+        header.extend(method.code().to_le_bytes());
+        header.extend(time.to_le_bytes());
+        header.extend(date.to_le_bytes());
+        header.extend(crc.to_le_bytes());
+        header.extend(compressed.to_le_bytes());
+        header.extend(size.to_le_bytes());
+        header.extend(name_len(name)?.to_le_bytes());
+        header.extend(0u16.to_le_bytes()); // extra field length
+        header.extend(name.as_bytes());
+        self.out.write_all(&header)
+    }
+
+    /// Write the central directory and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let (time, date) = self.modified;
+        let start = to_u32(self.out.stream_position()?, "archive")?;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            directory.extend(CENTRAL_HEADER.to_le_bytes());
+            directory.extend(VERSION.to_le_bytes()); // made by
+            directory.extend(VERSION.to_le_bytes()); // needed to extract
+            directory.extend(FLAG_UTF8.to_le_bytes());
+            directory.extend(entry.method.code().to_le_bytes());
+            directory.extend(time.to_le_bytes());
+            directory.extend(date.to_le_bytes());
+            directory.extend(entry.crc.to_le_bytes());
+            directory.extend(entry.compressed.to_le_bytes());
+            directory.extend(entry.size.to_le_bytes());
+            directory.extend(name_len(&entry.name)?.to_le_bytes());
+            directory.extend([0u8; 8]); // extra, comment, disk, internal attributes
+            directory.extend(0u32.to_le_bytes()); // external attributes
+            directory.extend(entry.offset.to_le_bytes());
+            directory.extend(entry.name.as_bytes());
+        }
+        let count =
+            u16::try_from(self.entries.len()).map_err(|_| too_large("number of entries"))?;
+        let size = to_u32(directory.len() as u64, "central directory")?;
+        directory.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        directory.extend([0u8; 4]); // disk numbers
+        directory.extend(count.to_le_bytes());
+        directory.extend(count.to_le_bytes());
+        directory.extend(size.to_le_bytes());
+        directory.extend(start.to_le_bytes());
+        directory.extend(0u16.to_le_bytes()); // comment length
+        self.out.write_all(&directory)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+fn name_len(name: &str) -> io::Result<u16> {
+    u16::try_from(name.len()).map_err(|_| too_large("entry name"))
+}
+
+/// MS-DOS `(time, date)` of `at`, in UTC. Clamped to 1980, the format's epoch.
+fn dos_time(at: SystemTime) -> (u16, u16) {
+    let secs = at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    if year < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+    let time = ((rem / 3600) << 11) | (((rem % 3600) / 60) << 5) | ((rem % 60) / 2);
+    let date = (((year - 1980).min(127) << 9) | (month << 5) | day) as u16;
+    (time as u16, date)
+}
+
+// ---------------------------------------------------------------------------
+// Reader
+// ---------------------------------------------------------------------------
+
+/// End of central directory record size, without its comment.
+const END_RECORD_LEN: u64 = 22;
+
+/// Central directory header size, without its variable fields.
+const CENTRAL_HEADER_LEN: usize = 46;
+
+/// Local header size, without its variable fields.
+const LOCAL_HEADER_LEN: usize = 30;
+
+/// What an archive may expand to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_entries: usize,
+    /// Uncompressed size of one entry.
+    pub max_entry_size: u64,
+    /// Uncompressed size of all entries together.
+    pub max_total_size: u64,
+    /// Uncompressed / compressed, checked on entries over 1 MiB.
+    pub max_ratio: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_entry_size: 256 << 20,
+            max_total_size: 1 << 30,
+            max_ratio: 200,
+        }
+    }
+}
+
+/// An entry of the central directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipEntry {
+    pub name: String,
+    pub method: Method,
+    pub crc: u32,
+    pub compressed: u64,
+    pub size: u64,
+    offset: u64,
+}
+
+impl ZipEntry {
+    pub fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+}
+
+/// Reads entries out of a ZIP archive.
+pub struct ZipReader<R: Read + Seek> {
+    input: R,
+    entries: Vec<ZipEntry>,
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Whether `name` stays inside the folder it is extracted to: relative,
+/// `/`-separated, no `.` / `..` / empty segments, no drive letter.
+pub fn is_safe_name(name: &str) -> bool {
+    let name = name.strip_suffix('/').unwrap_or(name);
+    !name.is_empty()
+        && !name.contains(['\\', '\0', ':'])
+        && name
+            .split('/')
+            .all(|segment| !matches!(segment, "" | "." | ".."))
+}
+
+impl<R: Read + Seek> ZipReader<R> {
+    /// Read the central directory, refusing archives outside `limits`.
+    pub fn new(mut input: R, limits: &Limits) -> io::Result<Self> {
+        let len = input.seek(SeekFrom::End(0))?;
+        if len < END_RECORD_LEN {
+            return Err(invalid("not a ZIP archive"));
+        }
+        // The end record is followed by a comment of at most 64 KiB.
+        let tail_len = len.min(END_RECORD_LEN + u64::from(u16::MAX));
+        input.seek(SeekFrom::Start(len - tail_len))?;
+        let mut tail = vec![0; tail_len as usize];
+        input.read_exact(&mut tail)?;
+        let end = (0..=tail.len() - END_RECORD_LEN as usize)
+            .rev()
+            .find(|&at| u32_at(&tail, at) == END_OF_CENTRAL_DIRECTORY)
+            .ok_or_else(|| invalid("not a ZIP archive"))?;
+        let record = &tail[end..];
+
+        let count = usize::from(u16_at(record, 10));
+        let directory_len = u32_at(record, 12);
+        let directory_start = u32_at(record, 16);
+        if u16_at(record, 4) != 0 || u16_at(record, 6) != 0 {
+            return Err(invalid("multi-disk archives are not supported"));
+        }
+        if count == usize::from(u16::MAX) || directory_start == u32::MAX {
+            return Err(invalid("ZIP64 archives are not supported"));
+        }
+        if count > limits.max_entries {
+            return Err(invalid(format!(
+                "archive has {count} entries (limit {})",
+                limits.max_entries
+            )));
+        }
+        if u64::from(directory_start) + u64::from(directory_len) > len {
+            return Err(invalid("central directory is out of bounds"));
+        }
+
+        input.seek(SeekFrom::Start(u64::from(directory_start)))?;
+        let mut directory = vec![0; directory_len as usize];
+        input.read_exact(&mut directory)?;
+
+        let mut entries = Vec::with_capacity(count);
+        let mut total = 0u64;
+        let mut at = 0;
+        for _ in 0..count {
+            let header = directory
+                .get(at..at + CENTRAL_HEADER_LEN)
+                .filter(|h| u32_at(h, 0) == CENTRAL_HEADER)
+                .ok_or_else(|| invalid("corrupt central directory"))?;
+            let flags = u16_at(header, 8);
+            let method = u16_at(header, 10);
+            let crc = u32_at(header, 16);
+            let compressed = u32_at(header, 20);
+            let size = u32_at(header, 24);
+            let name_len = usize::from(u16_at(header, 28));
+            let extra_len = usize::from(u16_at(header, 30));
+            let comment_len = usize::from(u16_at(header, 32));
+            let offset = u32_at(header, 42);
+            let name_start = at + CENTRAL_HEADER_LEN;
+            let name = directory
+                .get(name_start..name_start + name_len)
+                .ok_or_else(|| invalid("corrupt central directory"))?;
+            let name = String::from_utf8_lossy(name).into_owned();
+            at = name_start + name_len + extra_len + comment_len;
+
+            if !is_safe_name(&name) {
+                return Err(invalid(format!("unsafe entry name: {name}")));
+            }
+            if flags & FLAG_ENCRYPTED != 0 {
+                return Err(invalid(format!(
+                    "{name}: encrypted entries are not supported"
+                )));
+            }
+            if [compressed, size, offset].contains(&u32::MAX) {
+                return Err(invalid(format!("{name}: ZIP64 entries are not supported")));
+            }
+            let method = Method::from_code(method).ok_or_else(|| {
+                invalid(format!("{name}: unsupported compression method {method}"))
+            })?;
+            let (compressed, size) = (u64::from(compressed), u64::from(size));
+            if method == Method::Stored && compressed != size {
+                return Err(invalid(format!("{name}: stored entry sizes differ")));
+            }
+            if size > limits.max_entry_size {
+                return Err(invalid(format!(
+                    "{name}: {size} bytes uncompressed (limit {})",
+                    limits.max_entry_size
+                )));
+            }
+            if size > 1 << 20 && size / compressed.max(1) > limits.max_ratio {
+                return Err(invalid(format!(
+                    "{name}: compression ratio over {}:1",
+                    limits.max_ratio
+                )));
+            }
+            total += size;
+            if total > limits.max_total_size {
+                return Err(invalid(format!(
+                    "archive expands past {} bytes",
+                    limits.max_total_size
+                )));
+            }
+            if entries.iter().any(|e: &ZipEntry| e.name == name) {
+                return Err(invalid(format!("duplicate entry: {name}")));
+            }
+            entries.push(ZipEntry {
+                name,
+                method,
+                crc,
+                compressed,
+                size,
+                offset: u64::from(offset),
+            });
+        }
+        Ok(Self { input, entries })
+    }
+
+    /// Entries in central directory order.
+    pub fn entries(&self) -> &[ZipEntry] {
+        &self.entries
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&ZipEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// The uncompressed bytes of `name`, checked against its size and CRC.
+    pub fn read(&mut self, name: &str) -> io::Result<Vec<u8>> {
+        let entry = self
+            .entry(name)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no entry {name}")))?;
+        self.input.seek(SeekFrom::Start(entry.offset))?;
+        let mut header = [0; LOCAL_HEADER_LEN];
+        self.input.read_exact(&mut header)?;
+        if u32_at(&header, 0) != LOCAL_HEADER {
+            return Err(invalid(format!("{name}: corrupt local header")));
+        }
+        let skip = i64::from(u16_at(&header, 26)) + i64::from(u16_at(&header, 28));
+        self.input.seek(SeekFrom::Current(skip))?;
+
+        let compressed = (&mut self.input).take(entry.compressed);
+        // One byte past the declared size tells a lying header apart.
+        let limit = entry.size + 1;
+        let mut bytes = Vec::with_capacity(entry.size as usize);
+        match entry.method {
+            Method::Stored => compressed.take(limit).read_to_end(&mut bytes)?,
+            Method::Deflated => DeflateDecoder::new(compressed)
+                .take(limit)
+                .read_to_end(&mut bytes)?,
+        };
+        if bytes.len() as u64 != entry.size {
+            return Err(invalid(format!("{name}: size does not match its header")));
+        }
+        let mut crc = crc32fast::Hasher::new();
+        crc.update(&bytes);
+        if crc.finalize() != entry.crc {
+            return Err(invalid(format!("{name}: CRC mismatch")));
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn round_trips_and_refuses_unsafe_archives() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let text = "sajou ".repeat(1000);
+        zip.add_bytes("scene.json", Method::Deflated, text.as_bytes())
+            .unwrap();
+        zip.add_bytes("assets/sprites/a.png", Method::Stored, b"png")
+            .unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let mut reader = ZipReader::new(Cursor::new(bytes.clone()), &Limits::default()).unwrap();
+        let names: Vec<_> = reader.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["scene.json", "assets/sprites/a.png"]);
+        assert_eq!(reader.read("scene.json").unwrap(), text.as_bytes());
+        assert_eq!(reader.read("assets/sprites/a.png").unwrap(), b"png");
+
+        // Past the size limit: refused before inflating.
+        let tight = Limits {
+            max_total_size: 5000,
+            ..Limits::default()
+        };
+        assert!(ZipReader::new(Cursor::new(bytes.clone()), &tight).is_err());
+
+        // A header that understates the size is caught while inflating.
+        let mut lying = bytes;
+        let directory = lying.len()
+            - END_RECORD_LEN as usize
+            - 2 * CENTRAL_HEADER_LEN
+            - "scene.json".len()
+            - "assets/sprites/a.png".len();
+        lying[directory + 24..directory + 28].copy_from_slice(&10u32.to_le_bytes());
+        let mut reader = ZipReader::new(Cursor::new(lying), &Limits::default()).unwrap();
+        assert!(reader.read("scene.json").is_err());
+
+        for name in [
+            "../evil",
+            "/etc/passwd",
+            "a/../../b",
+            "C:/x",
+            "a\\..\\b",
+            "a//b",
+        ] {
+            assert!(!is_safe_name(name), "{name}");
+            let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+            zip.add_bytes(name, Method::Stored, b"x").unwrap();
+            let bytes = zip.finish().unwrap().into_inner();
+            assert!(ZipReader::new(Cursor::new(bytes), &Limits::default()).is_err());
+        }
+        assert!(is_safe_name("assets/gifs/"));
+    }
+}
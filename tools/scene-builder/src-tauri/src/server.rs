@@ -0,0 +1,123 @@
+//! Loopback HTTP listener hosted by the desktop app.
+//!
+//! Replaces the Node state server for packaged builds. The bound port is
+//! published in [`endpoint_file`] so `sajou-emit` and other local tools can
+//! find the running app without probing.
+
+use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+
+use axum::extract::Request;
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use tauri::{AppHandle, Manager};
+
+use crate::mcp;
+use crate::signals::{self, SignalHub};
+
+pub use sajou_client::endpoint::{endpoint_file, read_endpoint, ServerInfo, DEFAULT_PORT};
+
+/// Env var overriding the preferred port (shared with `@sajou/tap`).
+const PORT_ENV: &str = "SAJOU_PORT";
+
+fn write_endpoint(info: &ServerInfo) {
+    let Some(path) = endpoint_file() else { return };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(e) = std::fs::write(&path, serde_json::to_string(info).unwrap_or_default()) {
+        eprintln!("[sajou] cannot write {}: {e}", path.display());
+    }
+}
+
+/// Remove the endpoint file if it still points at this process.
+pub fn clear_endpoint() {
+    let Some(path) = endpoint_file() else { return };
+    if read_endpoint().is_some_and(|info| info.pid == std::process::id()) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Bind the preferred port on 127.0.0.1, falling back to an ephemeral one.
+fn bind() -> std::io::Result<TcpListener> {
+    let preferred = std::env::var(PORT_ENV)
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+    TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, preferred)))
+        .or_else(|_| TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))))
+}
+
+/// Permissive CORS, matching the Node server's `corsMiddleware`.
+async fn cors(req: Request, next: Next) -> Response {
+    let mut res = if req.method() == Method::OPTIONS {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        next.run(req).await
+    };
+    let headers = res.headers_mut();
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        HeaderValue::from_static("*"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static("GET, POST, PUT, DELETE, OPTIONS"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+        HeaderValue::from_static("Content-Type, Authorization, Mcp-Session-Id"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static("Mcp-Session-Id"),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_MAX_AGE,
+        HeaderValue::from_static("86400"),
+    );
+    res
+}
+
+/// Bind the listener, advertise it, and serve it on the Tauri async runtime.
+pub fn start(app: &AppHandle) -> std::io::Result<ServerInfo> {
+    let listener = bind()?;
+    listener.set_nonblocking(true)?;
+    let info = ServerInfo {
+        port: listener.local_addr()?.port(),
+        pid: std::process::id(),
+    };
+
+    let hub = SignalHub::new(app.clone());
+    app.manage(hub.clone());
+    app.manage(info);
+    write_endpoint(&info);
+
+    let router = Router::new()
+        .merge(signals::routes(hub.clone()))
+        .merge(mcp::http::routes(app.clone(), hub))
+        .layer(middleware::from_fn(cors));
+
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(l) => l,
+            Err(e) => {
+                eprintln!("[sajou] cannot register listener: {e}");
+                return;
+            }
+        };
+        if let Err(e) = axum::serve(listener, router).await {
+            eprintln!("[sajou] signal server stopped: {e}");
+        }
+    });
+
+    eprintln!("[sajou] Signal server on {}", info.base_url());
+    Ok(info)
+}
+
+/// Address of the embedded listener, for display in the signal source UI.
+#[tauri::command]
+pub fn signal_server_info(info: tauri::State<'_, ServerInfo>) -> ServerInfo {
+    *info
+}
@@ -0,0 +1,340 @@
+//! Signal parsing rules shared by every Rust ingestion path.
+//!
+//! Port of `src/simulator/signal-parser.ts`: HTTP POST normalisation, raw
+//! WebSocket/SSE message parsing, the well-known type set, and the JS value
+//! helpers the provider parsers in [`crate::sources`] are written with. Both
+//! implementations are checked against the same fixture corpus,
+//! `src/simulator/fixtures/signal-parser.json`.
+
+use serde_json::{json, Map, Value};
+
+use crate::signals::now_ms;
+
+pub use crate::sources::anthropic::parse_event as parse_anthropic_event;
+pub use crate::sources::openai::{parse_chunk as parse_openai_chunk, ChunkResult};
+pub use crate::sources::openclaw::parse_event as parse_openclaw_event;
+
+// ---------------------------------------------------------------------------
+// Known signal types
+// ---------------------------------------------------------------------------
+
+/// Mirrors `KNOWN_TYPES`.
+pub const KNOWN_TYPES: [&str; 14] = [
+    "task_dispatch",
+    "tool_call",
+    "tool_result",
+    "token_usage",
+    "agent_state_change",
+    "error",
+    "completion",
+    "text_delta",
+    "thinking",
+    "midi.note_on",
+    "midi.note_off",
+    "midi.control_change",
+    "midi.pitch_bend",
+    "midi.program_change",
+];
+
+pub fn is_known_type(kind: &str) -> bool {
+    KNOWN_TYPES.contains(&kind)
+}
+
+// ---------------------------------------------------------------------------
+// HTTP POST normalisation
+// ---------------------------------------------------------------------------
+
+/// Normalise a JSON body received via HTTP POST into a signal envelope.
+///
+/// Bodies with a string `type` are used as-is; anything else is wrapped as
+/// `{ type: "event", payload: body }`. Missing `id`, `timestamp`, `source`
+/// and `payload` are filled with defaults.
+pub fn normalize_http_post(body: Value) -> Value {
+    let mut envelope = match body {
+        Value::Object(map) if map.get("type").is_some_and(Value::is_string) => map,
+        other => {
+            let mut map = Map::new();
+            map.insert("type".into(), Value::from("event"));
+            map.insert("payload".into(), other);
+            map
+        }
+    };
+
+    if !truthy(envelope.get("id")) {
+        envelope.insert("id".into(), Value::from(uuid::Uuid::new_v4().to_string()));
+    }
+    if !truthy(envelope.get("timestamp")) {
+        envelope.insert("timestamp".into(), Value::from(now_ms()));
+    }
+    if !truthy(envelope.get("source")) {
+        envelope.insert("source".into(), Value::from("http"));
+    }
+    if !truthy(envelope.get("payload")) {
+        envelope.insert("payload".into(), Value::Object(Map::new()));
+    }
+
+    Value::Object(envelope)
+}
+
+// ---------------------------------------------------------------------------
+// WebSocket / SSE message parsing
+// ---------------------------------------------------------------------------
+
+/// Mirrors `ParseResult`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseResult {
+    /// A signal envelope ready for dispatch.
+    Signal(Value),
+    /// A `meta` message: not dispatched, only logged.
+    Meta { key: String, data: Value },
+    /// Not JSON (or `null`); carries the raw text.
+    Error(String),
+}
+
+/// Parse a raw JSON message into a signal.
+///
+/// 1. A known `type` → typed signal
+/// 2. A truthy `meta` → meta event
+/// 3. Anything else → generic `event` with the whole message as payload
+/// 4. Invalid JSON → error with the raw text
+pub fn parse_message(raw: &str) -> ParseResult {
+    let parsed = match serde_json::from_str::<Value>(raw) {
+        Ok(Value::Null) | Err(_) => return ParseResult::Error(raw.to_string()),
+        Ok(parsed) => parsed,
+    };
+    let id =
+        || coalesce([parsed.get("id")]).map_or_else(|| uuid::Uuid::new_v4().to_string(), js_string);
+    let timestamp = |fallback: Option<&Value>| {
+        coalesce([parsed.get("timestamp"), fallback]).map_or_else(|| now_ms().into(), js_number)
+    };
+    let source = |fallback: Option<&Value>| {
+        coalesce([parsed.get("source"), fallback]).map_or_else(|| "unknown".into(), js_string)
+    };
+
+    if let Some(kind) = parsed
+        .get("type")
+        .and_then(Value::as_str)
+        .filter(|k| is_known_type(k))
+    {
+        let payload = coalesce([parsed.get("payload")])
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        return ParseResult::Signal(Value::Object(record([
+            ("id", Some(id().into())),
+            ("type", Some(kind.into())),
+            ("timestamp", Some(timestamp(None))),
+            ("source", Some(source(None).into())),
+            ("correlationId", parsed.get("correlationId").cloned()),
+            ("payload", Some(payload)),
+        ])));
+    }
+
+    if let Some(meta) = parsed.get("meta").filter(|m| truthy(Some(m))) {
+        return ParseResult::Meta {
+            key: js_string(meta),
+            data: parsed.clone(),
+        };
+    }
+
+    let correlation_id = coalesce([parsed.get("correlationId"), parsed.get("runId")]).cloned();
+    ParseResult::Signal(Value::Object(record([
+        ("id", Some(id().into())),
+        ("type", Some("event".into())),
+        ("timestamp", Some(timestamp(parsed.get("ts")))),
+        ("source", Some(source(parsed.get("event")).into())),
+        ("correlationId", correlation_id),
+        ("payload", Some(parsed.clone())),
+    ])))
+}
+
+// ---------------------------------------------------------------------------
+// JS value helpers
+// ---------------------------------------------------------------------------
+
+/// `a ?? b ?? …` — the first value that is neither absent nor `null`.
+pub fn coalesce<const N: usize>(values: [Option<&Value>; N]) -> Option<&Value> {
+    values.into_iter().flatten().find(|v| !v.is_null())
+}
+
+/// JavaScript truthiness.
+pub fn truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(b)) => *b,
+        Some(Value::Number(n)) => n.as_f64().is_some_and(|f| f != 0.0),
+        Some(Value::String(s)) => !s.is_empty(),
+        Some(Value::Array(_) | Value::Object(_)) => true,
+    }
+}
+
+/// `String(value)`.
+pub fn js_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if f.fract() == 0.0 && f.abs() < 1e21 => format!("{}", f as i64),
+            _ => n.to_string(),
+        },
+        Value::Array(items) => items
+            .iter()
+            .map(|v| {
+                if v.is_null() {
+                    String::new()
+                } else {
+                    js_string(v)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        Value::Object(_) => "[object Object]".into(),
+        other => other.to_string(),
+    }
+}
+
+/// `Number(value)`; `NaN` serializes as `null`, like `JSON.stringify`.
+pub fn js_number(value: &Value) -> Value {
+    match value {
+        Value::Number(_) => value.clone(),
+        Value::Null => json!(0),
+        Value::Bool(b) => json!(u8::from(*b)),
+        Value::String(s) if s.trim().is_empty() => json!(0),
+        Value::String(s) => match s.trim().parse::<f64>() {
+            Ok(f) if f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 => json!(f as i64),
+            Ok(f) if f.is_finite() => json!(f),
+            _ => Value::Null,
+        },
+        _ => Value::Null,
+    }
+}
+
+/// Build an object, dropping absent (`undefined`) fields like `JSON.stringify`.
+pub fn record<const N: usize>(fields: [(&str, Option<Value>); N]) -> Map<String, Value> {
+    fields
+        .into_iter()
+        .filter_map(|(k, v)| v.map(|v| (k.to_string(), v)))
+        .collect()
+}
+
+/// A signal envelope stamped now with a fresh ID.
+pub fn envelope(kind: &str, source: &str, payload: Map<String, Value>) -> Value {
+    json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "type": kind,
+        "timestamp": now_ms(),
+        "source": source,
+        "payload": payload,
+    })
+}
+
+/// [`envelope`] for a signal that belongs to the flow `correlation_id`.
+pub fn correlated(
+    kind: &str,
+    source: &str,
+    correlation_id: &str,
+    payload: Map<String, Value>,
+) -> Value {
+    let mut signal = envelope(kind, source, payload);
+    signal["correlationId"] = correlation_id.into();
+    signal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shared with `signal-parser.test.ts`.
+    const CORPUS: &str = include_str!("../../src/simulator/fixtures/signal-parser.json");
+
+    /// Placeholder for IDs and timestamps the parsers generate.
+    const GENERATED: &str = "<generated>";
+
+    /// `actual` matches `expected`, where [`GENERATED`] stands for any truthy value.
+    fn matches(expected: &Value, actual: &Value) -> bool {
+        match (expected, actual) {
+            (Value::String(s), _) if s == GENERATED => truthy(Some(actual)),
+            (Value::Object(e), Value::Object(a)) => {
+                e.len() == a.len()
+                    && e.iter()
+                        .all(|(k, v)| a.get(k).is_some_and(|a| matches(v, a)))
+            }
+            (Value::Array(e), Value::Array(a)) => {
+                e.len() == a.len() && e.iter().zip(a).all(|(e, a)| matches(e, a))
+            }
+            _ => expected == actual,
+        }
+    }
+
+    fn cases<'a>(corpus: &'a Value, section: &str) -> &'a [Value] {
+        corpus[section].as_array().map_or(&[], Vec::as_slice)
+    }
+
+    fn check(case: &Value, actual: Value) {
+        assert!(
+            matches(&case["expected"], &actual),
+            "{}: expected {}, got {actual}",
+            case["name"],
+            case["expected"],
+        );
+    }
+
+    fn text<'a>(case: &'a Value, key: &str) -> &'a str {
+        case[key].as_str().unwrap_or_default()
+    }
+
+    #[test]
+    fn agrees_with_the_shared_fixture_corpus() {
+        let corpus: Value = serde_json::from_str(CORPUS).unwrap();
+        assert_eq!(corpus["knownTypes"], json!(KNOWN_TYPES));
+
+        for case in cases(&corpus, "normalizeHttpPost") {
+            check(case, normalize_http_post(case["input"].clone()));
+        }
+        for case in cases(&corpus, "parseMessage") {
+            let actual = match parse_message(text(case, "input")) {
+                ParseResult::Signal(signal) => json!({ "ok": true, "signal": signal }),
+                ParseResult::Meta { key, data } => {
+                    json!({ "ok": true, "meta": true, "key": key, "data": data })
+                }
+                ParseResult::Error(error) => json!({ "ok": false, "error": error }),
+            };
+            check(case, actual);
+        }
+        for case in cases(&corpus, "openaiChunk") {
+            let result = parse_openai_chunk(
+                &case["chunk"],
+                text(case, "model"),
+                text(case, "correlationId"),
+                case["tokenCount"].as_u64().unwrap_or(0),
+            );
+            check(
+                case,
+                json!({
+                    "signals": result.signals,
+                    "done": result.done,
+                    "tokenCount": result.token_count,
+                }),
+            );
+        }
+        for case in cases(&corpus, "anthropicEvent") {
+            let actual = parse_anthropic_event(
+                text(case, "eventType"),
+                &case["data"],
+                text(case, "model"),
+                text(case, "correlationId"),
+            );
+            // `{ skip: true }` and `null` both mean "no signal" here.
+            let actual = match actual {
+                Some(signal) => json!({ "signal": signal }),
+                None if case["expected"].is_null() => Value::Null,
+                None => json!({ "skip": true }),
+            };
+            check(case, actual);
+        }
+        for case in cases(&corpus, "openclawEvent") {
+            check(
+                case,
+                parse_openclaw_event(&case["event"]).unwrap_or(Value::Null),
+            );
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Signal ingestion — HTTP POST → webview event + SSE broadcast.
+//!
+//! Port of `packages/mcp-server/src/routes/signals.ts` for the desktop app;
+//! bodies are normalised by [`crate::signal_parser`].
+//! Envelopes pass through [`crate::validation`] first; every accepted one
+//! is emitted to the webview as [`SIGNAL_EVENT`] and
+//! re-broadcast on `GET /__signals__/stream` for non-webview consumers.
+
+use std::convert::Infallible;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::stream::{self, Stream};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+
+use crate::signal_parser::normalize_http_post;
+use crate::validation::Validator;
+
+/// Webview event carrying a normalised signal envelope.
+pub const SIGNAL_EVENT: &str = "signal://received";
+
+/// Buffered frames per SSE subscriber before it starts lagging.
+const BROADCAST_CAPACITY: usize = 1024;
+
+/// Fan-out point for every signal entering the Rust backend.
+#[derive(Clone)]
+pub struct SignalHub {
+    app: AppHandle,
+    tx: broadcast::Sender<String>,
+}
+
+impl SignalHub {
+    pub fn new(app: AppHandle) -> Self {
+        let (tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self { app, tx }
+    }
+
+    /// Forward an envelope to the webview and to all SSE subscribers.
+    pub fn publish(&self, envelope: &Value) {
+        if let Err(e) = self.app.emit(SIGNAL_EVENT, envelope) {
+            eprintln!("[sajou] failed to emit signal to webview: {e}");
+        }
+        // No subscribers is not an error — the webview is the primary consumer.
+        let _ = self.tx.send(envelope.to_string());
+    }
+
+    /// Validate an envelope arriving `via` an ingestion path, then publish
+    /// it. Returns the envelope as published, or why it was rejected.
+    pub fn ingest(&self, envelope: Value, via: &str) -> Result<Value, String> {
+        let envelope = self
+            .app
+            .state::<Validator>()
+            .admit(envelope, via)
+            .map_err(|errors| errors.join("; "))?;
+        self.publish(&envelope);
+        Ok(envelope)
+    }
+
+    /// Number of connected SSE clients.
+    pub fn client_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+}
+
+/// Current Unix epoch in milliseconds.
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Routes served by the loopback listener.
+pub fn routes(hub: SignalHub) -> Router {
+    Router::new()
+        .route("/api/signal", post(post_signal))
+        .route("/__signals__/stream", get(signal_stream))
+        .with_state(hub)
+}
+
+/// `POST /api/signal` — receive, normalise, validate, broadcast.
+async fn post_signal(State(hub): State<SignalHub>, body: Bytes) -> impl IntoResponse {
+    let body: Value = match serde_json::from_slice(&body) {
+        Ok(v) => v,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "ok": false, "error": format!("invalid JSON: {e}") })),
+            )
+        }
+    };
+
+    let envelope = match hub.ingest(normalize_http_post(body), "http") {
+        Ok(envelope) => envelope,
+        Err(error) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "ok": false, "error": error })),
+            )
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(json!({ "ok": true, "id": envelope["id"], "clients": hub.client_count() })),
+    )
+}
+
+/// `GET /__signals__/stream` — SSE endpoint.
+async fn signal_stream(
+    State(hub): State<SignalHub>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let rx = hub.tx.subscribe();
+    let connected = stream::once(async { Ok(Event::default().comment("connected")) });
+    let frames = stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(json) => return Some((Ok(Event::default().data(json)), rx)),
+                // A slow client skips missed frames instead of disconnecting.
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream::StreamExt::chain(connected, frames)).keep_alive(KeepAlive::default())
+}
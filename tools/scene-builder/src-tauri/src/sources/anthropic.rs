@@ -0,0 +1,556 @@
+//! Native Anthropic Messages streaming client.
+//!
+//! Port of `probeAnthropic` / `sendAnthropicPrompt` / `readAnthropicStream`
+//! in `src/views/signal-connection.ts` and `parseAnthropicEvent` in
+//! `src/simulator/signal-parser.ts`. The API key comes from
+//! [`crate::credentials`] and never crosses into the webview.
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+use tauri_plugin_http::reqwest;
+
+use super::sse::SseDecoder;
+use super::{with_task_id, SourceContext, Sources, StreamItem};
+use crate::credentials;
+use crate::signal_parser::{coalesce, correlated, js_number, js_string, record, truthy};
+
+/// Provider name in [`crate::credentials`].
+const PROVIDER: &str = "anthropic";
+
+/// `anthropic-version` header sent with every request.
+const API_VERSION: &str = "2023-06-01";
+
+/// Models offered when the API is reachable but does not list them.
+const DEFAULT_MODELS: [&str; 3] = [
+    "claude-sonnet-4-5-20250929",
+    "claude-haiku-4-5-20251001",
+    "claude-opus-4-6",
+];
+
+// ---------------------------------------------------------------------------
+// Event parsing
+// ---------------------------------------------------------------------------
+
+/// `String(value ?? fallback)`.
+fn text_or(value: Option<&Value>, fallback: &str) -> String {
+    coalesce([value]).map_or_else(|| fallback.to_string(), js_string)
+}
+
+/// Parse one SSE event of a Messages stream. `None` for events that carry
+/// no signal (pings, block stops, signature deltas…).
+///
+/// - `message_start` → `agent_state_change` (idle → acting)
+/// - `content_block_start` with a `tool_use` block → `tool_call`
+/// - `content_block_delta` `text_delta` / `thinking_delta` → `text_delta` / `thinking`
+/// - `message_delta` with `usage` → `token_usage`
+/// - `message_stop` → `completion`
+/// - `error` → `error`
+pub fn parse_event(
+    event_type: &str,
+    data: &Value,
+    model: &str,
+    correlation_id: &str,
+) -> Option<Value> {
+    let signal = |kind: &str, payload| correlated(kind, model, correlation_id, payload);
+    match event_type {
+        "message_start" => {
+            let msg_model = match data.get("message") {
+                Some(msg) if truthy(Some(msg)) => text_or(msg.get("model"), model),
+                _ => model.to_string(),
+            };
+            let payload = record([
+                ("agentId", Some(msg_model.as_str().into())),
+                ("from", Some("idle".into())),
+                ("to", Some("acting".into())),
+                ("reason", Some("message started".into())),
+            ]);
+            Some(correlated(
+                "agent_state_change",
+                &msg_model,
+                correlation_id,
+                payload,
+            ))
+        }
+        "content_block_start" => {
+            let block = data.get("content_block")?;
+            if block.get("type").and_then(Value::as_str) != Some("tool_use") {
+                return None;
+            }
+            let call_id = coalesce([block.get("id")])
+                .map_or_else(|| uuid::Uuid::new_v4().to_string(), js_string);
+            Some(signal(
+                "tool_call",
+                record([
+                    (
+                        "toolName",
+                        Some(text_or(block.get("name"), "unknown").into()),
+                    ),
+                    ("agentId", Some(model.into())),
+                    ("callId", Some(call_id.into())),
+                ]),
+            ))
+        }
+        "content_block_delta" => {
+            let delta = data.get("delta").filter(|d| truthy(Some(d)))?;
+            let (kind, field) = match delta.get("type").and_then(Value::as_str) {
+                Some("text_delta") => ("text_delta", "text"),
+                Some("thinking_delta") => ("thinking", "thinking"),
+                _ => return None,
+            };
+            Some(signal(
+                kind,
+                record([
+                    ("agentId", Some(model.into())),
+                    ("content", Some(text_or(delta.get(field), "").into())),
+                ]),
+            ))
+        }
+        "message_delta" => {
+            let usage = data.get("usage").filter(|u| truthy(Some(u)))?;
+            Some(signal(
+                "token_usage",
+                record([
+                    ("agentId", Some(model.into())),
+                    (
+                        "promptTokens",
+                        Some(js_number(usage.get("input_tokens").unwrap_or(&Value::Null))),
+                    ),
+                    (
+                        "completionTokens",
+                        Some(js_number(
+                            usage.get("output_tokens").unwrap_or(&Value::Null),
+                        )),
+                    ),
+                    ("model", Some(model.into())),
+                ]),
+            ))
+        }
+        "message_stop" => Some(signal(
+            "completion",
+            record([
+                ("agentId", Some(model.into())),
+                ("success", Some(true.into())),
+                ("result", Some("Anthropic stream completed".into())),
+            ]),
+        )),
+        "error" => {
+            let err = data.get("error").filter(|e| truthy(Some(e)));
+            let field = |key: &str, fallback: &str| match err {
+                Some(err) => text_or(err.get(key), fallback),
+                None => fallback.to_string(),
+            };
+            Some(signal(
+                "error",
+                record([
+                    ("agentId", Some(model.into())),
+                    ("message", Some(field("message", "Anthropic error").into())),
+                    ("code", Some(field("type", "ANTHROPIC_ERROR").into())),
+                    ("severity", Some("error".into())),
+                ]),
+            ))
+        }
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Stream decoding
+// ---------------------------------------------------------------------------
+
+/// Turns the bytes of a Messages SSE response into signals.
+pub struct MessageStream {
+    model: String,
+    correlation_id: String,
+    decoder: SseDecoder,
+    text_chunks: u64,
+}
+
+impl MessageStream {
+    pub fn new(model: &str, correlation_id: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            correlation_id: correlation_id.to_string(),
+            decoder: SseDecoder::default(),
+            text_chunks: 0,
+        }
+    }
+
+    /// Feed a chunk of the response body.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        for event in self.decoder.push(bytes) {
+            self.event(event.event.as_deref(), event.data.trim(), &mut items);
+        }
+        items
+    }
+
+    /// Flush whatever the response ended with.
+    pub fn finish(&mut self) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        if let Some(event) = self.decoder.finish() {
+            self.event(event.event.as_deref(), event.data.trim(), &mut items);
+        }
+        items
+    }
+
+    fn event(&mut self, event_type: Option<&str>, payload: &str, items: &mut Vec<StreamItem>) {
+        if payload.is_empty() {
+            return;
+        }
+        let Ok(data) = serde_json::from_str::<Value>(payload) else {
+            let head: String = payload.chars().take(100).collect();
+            items.push(StreamItem::Log {
+                level: "warn",
+                message: format!("[anthropic] Unparsed: {head}"),
+            });
+            return;
+        };
+        // Every Messages event repeats its name as `type`.
+        let event_type = event_type
+            .or_else(|| data.get("type").and_then(Value::as_str))
+            .unwrap_or_default();
+        let Some(signal) = parse_event(event_type, &data, &self.model, &self.correlation_id) else {
+            return;
+        };
+        let log = match signal["type"].as_str() {
+            Some("text_delta") => {
+                self.text_chunks += 1;
+                None
+            }
+            Some("completion") => Some((
+                "info",
+                format!(
+                    "Anthropic stream complete — {} text chunks.",
+                    self.text_chunks
+                ),
+            )),
+            Some("error") => Some((
+                "error",
+                format!(
+                    "Anthropic API error: {}",
+                    js_string(&signal["payload"]["message"])
+                ),
+            )),
+            _ => None,
+        };
+        items.push(StreamItem::Signal {
+            signal: with_task_id(signal, &self.correlation_id),
+            raw: payload.to_string(),
+        });
+        if let Some((level, message)) = log {
+            items.push(StreamItem::Log { level, message });
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Requests
+// ---------------------------------------------------------------------------
+
+fn api(url: &str, path: &str) -> String {
+    format!("{}{path}", url.trim_end_matches('/'))
+}
+
+/// Models served at `url`, like `probeAnthropic`: the listed ones, the
+/// defaults when the API does not list any, an error when the key is refused.
+pub async fn list_models(url: &str, api_key: &str) -> Result<Vec<String>, String> {
+    let response = reqwest::Client::new()
+        .get(api(url, "/v1/models"))
+        .header("x-api-key", api_key)
+        .header("anthropic-version", API_VERSION)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Anthropic probe error: {e}"))?;
+    let status = response.status();
+    if status.is_success() {
+        let body: Value = response
+            .text()
+            .await
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        let models: Vec<String> = body
+            .get("data")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|m| text_or(m.get("id"), "unknown"))
+            .collect();
+        if !models.is_empty() {
+            return Ok(models);
+        }
+    }
+    if matches!(status.as_u16(), 401 | 403) {
+        return Err(format!(
+            "Anthropic probe: auth error ({}) — API detected but key may be invalid.",
+            status.as_u16()
+        ));
+    }
+    Ok(DEFAULT_MODELS.iter().map(|m| m.to_string()).collect())
+}
+
+/// POST a streaming Messages request to `url` (the API base, without `/v1`)
+/// and hand every decoded item to `emit` as it arrives.
+pub async fn send(
+    url: &str,
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    correlation_id: &str,
+    mut emit: impl FnMut(StreamItem),
+) -> Result<(), String> {
+    let body = json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": prompt }],
+        "stream": true,
+        "max_tokens": 4096,
+    });
+    let mut response = reqwest::Client::new()
+        .post(api(url, "/v1/messages"))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("x-api-key", api_key)
+        .header("anthropic-version", API_VERSION)
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Anthropic request failed: {e}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        let reason = status.canonical_reason().unwrap_or_default();
+        return Err(format!(
+            "Anthropic request failed: HTTP {} {reason}",
+            status.as_u16()
+        ));
+    }
+
+    let mut decoder = MessageStream::new(model, correlation_id);
+    while let Some(bytes) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Anthropic stream error: {e}"))?
+    {
+        decoder.push(&bytes).into_iter().for_each(&mut emit);
+    }
+    decoder.finish().into_iter().for_each(emit);
+    Ok(())
+}
+
+async fn run(
+    ctx: SourceContext,
+    url: String,
+    api_key: String,
+    model: String,
+    prompt: String,
+) -> Result<(), String> {
+    let id = ctx.source_id().to_string();
+    let correlation_id = uuid::Uuid::new_v4().to_string();
+    ctx.debug(
+        "info",
+        &format!("[{id}] Sending prompt to Anthropic {model}…"),
+    );
+    ctx.dispatch_prompt(&prompt, &model, &correlation_id);
+
+    let sent = send(&url, &api_key, &model, &prompt, &correlation_id, |item| {
+        ctx.forward(item)
+    })
+    .await;
+    if let Err(e) = &sent {
+        ctx.debug("error", &format!("[{id}] {e}"));
+        return sent;
+    }
+    ctx.debug("info", &format!("[{id}] Anthropic response stream ended."));
+    Ok(())
+}
+
+fn stored_key(app: &AppHandle) -> Result<String, String> {
+    credentials::api_key(app, PROVIDER)?.ok_or_else(|| {
+        "No Anthropic API key configured. Set one in the source or ANTHROPIC_API_KEY.".into()
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Models available at `url`, probed with the backend-held key.
+#[tauri::command]
+pub async fn anthropic_probe(app: AppHandle, url: String) -> Result<Vec<String>, String> {
+    list_models(&url, &stored_key(&app)?).await
+}
+
+/// Stream a Messages response for `prompt` from `url`, authenticated with
+/// the backend-held key. Signals arrive as `source://signal`; resolves when
+/// the stream ends or is stopped with `source_stop_prompt`.
+#[tauri::command]
+pub async fn anthropic_prompt(
+    app: AppHandle,
+    sources: State<'_, Sources>,
+    source_id: String,
+    url: String,
+    model: String,
+    prompt: String,
+) -> Result<(), String> {
+    let api_key = stored_key(&app)?;
+    sources
+        .prompt(&app, &source_id, |ctx| {
+            run(ctx, url, api_key, model, prompt)
+        })
+        .await
+        .unwrap_or(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use super::*;
+
+    #[test]
+    fn maps_events_like_the_browser_parser() {
+        let start = json!({ "message": { "model": "claude-opus-4-6" } });
+        let signal = parse_event("message_start", &start, "fallback", "flow-1").unwrap();
+        assert_eq!(signal["type"], "agent_state_change");
+        assert_eq!(signal["source"], "claude-opus-4-6");
+        assert_eq!(signal["payload"]["to"], "acting");
+
+        let tool =
+            json!({ "content_block": { "type": "tool_use", "id": "toolu_1", "name": "grep" } });
+        let signal = parse_event("content_block_start", &tool, "m", "flow-1").unwrap();
+        assert_eq!(
+            signal["payload"],
+            json!({ "toolName": "grep", "agentId": "m", "callId": "toolu_1" })
+        );
+        let text = json!({ "content_block": { "type": "text", "text": "" } });
+        assert_eq!(
+            parse_event("content_block_start", &text, "m", "flow-1"),
+            None
+        );
+
+        let usage = json!({ "usage": { "output_tokens": 15 } });
+        let signal = parse_event("message_delta", &usage, "m", "flow-1").unwrap();
+        assert_eq!(signal["payload"]["promptTokens"], 0);
+        assert_eq!(signal["payload"]["completionTokens"], 15);
+        assert_eq!(parse_event("ping", &json!({}), "m", "flow-1"), None);
+    }
+
+    #[test]
+    fn streams_from_a_mock_server_with_the_backend_key() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Headers, then the body announced by Content-Length.
+            let head_end = loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                if let Some(i) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break i + 4;
+                }
+            };
+            let head = String::from_utf8_lossy(&request[..head_end]).to_lowercase();
+            let length: usize = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length: "))
+                .unwrap()
+                .trim()
+                .parse()
+                .unwrap();
+            while request.len() < head_end + length {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let events = [
+                (
+                    "message_start",
+                    r#"{"type":"message_start","message":{"model":"claude-haiku-4-5"}}"#,
+                ),
+                (
+                    "content_block_start",
+                    r#"{"type":"content_block_start","index":0,"content_block":{"type":"thinking","thinking":""}}"#,
+                ),
+                (
+                    "content_block_delta",
+                    r#"{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"Hmm"}}"#,
+                ),
+                (
+                    "content_block_delta",
+                    r#"{"type":"content_block_delta","index":1,"delta":{"type":"text_delta","text":"Hi"}}"#,
+                ),
+                (
+                    "content_block_start",
+                    r#"{"type":"content_block_start","index":2,"content_block":{"type":"tool_use","id":"toolu_9","name":"read_file","input":{}}}"#,
+                ),
+                ("ping", r#"{"type":"ping"}"#),
+                (
+                    "message_delta",
+                    r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":7}}"#,
+                ),
+                ("message_stop", r#"{"type":"message_stop"}"#),
+            ];
+            let mut body = String::new();
+            for (event, data) in events {
+                body.push_str(&format!("event: {event}\ndata: {data}\n\n"));
+            }
+            let response =
+                "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\n";
+            stream.write_all(response.as_bytes()).unwrap();
+            // Trickle the body so events straddle reads.
+            for piece in body.as_bytes().chunks(50) {
+                stream.write_all(piece).unwrap();
+                stream.flush().unwrap();
+            }
+            (
+                head,
+                String::from_utf8_lossy(&request[head_end..]).into_owned(),
+            )
+        });
+
+        let mut items = Vec::new();
+        tauri::async_runtime::block_on(send(
+            &url,
+            "sk-test",
+            "claude-haiku-4-5",
+            "hello",
+            "flow-1",
+            |item| items.push(item),
+        ))
+        .unwrap();
+
+        let (head, body) = server.join().unwrap();
+        assert!(head.starts_with("post /v1/messages "));
+        assert!(head.contains("x-api-key: sk-test\r\n"));
+        assert!(head.contains("anthropic-version: 2023-06-01\r\n"));
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["messages"][0]["content"], "hello");
+
+        let kinds: Vec<&str> = items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::Signal { signal, .. } => signal["type"].as_str(),
+                StreamItem::Log { .. } => None,
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "agent_state_change",
+                "thinking",
+                "text_delta",
+                "tool_call",
+                "token_usage",
+                "completion"
+            ]
+        );
+        assert_eq!(
+            items.last(),
+            Some(&StreamItem::Log {
+                level: "info",
+                message: "Anthropic stream complete — 1 text chunks.".into(),
+            })
+        );
+    }
+}
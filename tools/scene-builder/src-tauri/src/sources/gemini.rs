@@ -0,0 +1,505 @@
+//! Native Gemini streaming client.
+//!
+//! Streams `models/{model}:streamGenerateContent?alt=sse` and maps each
+//! `GenerateContentResponse` to the well-known signal types, like the OpenAI
+//! and Anthropic clients: text parts, thought parts and function calls as
+//! they arrive, `usageMetadata` and the finish reason once the candidate is
+//! done. The API key comes from [`crate::credentials`].
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+use tauri_plugin_http::reqwest;
+
+use super::openai::ChunkResult;
+use super::sse::SseDecoder;
+use super::{SourceContext, Sources, StreamItem};
+use crate::credentials;
+use crate::signal_parser::{coalesce, correlated, js_string, record, truthy};
+
+/// Provider name in [`crate::credentials`].
+const PROVIDER: &str = "gemini";
+
+/// API version prefix of every endpoint.
+const API_VERSION: &str = "v1beta";
+
+/// Finish reasons of a generation that ran to its normal end.
+const NORMAL_FINISH: [&str; 2] = ["STOP", "MAX_TOKENS"];
+
+// ---------------------------------------------------------------------------
+// Chunk parsing
+// ---------------------------------------------------------------------------
+
+/// A `*TokenCount` field, absent as 0.
+fn token_field(usage: &Value, key: &str) -> u64 {
+    usage.get(key).and_then(Value::as_u64).unwrap_or(0)
+}
+
+/// Parse one `GenerateContentResponse` of the stream.
+///
+/// - text part → `text_delta`; with `thought: true` → `thinking`
+/// - `functionCall` part → `tool_call`
+/// - `finishReason` → `token_usage` (from `usageMetadata`) then `completion`
+/// - `error` or a blocked prompt → `error`
+pub fn parse_chunk(
+    chunk: &Value,
+    model: &str,
+    correlation_id: &str,
+    token_count: u64,
+) -> ChunkResult {
+    let mut signals = Vec::new();
+    let mut count_now = token_count;
+    let signal = |kind: &str, payload| correlated(kind, model, correlation_id, payload);
+    let agent_id =
+        coalesce([chunk.get("modelVersion")]).map_or_else(|| model.to_string(), js_string);
+
+    let blocked = chunk.pointer("/promptFeedback/blockReason");
+    if truthy(chunk.get("error")) || truthy(blocked) {
+        let (message, code) = match blocked.filter(|b| truthy(Some(b))) {
+            Some(reason) => (
+                format!("Prompt blocked: {}", js_string(reason)),
+                js_string(reason),
+            ),
+            None => {
+                let err = &chunk["error"];
+                let text = |key: &str, fallback: &str| {
+                    coalesce([err.get(key)]).map_or_else(|| fallback.to_string(), js_string)
+                };
+                (
+                    text("message", "Gemini error"),
+                    text("status", "GEMINI_ERROR"),
+                )
+            }
+        };
+        signals.push(signal(
+            "error",
+            record([
+                ("agentId", Some(agent_id.as_str().into())),
+                ("message", Some(message.into())),
+                ("code", Some(code.into())),
+                ("severity", Some("error".into())),
+            ]),
+        ));
+        return ChunkResult {
+            signals,
+            done: true,
+            token_count: count_now,
+        };
+    }
+
+    let Some(candidate) = chunk
+        .get("candidates")
+        .and_then(Value::as_array)
+        .and_then(|c| c.first())
+    else {
+        return ChunkResult {
+            signals,
+            done: false,
+            token_count: count_now,
+        };
+    };
+
+    let parts = candidate
+        .pointer("/content/parts")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten();
+    for part in parts {
+        if let Some(call) = part.get("functionCall") {
+            let call_id = coalesce([call.get("id")])
+                .map_or_else(|| uuid::Uuid::new_v4().to_string(), js_string);
+            let name = coalesce([call.get("name")]).map_or_else(|| "unknown".into(), js_string);
+            signals.push(signal(
+                "tool_call",
+                record([
+                    ("toolName", Some(name.into())),
+                    ("agentId", Some(agent_id.as_str().into())),
+                    ("callId", Some(call_id.into())),
+                    ("input", call.get("args").filter(|a| a.is_object()).cloned()),
+                ]),
+            ));
+        } else if let Some(text) = part.get("text").filter(|t| truthy(Some(t))) {
+            let payload = |index: Option<Value>| {
+                record([
+                    ("agentId", Some(agent_id.as_str().into())),
+                    ("content", Some(text.clone())),
+                    ("index", index),
+                ])
+            };
+            if part.get("thought").and_then(Value::as_bool) == Some(true) {
+                signals.push(signal("thinking", payload(None)));
+            } else {
+                count_now += 1;
+                signals.push(signal("text_delta", payload(Some((count_now - 1).into()))));
+            }
+        }
+    }
+
+    let finish = candidate
+        .get("finishReason")
+        .and_then(Value::as_str)
+        .filter(|r| !r.is_empty() && *r != "FINISH_REASON_UNSPECIFIED");
+    if let Some(reason) = finish {
+        if let Some(usage) = chunk.get("usageMetadata").filter(|u| u.is_object()) {
+            // Thinking tokens are billed as output.
+            let completion = token_field(usage, "candidatesTokenCount")
+                + token_field(usage, "thoughtsTokenCount");
+            signals.push(signal(
+                "token_usage",
+                record([
+                    ("agentId", Some(agent_id.as_str().into())),
+                    (
+                        "promptTokens",
+                        Some(token_field(usage, "promptTokenCount").into()),
+                    ),
+                    ("completionTokens", Some(completion.into())),
+                    ("model", Some(agent_id.as_str().into())),
+                ]),
+            ));
+        }
+        let normal = NORMAL_FINISH.contains(&reason);
+        let result = if reason == "STOP" {
+            format!("Stream completed ({count_now} chunks)")
+        } else {
+            format!("Stream ended: {reason} ({count_now} chunks)")
+        };
+        signals.push(signal(
+            "completion",
+            record([
+                ("taskId", Some(correlation_id.into())),
+                ("agentId", Some(agent_id.as_str().into())),
+                ("success", Some(normal.into())),
+                ("result", Some(result.into())),
+            ]),
+        ));
+    }
+
+    ChunkResult {
+        signals,
+        done: finish.is_some(),
+        token_count: count_now,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Stream decoding
+// ---------------------------------------------------------------------------
+
+/// Turns the bytes of a `streamGenerateContent` SSE response into signals.
+pub struct ContentStream {
+    model: String,
+    correlation_id: String,
+    decoder: SseDecoder,
+    token_count: u64,
+}
+
+impl ContentStream {
+    pub fn new(model: &str, correlation_id: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            correlation_id: correlation_id.to_string(),
+            decoder: SseDecoder::default(),
+            token_count: 0,
+        }
+    }
+
+    /// Feed a chunk of the response body.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        for event in self.decoder.push(bytes) {
+            self.data(event.data.trim(), &mut items);
+        }
+        items
+    }
+
+    /// Flush whatever the response ended with.
+    pub fn finish(&mut self) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        if let Some(event) = self.decoder.finish() {
+            self.data(event.data.trim(), &mut items);
+        }
+        items
+    }
+
+    fn data(&mut self, payload: &str, items: &mut Vec<StreamItem>) {
+        if payload.is_empty() {
+            return;
+        }
+        let Ok(chunk) = serde_json::from_str::<Value>(payload) else {
+            let head: String = payload.chars().take(100).collect();
+            items.push(StreamItem::Log {
+                level: "warn",
+                message: format!("[gemini] Unparsed: {head}"),
+            });
+            return;
+        };
+        let result = parse_chunk(&chunk, &self.model, &self.correlation_id, self.token_count);
+        self.token_count = result.token_count;
+        items.extend(result.signals.into_iter().map(|signal| StreamItem::Signal {
+            signal,
+            raw: payload.to_string(),
+        }));
+        if result.done {
+            items.push(StreamItem::Log {
+                level: "info",
+                message: format!("Generation finished — {} tokens.", self.token_count),
+            });
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Requests
+// ---------------------------------------------------------------------------
+
+/// Model ID without the `models/` resource prefix.
+fn model_id(name: &str) -> &str {
+    name.strip_prefix("models/").unwrap_or(name)
+}
+
+fn api(url: &str, path: &str) -> String {
+    format!("{}/{API_VERSION}{path}", url.trim_end_matches('/'))
+}
+
+/// Whether `model` can be asked for its thoughts; older generations reject
+/// `thinkingConfig`.
+fn supports_thinking(model: &str) -> bool {
+    !["gemini-1.0", "gemini-1.5", "gemini-2.0", "gemma"]
+        .iter()
+        .any(|prefix| model.starts_with(prefix))
+}
+
+/// Body of a streaming request for a single user prompt.
+fn request_body(model: &str, prompt: &str) -> Value {
+    let mut body = json!({
+        "contents": [{ "role": "user", "parts": [{ "text": prompt }] }],
+    });
+    if supports_thinking(model) {
+        body["generationConfig"] = json!({ "thinkingConfig": { "includeThoughts": true } });
+    }
+    body
+}
+
+/// Error of a non-2xx response, with the API's `error.message`.
+async fn http_error(context: &str, response: reqwest::Response) -> String {
+    let status = response.status();
+    let reason = status.canonical_reason().unwrap_or_default();
+    let detail = response
+        .text()
+        .await
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .and_then(|body| body.pointer("/error/message").map(js_string))
+        .map(|e| format!(": {e}"))
+        .unwrap_or_default();
+    format!("{context}: HTTP {} {reason}{detail}", status.as_u16())
+}
+
+/// Models at `url` that support `generateContent`.
+pub async fn list_models(url: &str, api_key: &str) -> Result<Vec<String>, String> {
+    let response = reqwest::Client::new()
+        .get(api(url, "/models?pageSize=1000"))
+        .header("x-goog-api-key", api_key)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Gemini probe error: {e}"))?;
+    if !response.status().is_success() {
+        return Err(http_error("Gemini probe failed", response).await);
+    }
+    let raw = response.text().await.map_err(|e| e.to_string())?;
+    let body: Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    let generates = |m: &Value| match m.get("supportedGenerationMethods") {
+        Some(Value::Array(methods)) => methods.iter().any(|x| x == "generateContent"),
+        _ => true,
+    };
+    Ok(body
+        .get("models")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|m| generates(m))
+        .filter_map(|m| m.get("name").and_then(Value::as_str))
+        .map(|name| model_id(name).to_string())
+        .collect())
+}
+
+/// POST a streaming request to `url` (the API base, without the version)
+/// and hand every decoded item to `emit` as it arrives.
+pub async fn send(
+    url: &str,
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    correlation_id: &str,
+    mut emit: impl FnMut(StreamItem),
+) -> Result<(), String> {
+    let model = model_id(model);
+    let endpoint = api(
+        url,
+        &format!("/models/{model}:streamGenerateContent?alt=sse"),
+    );
+    let mut response = reqwest::Client::new()
+        .post(endpoint)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("x-goog-api-key", api_key)
+        .body(request_body(model, prompt).to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Gemini request failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(http_error("Gemini request failed", response).await);
+    }
+
+    let mut decoder = ContentStream::new(model, correlation_id);
+    while let Some(bytes) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Gemini stream error: {e}"))?
+    {
+        decoder.push(&bytes).into_iter().for_each(&mut emit);
+    }
+    decoder.finish().into_iter().for_each(emit);
+    Ok(())
+}
+
+async fn run(
+    ctx: SourceContext,
+    url: String,
+    api_key: String,
+    model: String,
+    prompt: String,
+) -> Result<(), String> {
+    let id = ctx.source_id().to_string();
+    let correlation_id = uuid::Uuid::new_v4().to_string();
+    ctx.debug("info", &format!("[{id}] Sending prompt to Gemini {model}…"));
+    ctx.dispatch_prompt(&prompt, &model, &correlation_id);
+
+    let sent = send(&url, &api_key, &model, &prompt, &correlation_id, |item| {
+        ctx.forward(item)
+    })
+    .await;
+    if let Err(e) = &sent {
+        ctx.debug("error", &format!("[{id}] {e}"));
+        return sent;
+    }
+    ctx.debug("info", &format!("[{id}] Gemini response stream ended."));
+    Ok(())
+}
+
+fn stored_key(app: &AppHandle) -> Result<String, String> {
+    credentials::api_key(app, PROVIDER)?.ok_or_else(|| {
+        "No Gemini API key configured. Set one in the source or GEMINI_API_KEY.".into()
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Models available at `url`, probed with the backend-held key.
+#[tauri::command]
+pub async fn gemini_probe(app: AppHandle, url: String) -> Result<Vec<String>, String> {
+    list_models(&url, &stored_key(&app)?).await
+}
+
+/// Stream a response for `prompt` from `url`, authenticated with the
+/// backend-held key. Signals arrive as `source://signal`; resolves when the
+/// stream ends or is stopped with `source_stop_prompt`.
+#[tauri::command]
+pub async fn gemini_prompt(
+    app: AppHandle,
+    sources: State<'_, Sources>,
+    source_id: String,
+    url: String,
+    model: String,
+    prompt: String,
+) -> Result<(), String> {
+    let api_key = stored_key(&app)?;
+    sources
+        .prompt(&app, &source_id, |ctx| {
+            run(ctx, url, api_key, model, prompt)
+        })
+        .await
+        .unwrap_or(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_parts_and_final_usage() {
+        let chunk = json!({
+            "modelVersion": "gemini-2.5-flash",
+            "candidates": [{ "content": { "role": "model", "parts": [
+                { "text": "Plan first.", "thought": true },
+                { "text": "Checking." },
+                { "functionCall": { "name": "get_weather", "args": { "city": "Lyon" } } },
+            ]}}],
+        });
+        let result = parse_chunk(&chunk, "gemini-2.5-flash", "flow-1", 4);
+        let kinds: Vec<&Value> = result.signals.iter().map(|s| &s["type"]).collect();
+        assert_eq!(kinds, ["thinking", "text_delta", "tool_call"]);
+        assert_eq!(result.signals[1]["payload"]["index"], 4);
+        assert_eq!(result.signals[2]["payload"]["toolName"], "get_weather");
+        assert_eq!(
+            result.signals[2]["payload"]["input"],
+            json!({ "city": "Lyon" })
+        );
+        assert_eq!(result.token_count, 5);
+        assert!(!result.done);
+
+        let last = json!({
+            "candidates": [{ "content": { "parts": [{ "text": "" }] }, "finishReason": "MAX_TOKENS" }],
+            "usageMetadata": { "promptTokenCount": 9, "candidatesTokenCount": 40, "thoughtsTokenCount": 12 },
+        });
+        let result = parse_chunk(&last, "gemini-2.5-flash", "flow-1", 5);
+        assert!(result.done);
+        assert_eq!(result.signals[0]["payload"]["promptTokens"], 9);
+        assert_eq!(result.signals[0]["payload"]["completionTokens"], 52);
+        assert_eq!(result.signals[1]["payload"]["success"], true);
+        assert_eq!(
+            result.signals[1]["payload"]["result"],
+            "Stream ended: MAX_TOKENS (5 chunks)"
+        );
+
+        let blocked = json!({ "promptFeedback": { "blockReason": "SAFETY" } });
+        let result = parse_chunk(&blocked, "m", "flow-1", 0);
+        assert_eq!(result.signals[0]["type"], "error");
+        assert_eq!(result.signals[0]["payload"]["code"], "SAFETY");
+    }
+
+    #[test]
+    fn decodes_a_split_stream_and_shapes_the_request() {
+        let body = concat!(
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Hel\"}]}}]}\r\n\r\n",
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"lo\"}]},\"finishReason\":\"STOP\"}],",
+            "\"usageMetadata\":{\"promptTokenCount\":2,\"candidatesTokenCount\":2}}\r\n\r\n",
+        );
+        let mut stream = ContentStream::new("gemini-2.5-pro", "flow-1");
+        let mut items = Vec::new();
+        for piece in body.as_bytes().chunks(13) {
+            items.extend(stream.push(piece));
+        }
+        items.extend(stream.finish());
+        let kinds: Vec<&str> = items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::Signal { signal, .. } => signal["type"].as_str(),
+                StreamItem::Log { .. } => None,
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            ["text_delta", "text_delta", "token_usage", "completion"]
+        );
+
+        assert!(request_body("gemini-2.5-pro", "hi")
+            .get("generationConfig")
+            .is_some());
+        assert!(request_body("gemini-2.0-flash", "hi")
+            .get("generationConfig")
+            .is_none());
+        assert_eq!(model_id("models/gemini-2.5-pro"), "gemini-2.5-pro");
+    }
+}
@@ -0,0 +1,314 @@
+//! Native signal source connections.
+//!
+//! Rust-side counterparts of the transports in
+//! `src/views/signal-connection.ts`. Each connection runs as a backend task,
+//! so it survives webview reloads; its signals, status changes and log lines
+//! are streamed to the webview as [`SIGNAL_EVENT`], [`STATUS_EVENT`] and
+//! [`DEBUG_EVENT`], and `source_statuses` lets a reloaded webview catch up.
+//! Prompts sent to LLM sources stream the same way, one at a time per source.
+
+pub mod anthropic;
+pub mod gemini;
+pub mod ollama;
+pub mod openai;
+pub mod openclaw;
+pub mod sse;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use futures_util::future::{abortable, AbortHandle};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::signal_parser::{correlated, record};
+use crate::validation::Validator;
+
+/// Webview event carrying a signal from a native source.
+pub const SIGNAL_EVENT: &str = "source://signal";
+
+/// Webview event carrying a native source's status change.
+pub const STATUS_EVENT: &str = "source://status";
+
+/// Webview event carrying a native source's log line.
+pub const DEBUG_EVENT: &str = "source://debug";
+
+/// Mirrors `ConnectionStatus` in `signal-connection.ts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Status {
+    Disconnected,
+    Connecting,
+    Connected,
+    Error,
+}
+
+/// Status of one native source, as sent with [`STATUS_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceStatus {
+    pub source_id: String,
+    pub status: Status,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceSignal<'a> {
+    source_id: &'a str,
+    signal: &'a Value,
+    /// The message as received, for the raw log.
+    raw: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceDebug<'a> {
+    source_id: &'a str,
+    message: &'a str,
+    level: &'a str,
+}
+
+/// What a stream decoder produced from a response.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamItem {
+    /// A signal envelope and the payload it was parsed from.
+    Signal { signal: Value, raw: String },
+    /// A line for the connection log.
+    Log {
+        level: &'static str,
+        message: String,
+    },
+}
+
+struct Connection {
+    task: JoinHandle<()>,
+    status: SourceStatus,
+}
+
+/// Managed state: running native connections and prompts by source ID.
+#[derive(Default)]
+pub struct Sources {
+    connections: Mutex<HashMap<String, Connection>>,
+    /// In-flight prompt streams, tagged so a finished prompt only clears its
+    /// own entry.
+    prompts: Mutex<HashMap<String, (u64, AbortHandle)>>,
+    next_prompt: AtomicU64,
+}
+
+impl Sources {
+    /// Start `run` as the connection for `source_id`, replacing any previous one.
+    pub fn spawn<F>(&self, app: &AppHandle, source_id: &str, run: impl FnOnce(SourceContext) -> F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let ctx = SourceContext {
+            app: app.clone(),
+            source_id: source_id.to_string(),
+        };
+        let status = SourceStatus {
+            source_id: source_id.to_string(),
+            status: Status::Connecting,
+            error: None,
+        };
+        // Hold the lock across the spawn so the task's first status update
+        // finds its entry.
+        let mut connections = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+        let task = tauri::async_runtime::spawn(run(ctx));
+        if let Some(old) = connections.insert(source_id.to_string(), Connection { task, status }) {
+            old.task.abort();
+        }
+    }
+
+    /// Stop the connection for `source_id`. Returns whether one was running.
+    pub fn stop(&self, source_id: &str) -> bool {
+        let mut connections = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+        match connections.remove(source_id) {
+            Some(connection) => {
+                connection.task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Run `run` as the prompt of `source_id`, cancelling any previous one.
+    /// Resolves to `None` when the prompt was stopped.
+    pub async fn prompt<F>(
+        &self,
+        app: &AppHandle,
+        source_id: &str,
+        run: impl FnOnce(SourceContext) -> F,
+    ) -> Option<F::Output>
+    where
+        F: Future,
+    {
+        let ctx = SourceContext {
+            app: app.clone(),
+            source_id: source_id.to_string(),
+        };
+        let (stream, handle) = abortable(run(ctx));
+        let tag = self.next_prompt.fetch_add(1, Ordering::Relaxed);
+        {
+            let mut prompts = self.prompts.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some((_, old)) = prompts.insert(source_id.to_string(), (tag, handle)) {
+                old.abort();
+            }
+        }
+        let result = stream.await.ok();
+        let mut prompts = self.prompts.lock().unwrap_or_else(|e| e.into_inner());
+        if prompts.get(source_id).is_some_and(|(t, _)| *t == tag) {
+            prompts.remove(source_id);
+        }
+        result
+    }
+
+    /// Stop the prompt streaming for `source_id`. Returns whether one was.
+    pub fn stop_prompt(&self, source_id: &str) -> bool {
+        let mut prompts = self.prompts.lock().unwrap_or_else(|e| e.into_inner());
+        match prompts.remove(source_id) {
+            Some((_, handle)) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_status(&self, status: &SourceStatus) {
+        let mut connections = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(connection) = connections.get_mut(&status.source_id) {
+            connection.status = status.clone();
+        }
+    }
+
+    /// Last known status of every native source.
+    pub fn statuses(&self) -> Vec<SourceStatus> {
+        let connections = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+        connections.values().map(|c| c.status.clone()).collect()
+    }
+}
+
+/// Handle a connection task uses to report to the webview.
+#[derive(Clone)]
+pub struct SourceContext {
+    app: AppHandle,
+    source_id: String,
+}
+
+impl SourceContext {
+    pub fn source_id(&self) -> &str {
+        &self.source_id
+    }
+
+    /// Record and announce a status change.
+    pub fn status(&self, status: Status, error: Option<String>) {
+        let status = SourceStatus {
+            source_id: self.source_id.clone(),
+            status,
+            error,
+        };
+        self.app.state::<Sources>().set_status(&status);
+        let _ = self.app.emit(STATUS_EVENT, status);
+    }
+
+    /// Validate a signal envelope and forward it to the webview.
+    pub fn signal(&self, signal: &Value, raw: &str) {
+        let via = format!("source:{}", self.source_id);
+        let signal = match self.app.state::<Validator>().admit(signal.clone(), &via) {
+            Ok(signal) => signal,
+            Err(errors) => {
+                self.debug("warn", &format!("Rejected signal: {}", errors.join("; ")));
+                return;
+            }
+        };
+        let payload = SourceSignal {
+            source_id: &self.source_id,
+            signal: &signal,
+            raw,
+        };
+        if let Err(e) = self.app.emit(SIGNAL_EVENT, payload) {
+            eprintln!("[sajou] failed to emit signal to webview: {e}");
+        }
+    }
+
+    /// Send a line to the webview's connection log.
+    pub fn debug(&self, level: &str, message: &str) {
+        let payload = SourceDebug {
+            source_id: &self.source_id,
+            message,
+            level,
+        };
+        let _ = self.app.emit(DEBUG_EVENT, payload);
+    }
+
+    /// Forward decoder output; log lines get the `[sourceId]` prefix.
+    pub fn forward(&self, item: StreamItem) {
+        match item {
+            StreamItem::Signal { signal, raw } => self.signal(&signal, &raw),
+            StreamItem::Log { level, message } => {
+                self.debug(level, &format!("[{}] {message}", self.source_id))
+            }
+        }
+    }
+
+    /// Announce a prompt as the `task_dispatch` that opens its flow.
+    pub fn dispatch_prompt(&self, prompt: &str, model: &str, correlation_id: &str) {
+        let signal = prompt_dispatch(prompt, model, correlation_id);
+        let raw = json!({ "prompt": prompt, "model": model }).to_string();
+        self.signal(&signal, &raw);
+    }
+}
+
+/// Name the prompt's flow as the `taskId` of a `completion`, which the
+/// schema requires and the browser-shaped provider parsers leave out.
+pub fn with_task_id(mut signal: Value, correlation_id: &str) -> Value {
+    if signal["type"] == "completion" && signal["payload"].get("taskId").is_none() {
+        signal["payload"]["taskId"] = correlation_id.into();
+    }
+    signal
+}
+
+/// The `task_dispatch` of a prompt: the flow is the task, sent by the
+/// user to the model.
+pub fn prompt_dispatch(prompt: &str, model: &str, correlation_id: &str) -> Value {
+    let mut signal = correlated(
+        "task_dispatch",
+        "user",
+        correlation_id,
+        record([
+            ("taskId", Some(correlation_id.into())),
+            ("from", Some("user".into())),
+            ("to", Some(model.into())),
+            ("description", Some(prompt.into())),
+        ]),
+    );
+    signal["metadata"] = json!({ "model": model });
+    signal
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Stop a native source. Returns whether it was running.
+#[tauri::command]
+pub fn source_disconnect(sources: State<'_, Sources>, source_id: String) -> bool {
+    sources.stop(&source_id)
+}
+
+/// Stop the prompt a source is streaming. Returns whether one was.
+#[tauri::command]
+pub fn source_stop_prompt(sources: State<'_, Sources>, source_id: String) -> bool {
+    sources.stop_prompt(&source_id)
+}
+
+/// Status of every native source, for a webview that just (re)loaded.
+#[tauri::command]
+pub fn source_statuses(sources: State<'_, Sources>) -> Vec<SourceStatus> {
+    sources.statuses()
+}
@@ -0,0 +1,453 @@
+//! Native Ollama client.
+//!
+//! Speaks Ollama's own `/api/chat` NDJSON stream rather than its OpenAI
+//! compatibility layer, which drops `thinking`, the real token counts
+//! (`prompt_eval_count` / `eval_count`) and the timings. Chunks map to the
+//! same signals as `parseOpenAIChunk`; the final chunk adds a `token_usage`
+//! with those counts, its durations (in milliseconds) going in the signal's
+//! `metadata` so the payload stays within the schema.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+use tauri_plugin_http::reqwest;
+
+use super::openai::ChunkResult;
+use super::{SourceContext, Sources, StreamItem};
+use crate::signal_parser::{coalesce, correlated, js_string, record, truthy};
+
+/// One installed model, as listed by `/api/tags`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct OllamaModel {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub modified_at: Option<String>,
+    #[serde(default)]
+    pub details: ModelDetails,
+}
+
+/// `details` of an `/api/tags` entry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct ModelDetails {
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub family: Option<String>,
+    #[serde(default)]
+    pub parameter_size: Option<String>,
+    #[serde(default)]
+    pub quantization_level: Option<String>,
+}
+
+/// Models of an `/api/tags` body; malformed entries are skipped.
+pub fn models_from_tags(tags: &Value) -> Vec<OllamaModel> {
+    tags.get("models")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|m| serde_json::from_value(m.clone()).ok())
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Chunk parsing
+// ---------------------------------------------------------------------------
+
+/// Nanoseconds → milliseconds, kept to the microsecond.
+fn millis(value: Option<&Value>) -> Option<Value> {
+    let ns = value?.as_u64()?;
+    Some(json!((ns / 1_000) as f64 / 1_000.0))
+}
+
+/// Parse one `/api/chat` stream line.
+///
+/// - `message.thinking` → `thinking`
+/// - `message.content` → `text_delta`
+/// - `done` → `token_usage` (counts and durations) then `completion`
+/// - `error` → `error`
+pub fn parse_chunk(
+    chunk: &Value,
+    model: &str,
+    correlation_id: &str,
+    token_count: u64,
+) -> ChunkResult {
+    let mut signals = Vec::new();
+    let mut count_now = token_count;
+    let signal = |kind: &str, payload| correlated(kind, model, correlation_id, payload);
+    let agent_id = || coalesce([chunk.get("model")]).map_or_else(|| model.to_string(), js_string);
+
+    if let Some(err) = chunk.get("error").filter(|e| truthy(Some(e))) {
+        signals.push(signal(
+            "error",
+            record([
+                ("agentId", Some(model.into())),
+                ("message", Some(js_string(err).into())),
+                ("code", Some("OLLAMA_ERROR".into())),
+                ("severity", Some("error".into())),
+            ]),
+        ));
+        return ChunkResult {
+            signals,
+            done: true,
+            token_count: count_now,
+        };
+    }
+
+    if let Some(message) = chunk.get("message") {
+        if let Some(thinking) = message.get("thinking").filter(|v| truthy(Some(v))) {
+            signals.push(signal(
+                "thinking",
+                record([
+                    ("agentId", Some(agent_id().into())),
+                    ("content", Some(thinking.clone())),
+                ]),
+            ));
+        }
+        if let Some(content) = message.get("content").filter(|v| truthy(Some(v))) {
+            count_now += 1;
+            signals.push(signal(
+                "text_delta",
+                record([
+                    ("agentId", Some(agent_id().into())),
+                    ("content", Some(content.clone())),
+                    ("index", Some((count_now - 1).into())),
+                ]),
+            ));
+        }
+    }
+
+    let done = chunk.get("done").and_then(Value::as_bool) == Some(true);
+    if done {
+        let tokens = |key: &str| chunk.get(key).cloned().unwrap_or_else(|| 0.into());
+        let mut usage = signal(
+            "token_usage",
+            record([
+                ("agentId", Some(agent_id().into())),
+                ("promptTokens", Some(tokens("prompt_eval_count"))),
+                ("completionTokens", Some(tokens("eval_count"))),
+                ("model", Some(agent_id().into())),
+            ]),
+        );
+        usage["metadata"] = Value::Object(record([
+            ("totalDurationMs", millis(chunk.get("total_duration"))),
+            ("loadDurationMs", millis(chunk.get("load_duration"))),
+            (
+                "promptEvalDurationMs",
+                millis(chunk.get("prompt_eval_duration")),
+            ),
+            ("evalDurationMs", millis(chunk.get("eval_duration"))),
+        ]));
+        signals.push(usage);
+        signals.push(signal(
+            "completion",
+            record([
+                ("taskId", Some(correlation_id.into())),
+                ("agentId", Some(agent_id().into())),
+                ("success", Some(true.into())),
+                (
+                    "result",
+                    Some(format!("Stream completed ({count_now} chunks)").into()),
+                ),
+            ]),
+        ));
+    }
+
+    ChunkResult {
+        signals,
+        done,
+        token_count: count_now,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Stream decoding
+// ---------------------------------------------------------------------------
+
+/// Turns the bytes of an `/api/chat` NDJSON response into signals.
+pub struct ChatStream {
+    model: String,
+    correlation_id: String,
+    buf: Vec<u8>,
+    token_count: u64,
+}
+
+impl ChatStream {
+    pub fn new(model: &str, correlation_id: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            correlation_id: correlation_id.to_string(),
+            buf: Vec::new(),
+            token_count: 0,
+        }
+    }
+
+    /// Feed a chunk of the response body.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<StreamItem> {
+        self.buf.extend_from_slice(bytes);
+        let mut items = Vec::new();
+        while let Some(eol) = self.buf.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=eol).collect();
+            self.line(&String::from_utf8_lossy(&line), &mut items);
+        }
+        items
+    }
+
+    /// Flush a last line left without a newline.
+    pub fn finish(&mut self) -> Vec<StreamItem> {
+        let rest = std::mem::take(&mut self.buf);
+        let mut items = Vec::new();
+        self.line(&String::from_utf8_lossy(&rest), &mut items);
+        items
+    }
+
+    fn line(&mut self, line: &str, items: &mut Vec<StreamItem>) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        let Ok(chunk) = serde_json::from_str::<Value>(line) else {
+            let head: String = line.chars().take(100).collect();
+            items.push(StreamItem::Log {
+                level: "warn",
+                message: format!("[ollama] Unparsed: {head}"),
+            });
+            return;
+        };
+        let result = parse_chunk(&chunk, &self.model, &self.correlation_id, self.token_count);
+        self.token_count = result.token_count;
+        items.extend(result.signals.into_iter().map(|signal| StreamItem::Signal {
+            signal,
+            raw: line.to_string(),
+        }));
+        if result.done {
+            items.push(StreamItem::Log {
+                level: "info",
+                message: format!("Generation finished — {} tokens.", self.token_count),
+            });
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Requests
+// ---------------------------------------------------------------------------
+
+fn api(url: &str, path: &str) -> String {
+    format!("{}{path}", url.trim_end_matches('/'))
+}
+
+/// Error of a non-2xx response, with Ollama's `{ "error": … }` message.
+async fn http_error(response: reqwest::Response) -> String {
+    let status = response.status();
+    let reason = status.canonical_reason().unwrap_or_default();
+    let detail = response
+        .text()
+        .await
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .and_then(|body| body.get("error").map(js_string))
+        .map(|e| format!(": {e}"))
+        .unwrap_or_default();
+    format!("Request failed: HTTP {} {reason}{detail}", status.as_u16())
+}
+
+/// Installed models at `url`, from `/api/tags`.
+pub async fn list_models(url: &str) -> Result<Vec<OllamaModel>, String> {
+    let response = reqwest::Client::new()
+        .get(api(url, "/api/tags"))
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama probe error: {e}"))?;
+    if !response.status().is_success() {
+        return Err(http_error(response).await);
+    }
+    let raw = response.text().await.map_err(|e| e.to_string())?;
+    let tags: Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    Ok(models_from_tags(&tags))
+}
+
+/// POST a streaming `/api/chat` request to `url` and hand every decoded
+/// item to `emit` as it arrives.
+pub async fn send(
+    url: &str,
+    model: &str,
+    prompt: &str,
+    correlation_id: &str,
+    mut emit: impl FnMut(StreamItem),
+) -> Result<(), String> {
+    let body = json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": prompt }],
+        "stream": true,
+    });
+    let mut response = reqwest::Client::new()
+        .post(api(url, "/api/chat"))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(http_error(response).await);
+    }
+
+    let mut decoder = ChatStream::new(model, correlation_id);
+    while let Some(bytes) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Stream interrupted: {e}"))?
+    {
+        decoder.push(&bytes).into_iter().for_each(&mut emit);
+    }
+    decoder.finish().into_iter().for_each(emit);
+    Ok(())
+}
+
+async fn run(ctx: SourceContext, url: String, model: String, prompt: String) -> Result<(), String> {
+    let id = ctx.source_id().to_string();
+    let correlation_id = uuid::Uuid::new_v4().to_string();
+    ctx.debug("info", &format!("[{id}] Sending prompt to Ollama {model}…"));
+    ctx.dispatch_prompt(&prompt, &model, &correlation_id);
+
+    let sent = send(&url, &model, &prompt, &correlation_id, |item| {
+        ctx.forward(item)
+    })
+    .await;
+    if let Err(e) = &sent {
+        ctx.debug("error", &format!("[{id}] {e}"));
+        return sent;
+    }
+    ctx.debug("info", &format!("[{id}] Response stream ended."));
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Installed models at `url`, with their details.
+#[tauri::command]
+pub async fn ollama_models(url: String) -> Result<Vec<OllamaModel>, String> {
+    list_models(&url).await
+}
+
+/// Stream an `/api/chat` response for `prompt` from `url`. Signals arrive as
+/// `source://signal`; resolves when the stream ends or is stopped with
+/// `source_stop_prompt`.
+#[tauri::command]
+pub async fn ollama_prompt(
+    app: AppHandle,
+    sources: State<'_, Sources>,
+    source_id: String,
+    url: String,
+    model: String,
+    prompt: String,
+) -> Result<(), String> {
+    sources
+        .prompt(&app, &source_id, |ctx| run(ctx, url, model, prompt))
+        .await
+        .unwrap_or(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn final_chunk_reports_real_counts_and_timings() {
+        let last = json!({
+            "model": "qwen3:8b",
+            "message": { "role": "assistant", "content": "" },
+            "done": true,
+            "done_reason": "stop",
+            "total_duration": 5_191_566_416u64,
+            "load_duration": 2_154_458u64,
+            "prompt_eval_count": 26,
+            "prompt_eval_duration": 383_809_000u64,
+            "eval_count": 298,
+            "eval_duration": 4_799_921_000u64,
+        });
+        let result = parse_chunk(&last, "qwen3", "flow-1", 298);
+        assert!(result.done);
+        assert_eq!(result.signals.len(), 2);
+        assert_eq!(
+            result.signals[0]["payload"],
+            json!({
+                "agentId": "qwen3:8b",
+                "promptTokens": 26,
+                "completionTokens": 298,
+                "model": "qwen3:8b",
+            })
+        );
+        assert_eq!(
+            result.signals[0]["metadata"],
+            json!({
+                "totalDurationMs": 5191.566,
+                "loadDurationMs": 2.154,
+                "promptEvalDurationMs": 383.809,
+                "evalDurationMs": 4799.921,
+            })
+        );
+        assert_eq!(
+            result.signals[1]["payload"]["result"],
+            "Stream completed (298 chunks)"
+        );
+    }
+
+    #[test]
+    fn decodes_split_ndjson_with_thinking() {
+        let body = concat!(
+            "{\"model\":\"qwen3\",\"message\":{\"role\":\"assistant\",\"content\":\"\",\"thinking\":\"Hm\"},\"done\":false}\n",
+            "{\"model\":\"qwen3\",\"message\":{\"role\":\"assistant\",\"content\":\"Hi\"},\"done\":false}\n",
+            "oops\n",
+            "{\"model\":\"qwen3\",\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"eval_count\":1}",
+        );
+        let mut stream = ChatStream::new("qwen3", "flow-1");
+        let mut items = Vec::new();
+        for piece in body.as_bytes().chunks(11) {
+            items.extend(stream.push(piece));
+        }
+        items.extend(stream.finish());
+
+        let kinds: Vec<&str> = items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::Signal { signal, .. } => signal["type"].as_str(),
+                StreamItem::Log { .. } => None,
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            ["thinking", "text_delta", "token_usage", "completion"]
+        );
+        assert!(items.contains(&StreamItem::Log {
+            level: "warn",
+            message: "[ollama] Unparsed: oops".into(),
+        }));
+    }
+
+    #[test]
+    fn lists_tags_with_details() {
+        let tags = json!({ "models": [
+            {
+                "name": "llama3.2:latest",
+                "model": "llama3.2:latest",
+                "modified_at": "2025-05-04T17:37:44Z",
+                "size": 2_019_393_189u64,
+                "details": { "format": "gguf", "family": "llama", "parameter_size": "3.2B", "quantization_level": "Q4_K_M" },
+            },
+            { "size": 1 },
+        ]});
+        let models = models_from_tags(&tags);
+        assert_eq!(models.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&models[0]).unwrap()["details"]["parameterSize"],
+            "3.2B"
+        );
+    }
+}
@@ -0,0 +1,439 @@
+//! Native OpenAI-compatible streaming client.
+//!
+//! Port of `sendOpenAIPrompt` / `readOpenAIStream` in
+//! `src/views/signal-connection.ts` and `parseOpenAIChunk` in
+//! `src/simulator/signal-parser.ts`. Chat completions against LM Studio,
+//! Ollama or any OpenAI-compatible endpoint are streamed and decoded in the
+//! backend; [`ChatStream`] does the decoding and knows nothing about Tauri,
+//! so other backend features can reuse it.
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+use tauri_plugin_http::reqwest;
+
+use super::sse::SseDecoder;
+use super::{with_task_id, SourceContext, Sources, StreamItem};
+use crate::signal_parser::{coalesce, correlated, js_number, js_string, record, truthy};
+
+// ---------------------------------------------------------------------------
+// Chunk parsing
+// ---------------------------------------------------------------------------
+
+/// Result of [`parse_chunk`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkResult {
+    pub signals: Vec<Value>,
+    /// The generation finished (or failed).
+    pub done: bool,
+    /// Running count of `text_delta` chunks.
+    pub token_count: u64,
+}
+
+/// Parse one streaming chunk (a `data:` payload).
+///
+/// - `choices[0].delta.content` → `text_delta`
+/// - `choices[0].delta.reasoning_content` → `thinking` (GLM/DeepSeek)
+/// - `choices[0].finish_reason == "stop"` → `completion`
+/// - `usage` (sent with `stream_options.include_usage`) → `token_usage`
+/// - `error` → `error`
+pub fn parse_chunk(
+    chunk: &Value,
+    model: &str,
+    correlation_id: &str,
+    token_count: u64,
+) -> ChunkResult {
+    let mut signals = Vec::new();
+    let mut count_now = token_count;
+    let signal = |kind: &str, payload| correlated(kind, model, correlation_id, payload);
+    let agent_id = || coalesce([chunk.get("model")]).map_or_else(|| model.to_string(), js_string);
+
+    if truthy(chunk.get("error")) {
+        let err = &chunk["error"];
+        let text = |key: &str, fallback: &str| {
+            coalesce([err.get(key)]).map_or_else(|| fallback.to_string(), js_string)
+        };
+        signals.push(signal(
+            "error",
+            record([
+                ("agentId", Some(model.into())),
+                (
+                    "message",
+                    Some(text("message", "Unknown OpenAI error").into()),
+                ),
+                ("code", Some(text("code", "OPENAI_ERROR").into())),
+                ("severity", Some("error".into())),
+            ]),
+        ));
+        return ChunkResult {
+            signals,
+            done: true,
+            token_count: count_now,
+        };
+    }
+
+    if let Some(usage) = chunk.get("usage").filter(|u| truthy(Some(u))) {
+        signals.push(signal(
+            "token_usage",
+            record([
+                ("agentId", Some(agent_id().into())),
+                (
+                    "promptTokens",
+                    Some(js_number(
+                        usage.get("prompt_tokens").unwrap_or(&Value::Null),
+                    )),
+                ),
+                (
+                    "completionTokens",
+                    Some(js_number(
+                        usage.get("completion_tokens").unwrap_or(&Value::Null),
+                    )),
+                ),
+                ("model", Some(agent_id().into())),
+            ]),
+        ));
+    }
+
+    let Some(choice) = chunk
+        .get("choices")
+        .and_then(Value::as_array)
+        .and_then(|c| c.first())
+    else {
+        return ChunkResult {
+            signals,
+            done: false,
+            token_count: count_now,
+        };
+    };
+
+    if let Some(delta) = choice.get("delta") {
+        if let Some(reasoning) = delta.get("reasoning_content").filter(|v| truthy(Some(v))) {
+            signals.push(signal(
+                "thinking",
+                record([
+                    ("agentId", Some(agent_id().into())),
+                    ("content", Some(reasoning.clone())),
+                ]),
+            ));
+        }
+        if let Some(content) = delta.get("content").filter(|v| truthy(Some(v))) {
+            count_now += 1;
+            signals.push(signal(
+                "text_delta",
+                record([
+                    ("agentId", Some(agent_id().into())),
+                    ("content", Some(content.clone())),
+                    ("index", Some((count_now - 1).into())),
+                ]),
+            ));
+        }
+    }
+
+    let done = choice.get("finish_reason").and_then(Value::as_str) == Some("stop");
+    if done {
+        signals.push(signal(
+            "completion",
+            record([
+                ("agentId", Some(agent_id().into())),
+                ("success", Some(true.into())),
+                (
+                    "result",
+                    Some(format!("Stream completed ({count_now} chunks)").into()),
+                ),
+            ]),
+        ));
+    }
+
+    ChunkResult {
+        signals,
+        done,
+        token_count: count_now,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Stream decoding
+// ---------------------------------------------------------------------------
+
+/// Turns the bytes of a chat completion SSE response into signals.
+pub struct ChatStream {
+    model: String,
+    correlation_id: String,
+    decoder: SseDecoder,
+    token_count: u64,
+}
+
+impl ChatStream {
+    pub fn new(model: &str, correlation_id: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            correlation_id: correlation_id.to_string(),
+            decoder: SseDecoder::default(),
+            token_count: 0,
+        }
+    }
+
+    /// Feed a chunk of the response body.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        for event in self.decoder.push(bytes) {
+            self.data(event.data.trim(), &mut items);
+        }
+        items
+    }
+
+    /// Flush whatever the response ended with.
+    pub fn finish(&mut self) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        if let Some(event) = self.decoder.finish() {
+            self.data(event.data.trim(), &mut items);
+        }
+        items
+    }
+
+    fn data(&mut self, payload: &str, items: &mut Vec<StreamItem>) {
+        if payload.is_empty() {
+            return;
+        }
+        if payload == "[DONE]" {
+            let payload_map = record([
+                ("taskId", Some(self.correlation_id.as_str().into())),
+                ("success", Some(true.into())),
+            ]);
+            let mut signal =
+                correlated("completion", &self.model, &self.correlation_id, payload_map);
+            signal["metadata"] = json!({ "totalTokens": self.token_count });
+            items.push(StreamItem::Signal {
+                signal,
+                raw: payload.to_string(),
+            });
+            items.push(StreamItem::Log {
+                level: "info",
+                message: format!(
+                    "Stream complete — {} token chunks received.",
+                    self.token_count
+                ),
+            });
+            return;
+        }
+        let Ok(chunk) = serde_json::from_str::<Value>(payload) else {
+            let head: String = payload.chars().take(100).collect();
+            items.push(StreamItem::Log {
+                level: "warn",
+                message: format!("[openai] Unparsed: {head}"),
+            });
+            return;
+        };
+        let result = parse_chunk(&chunk, &self.model, &self.correlation_id, self.token_count);
+        self.token_count = result.token_count;
+        items.extend(result.signals.into_iter().map(|signal| StreamItem::Signal {
+            signal: with_task_id(signal, &self.correlation_id),
+            raw: payload.to_string(),
+        }));
+        if result.done {
+            items.push(StreamItem::Log {
+                level: "info",
+                message: format!("Generation finished — {} tokens.", self.token_count),
+            });
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Request
+// ---------------------------------------------------------------------------
+
+/// Body of a streaming chat completion for a single user prompt.
+fn request_body(model: &str, prompt: &str) -> String {
+    json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": prompt }],
+        "stream": true,
+        "stream_options": { "include_usage": true },
+    })
+    .to_string()
+}
+
+/// POST a streaming chat completion to `url` (the API base, without `/v1`)
+/// and hand every decoded item to `emit` as it arrives.
+pub async fn send(
+    url: &str,
+    api_key: Option<&str>,
+    model: &str,
+    prompt: &str,
+    correlation_id: &str,
+    mut emit: impl FnMut(StreamItem),
+) -> Result<(), String> {
+    let endpoint = format!("{}/v1/chat/completions", url.trim_end_matches('/'));
+    let mut request = reqwest::Client::new()
+        .post(&endpoint)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(request_body(model, prompt));
+    if let Some(key) = api_key.filter(|k| !k.is_empty()) {
+        request = request.bearer_auth(key);
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        let reason = status.canonical_reason().unwrap_or_default();
+        return Err(format!("Request failed: HTTP {} {reason}", status.as_u16()));
+    }
+
+    let mut decoder = ChatStream::new(model, correlation_id);
+    while let Some(bytes) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Stream interrupted: {e}"))?
+    {
+        decoder.push(&bytes).into_iter().for_each(&mut emit);
+    }
+    decoder.finish().into_iter().for_each(emit);
+    Ok(())
+}
+
+async fn run(
+    ctx: SourceContext,
+    url: String,
+    api_key: Option<String>,
+    model: String,
+    prompt: String,
+) -> Result<(), String> {
+    let id = ctx.source_id().to_string();
+    let correlation_id = uuid::Uuid::new_v4().to_string();
+    ctx.debug("info", &format!("[{id}] Sending prompt to {model}…"));
+    ctx.dispatch_prompt(&prompt, &model, &correlation_id);
+
+    let sent = send(
+        &url,
+        api_key.as_deref(),
+        &model,
+        &prompt,
+        &correlation_id,
+        |item| ctx.forward(item),
+    )
+    .await;
+    if let Err(e) = &sent {
+        ctx.debug("error", &format!("[{id}] {e}"));
+        return sent;
+    }
+    ctx.debug("info", &format!("[{id}] Response stream ended."));
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Stream a chat completion for `prompt` from `url` (the API base, without
+/// `/v1`). Signals arrive as `source://signal`; resolves when the stream
+/// ends or is stopped with `source_stop_prompt`.
+#[tauri::command]
+pub async fn openai_prompt(
+    app: AppHandle,
+    sources: State<'_, Sources>,
+    source_id: String,
+    url: String,
+    api_key: Option<String>,
+    model: String,
+    prompt: String,
+) -> Result<(), String> {
+    sources
+        .prompt(&app, &source_id, |ctx| {
+            run(ctx, url, api_key, model, prompt)
+        })
+        .await
+        .unwrap_or(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(items: &[StreamItem]) -> Vec<&str> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::Signal { signal, .. } => signal["type"].as_str(),
+                StreamItem::Log { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn maps_chunks_like_the_browser_parser() {
+        let chunk = json!({
+            "model": "deepseek-v3",
+            "choices": [{ "delta": { "content": "Answer", "reasoning_content": "Because..." } }],
+        });
+        let result = parse_chunk(&chunk, "fallback", "flow-1", 5);
+        assert_eq!(result.token_count, 6);
+        assert!(!result.done);
+        assert_eq!(result.signals[0]["type"], "thinking");
+        assert_eq!(result.signals[1]["payload"]["index"], 5);
+        assert_eq!(result.signals[1]["payload"]["agentId"], "deepseek-v3");
+        assert_eq!(result.signals[1]["source"], "fallback");
+        assert_eq!(result.signals[1]["correlationId"], "flow-1");
+
+        let usage = json!({
+            "choices": [],
+            "usage": { "prompt_tokens": 12, "completion_tokens": 34 },
+        });
+        let result = parse_chunk(&usage, "qwen3-8b", "flow-1", 3);
+        assert_eq!(result.signals.len(), 1);
+        assert_eq!(
+            result.signals[0]["payload"],
+            json!({
+                "agentId": "qwen3-8b",
+                "promptTokens": 12,
+                "completionTokens": 34,
+                "model": "qwen3-8b",
+            })
+        );
+
+        let error = json!({ "error": { "message": "Rate limited" } });
+        let result = parse_chunk(&error, "gpt-4", "flow-1", 0);
+        assert!(result.done);
+        assert_eq!(result.signals[0]["payload"]["code"], "OPENAI_ERROR");
+    }
+
+    #[test]
+    fn decodes_a_split_sse_response() {
+        let body = concat!(
+            ": ping\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"},\"finish_reason\":\"stop\"}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":2}}\n\n",
+            "data: not json\n\n",
+            "data: [DONE]\n\n",
+        );
+        let mut stream = ChatStream::new("llama3", "flow-1");
+        let mut items = Vec::new();
+        for piece in body.as_bytes().chunks(7) {
+            items.extend(stream.push(piece));
+        }
+        items.extend(stream.finish());
+
+        assert_eq!(
+            kinds(&items),
+            [
+                "text_delta",
+                "text_delta",
+                "completion",
+                "token_usage",
+                "completion"
+            ]
+        );
+        assert!(items.contains(&StreamItem::Log {
+            level: "warn",
+            message: "[openai] Unparsed: not json".into(),
+        }));
+        let StreamItem::Signal { signal, raw } = &items[items.len() - 2] else {
+            panic!("expected the [DONE] completion");
+        };
+        assert_eq!(signal["metadata"]["totalTokens"], 2);
+        assert_eq!(raw, "[DONE]");
+    }
+}
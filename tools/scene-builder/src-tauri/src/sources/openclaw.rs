@@ -0,0 +1,689 @@
+//! Native OpenClaw gateway client.
+//!
+//! Port of `connectOpenClaw` in `src/views/signal-connection.ts` and
+//! `parseOpenClawEvent` in `src/simulator/signal-parser.ts`: same handshake,
+//! same backoff schedule, same event → signal mapping. Frames are first
+//! decoded by [`parse_message`], so a frame that is already a sajou signal
+//! is forwarded as-is and `meta` frames are only logged.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, State};
+
+use super::{SourceContext, Sources, Status};
+use crate::openclaw::{read_openclaw_token, CredentialError};
+use crate::signal_parser::{
+    coalesce, envelope, is_known_type, js_number, js_string, parse_message, record, truthy,
+    ParseResult,
+};
+use crate::ws::{self, WsClient};
+
+/// Maximum reconnect attempts before giving up.
+const MAX_RECONNECT: u32 = 10;
+
+/// Maximum backoff delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// `source` field of every OpenClaw signal.
+const SOURCE: &str = "openclaw";
+
+// ---------------------------------------------------------------------------
+// Event parsing
+// ---------------------------------------------------------------------------
+
+/// `{ ...data }` when `data` is an object.
+fn spread(data: &Value) -> Map<String, Value> {
+    data.as_object().cloned().unwrap_or_default()
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// Parse a single OpenClaw gateway event into a signal envelope.
+///
+/// Internal events (challenge, presence, pong, keepalive pings) return `None`.
+pub fn parse_event(event: &Value) -> Option<Value> {
+    let kind = str_field(event, "type");
+    let category = str_field(event, "event");
+    let payload = coalesce([event.get("payload")])
+        .cloned()
+        .unwrap_or(json!({}));
+    let stream = str_field(&payload, "stream");
+    let data = coalesce([payload.get("data")])
+        .cloned()
+        .unwrap_or(json!({}));
+
+    // --- Internal events: skip silently ---
+    if kind == Some("connect.challenge") || category == Some("connect.challenge") {
+        return None;
+    }
+    if matches!(kind, Some("res" | "pong" | "ping")) || category == Some("system-presence") {
+        return None;
+    }
+
+    let meta = |key: &str| {
+        coalesce([payload.get(key), data.get(key)])
+            .cloned()
+            .unwrap_or(json!(""))
+    };
+    let channel = meta("provider");
+    let channel_label = meta("label");
+    let session_key = meta("sessionKey");
+    let agent_id = coalesce([data.get("agentId")])
+        .cloned()
+        .unwrap_or(json!(SOURCE));
+    let is = |name: &str| kind == Some(name) || category == Some(name);
+
+    if is("heartbeat") {
+        let mut payload = spread(&data);
+        payload.insert("_meta".into(), json!({ "heartbeat": true }));
+        return Some(envelope("event", SOURCE, payload));
+    }
+
+    if is("cron") {
+        let mut payload = spread(&data);
+        let job = coalesce([data.get("cronJobId"), data.get("jobId")]).cloned();
+        let meta = record([("cron", Some(json!(true))), ("cronJobId", job)]);
+        payload.insert("_meta".into(), Value::Object(meta));
+        return Some(envelope("event", SOURCE, payload));
+    }
+
+    if is("exec.approval.requested") {
+        let payload = record([
+            ("agentId", Some(agent_id)),
+            ("from", Some(json!("acting"))),
+            ("to", Some(json!("waiting"))),
+            ("reason", Some(json!("approval"))),
+            ("channel", Some(channel)),
+            ("channelLabel", Some(channel_label)),
+            ("sessionKey", Some(session_key)),
+        ]);
+        return Some(envelope("agent_state_change", SOURCE, payload));
+    }
+
+    if category == Some("agent") {
+        let ids = AgentIds {
+            agent_id,
+            channel,
+            channel_label,
+            session_key,
+        };
+        return parse_agent_event(stream, &data, ids);
+    }
+
+    if category == Some("session") {
+        let zero = json!(0);
+        let prompt =
+            coalesce([data.get("promptTokens"), data.get("input_tokens")]).unwrap_or(&zero);
+        let completion =
+            coalesce([data.get("completionTokens"), data.get("output_tokens")]).unwrap_or(&zero);
+        if prompt.is_number() || completion.is_number() {
+            let payload = record([
+                ("agentId", Some(agent_id)),
+                ("promptTokens", Some(js_number(prompt))),
+                ("completionTokens", Some(js_number(completion))),
+                (
+                    "model",
+                    Some(
+                        coalesce([data.get("model")])
+                            .cloned()
+                            .unwrap_or(json!("unknown")),
+                    ),
+                ),
+                ("channel", Some(channel)),
+                ("sessionKey", Some(session_key)),
+            ]);
+            return Some(envelope("token_usage", SOURCE, payload));
+        }
+    }
+
+    // --- Fallback: generic event ---
+    if kind == Some("event") || category.is_some_and(|c| !c.is_empty()) {
+        let mut payload = spread(&payload);
+        if let Some(category) = category {
+            payload.insert("eventCategory".into(), json!(category));
+        }
+        return Some(envelope("event", SOURCE, payload));
+    }
+
+    None
+}
+
+/// Identity fields shared by every agent sub-event.
+struct AgentIds {
+    agent_id: Value,
+    channel: Value,
+    channel_label: Value,
+    session_key: Value,
+}
+
+/// Parse an `event: "agent"` sub-event by stream type.
+fn parse_agent_event(stream: Option<&str>, data: &Value, ids: AgentIds) -> Option<Value> {
+    let phase = coalesce([data.get("phase"), data.get("status")])
+        .and_then(Value::as_str)
+        .unwrap_or("");
+    let started = matches!(phase, "start" | "started");
+    let ended = matches!(phase, "end" | "completed" | "done");
+    let tool_name = || {
+        let name =
+            coalesce([data.get("toolName"), data.get("tool")]).map_or("unknown".into(), js_string);
+        Some(json!(name))
+    };
+
+    match stream? {
+        "lifecycle" if started => Some(envelope(
+            "agent_state_change",
+            SOURCE,
+            record([
+                ("agentId", Some(ids.agent_id)),
+                ("from", Some(json!("idle"))),
+                ("to", Some(json!("acting"))),
+                ("channel", Some(ids.channel)),
+                ("channelLabel", Some(ids.channel_label)),
+                ("sessionKey", Some(ids.session_key)),
+            ]),
+        )),
+        "lifecycle" if ended => Some(envelope(
+            "completion",
+            SOURCE,
+            record([
+                ("agentId", Some(ids.agent_id)),
+                ("success", Some(json!(true))),
+                ("channel", Some(ids.channel)),
+                ("channelLabel", Some(ids.channel_label)),
+                ("sessionKey", Some(ids.session_key)),
+            ]),
+        )),
+        "lifecycle" if matches!(phase, "error" | "failed") => {
+            let message = coalesce([data.get("message"), data.get("error")])
+                .map_or("Agent error".into(), js_string);
+            Some(envelope(
+                "error",
+                SOURCE,
+                record([
+                    ("agentId", Some(ids.agent_id)),
+                    ("message", Some(json!(message))),
+                    ("severity", Some(json!("error"))),
+                    ("channel", Some(ids.channel)),
+                    ("channelLabel", Some(ids.channel_label)),
+                    ("sessionKey", Some(ids.session_key)),
+                ]),
+            ))
+        }
+        "tool" if started => {
+            let call_id = coalesce([data.get("callId"), data.get("id")])
+                .map_or_else(|| uuid::Uuid::new_v4().to_string(), js_string);
+            Some(envelope(
+                "tool_call",
+                SOURCE,
+                record([
+                    ("toolName", tool_name()),
+                    ("agentId", Some(ids.agent_id)),
+                    ("callId", Some(json!(call_id))),
+                    ("channel", Some(ids.channel)),
+                    ("sessionKey", Some(ids.session_key)),
+                ]),
+            ))
+        }
+        "tool" if ended => Some(envelope(
+            "tool_result",
+            SOURCE,
+            record([
+                ("toolName", tool_name()),
+                ("agentId", Some(ids.agent_id)),
+                (
+                    "success",
+                    Some(json!(data.get("success") != Some(&json!(false)))),
+                ),
+                (
+                    "output",
+                    coalesce([data.get("output"), data.get("result")]).cloned(),
+                ),
+                ("channel", Some(ids.channel)),
+                ("sessionKey", Some(ids.session_key)),
+            ]),
+        )),
+        // Prefer `delta` (incremental chunk) over `text` (accumulated full text)
+        "assistant" | "thinking" => {
+            let text = coalesce([data.get("delta"), data.get("content"), data.get("text")]);
+            if !truthy(text) {
+                return None;
+            }
+            let content = Some(json!(text.map(js_string)));
+            if stream == Some("thinking") {
+                return Some(envelope(
+                    "thinking",
+                    SOURCE,
+                    record([("agentId", Some(ids.agent_id)), ("content", content)]),
+                ));
+            }
+            Some(envelope(
+                "text_delta",
+                SOURCE,
+                record([
+                    ("agentId", Some(ids.agent_id)),
+                    ("content", content),
+                    ("channel", Some(ids.channel)),
+                    ("channelLabel", Some(ids.channel_label)),
+                    ("sessionKey", Some(ids.session_key)),
+                ]),
+            ))
+        }
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema shaping
+// ---------------------------------------------------------------------------
+
+/// Payload fields [`parse_event`] keeps for the browser that no well-known
+/// payload schema has room for.
+const ORIGIN_FIELDS: [&str; 3] = ["channel", "channelLabel", "sessionKey"];
+
+/// Reshape a [`parse_event`] signal so the native source emits it
+/// schema-valid.
+///
+/// `parse_event` keeps the browser's payload shapes, which the shared corpus
+/// holds both parsers to. Before emitting, the channel fields move into
+/// `metadata`, a non-object tool `output` is wrapped as `{ value }`, and a
+/// `completion` names the run it closes as its `taskId`.
+pub fn schema_shaped(mut signal: Value, event: &Value) -> Value {
+    let kind = js_string(&signal["type"]);
+    if !is_known_type(&kind) {
+        return signal;
+    }
+    let Some(payload) = signal.get_mut("payload").and_then(Value::as_object_mut) else {
+        return signal;
+    };
+
+    let mut origin = Map::new();
+    for key in ORIGIN_FIELDS {
+        if let Some(value) = payload.remove(key) {
+            origin.insert(key.into(), value);
+        }
+    }
+    if kind == "tool_result" {
+        if let Some(output) = payload.get_mut("output").filter(|o| !o.is_object()) {
+            *output = json!({ "value": output.take() });
+        }
+    }
+    if kind == "completion" && !payload.contains_key("taskId") {
+        // Without a run ID, the session (or the agent) stands for the run.
+        let task_id = coalesce([
+            event["payload"].get("runId"),
+            event["payload"]["data"].get("runId"),
+        ])
+        .or(origin.get("sessionKey").filter(|k| truthy(Some(k))))
+        .or(payload.get("agentId"))
+        .map_or_else(|| SOURCE.to_string(), js_string);
+        payload.insert("taskId".into(), json!(task_id));
+    }
+
+    if !origin.is_empty() {
+        signal["metadata"] = Value::Object(origin);
+    }
+    signal
+}
+
+// ---------------------------------------------------------------------------
+// Connection
+// ---------------------------------------------------------------------------
+
+/// `connect` request answering the gateway's challenge.
+fn connect_request(token: &str) -> Value {
+    json!({
+        "type": "req",
+        "id": uuid::Uuid::new_v4().to_string(),
+        "method": "connect",
+        "params": {
+            "minProtocol": 3,
+            "maxProtocol": 3,
+            "client": {
+                "id": "gateway-client",
+                "version": env!("CARGO_PKG_VERSION"),
+                "platform": std::env::consts::OS,
+                "mode": "backend",
+            },
+            "role": "operator",
+            "scopes": ["operator.read"],
+            "caps": [],
+            "commands": [],
+            "permissions": {},
+            "auth": { "token": token },
+            "locale": "fr-CH",
+            "userAgent": format!("sajou/{}", env!("CARGO_PKG_VERSION")),
+        },
+    })
+}
+
+/// Error message of a rejected `connect` response.
+fn rejection_message(res: &Value) -> String {
+    match coalesce([res.get("error"), res.get("message")]) {
+        Some(Value::Object(err)) => err
+            .get("message")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .unwrap_or_else(|| Value::Object(err.clone()).to_string()),
+        Some(other) => js_string(other),
+        None => "Authentication failed".into(),
+    }
+}
+
+/// Reconnect delay after `attempt` consecutive failures (1-based).
+fn backoff(attempt: u32) -> Duration {
+    let exp = attempt.saturating_sub(1).min(16);
+    Duration::from_secs(1u64 << exp).min(MAX_BACKOFF)
+}
+
+/// How a session ended.
+enum Ended {
+    /// The gateway closed the connection cleanly.
+    Closed,
+    /// The gateway refused our credentials — retrying will not help.
+    Rejected(String),
+    /// Transport failure; `handshake` tells whether we were connected.
+    Lost { handshake: bool, error: String },
+}
+
+/// One connection, from open to close.
+async fn session(ctx: &SourceContext, url: &str, token: &str) -> Ended {
+    let mut ws = match WsClient::connect(url).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            return Ended::Lost {
+                handshake: false,
+                error: e.to_string(),
+            }
+        }
+    };
+    ctx.debug(
+        "info",
+        &format!(
+            "[{}] OpenClaw WebSocket opened — waiting for challenge…",
+            ctx.source_id()
+        ),
+    );
+
+    let mut handshake = false;
+    loop {
+        let raw = match ws.recv().await {
+            Ok(Some(raw)) => raw,
+            Ok(None) => return Ended::Closed,
+            Err(e) => {
+                return Ended::Lost {
+                    handshake,
+                    error: e.to_string(),
+                }
+            }
+        };
+        // Decoded like any raw message: gateway frames come back as a
+        // generic `event` carrying the whole frame.
+        let msg = match parse_message(&raw) {
+            ParseResult::Signal(mut signal) if signal["type"] == "event" => {
+                signal["payload"].take()
+            }
+            ParseResult::Signal(signal) => {
+                // Already a sajou signal: forwarded as-is once connected.
+                if handshake {
+                    ctx.signal(&signal, &raw);
+                }
+                continue;
+            }
+            ParseResult::Meta { key, data } => {
+                ctx.debug(
+                    "info",
+                    &format!("[{}] [meta] {key}: {data}", ctx.source_id()),
+                );
+                continue;
+            }
+            ParseResult::Error(_) => {
+                let head: String = raw.chars().take(100).collect();
+                ctx.debug(
+                    "warn",
+                    &format!(
+                        "[{}] [openclaw] Unparseable message: {head}",
+                        ctx.source_id()
+                    ),
+                );
+                continue;
+            }
+        };
+
+        if handshake {
+            if let Some(signal) = parse_event(&msg) {
+                ctx.signal(&schema_shaped(signal, &msg), &raw);
+            }
+            continue;
+        }
+
+        let kind = str_field(&msg, "type");
+        if kind == Some("connect.challenge")
+            || str_field(&msg, "event") == Some("connect.challenge")
+        {
+            ctx.debug(
+                "info",
+                &format!("[{}] Challenge received — sending auth…", ctx.source_id()),
+            );
+            if let Err(e) = ws.send_text(&connect_request(token).to_string()).await {
+                return Ended::Lost {
+                    handshake,
+                    error: e.to_string(),
+                };
+            }
+        } else if kind == Some("res") {
+            if msg.get("ok") == Some(&json!(true)) {
+                handshake = true;
+                ctx.status(Status::Connected, None);
+                ctx.debug(
+                    "info",
+                    &format!("[{}] OpenClaw connected.", ctx.source_id()),
+                );
+            } else {
+                ws.close().await;
+                return Ended::Rejected(rejection_message(&msg));
+            }
+        } else {
+            let head: String = raw.chars().take(120).collect();
+            ctx.debug(
+                "warn",
+                &format!(
+                    "[{}] [openclaw] Unexpected handshake message: {head}",
+                    ctx.source_id()
+                ),
+            );
+        }
+    }
+}
+
+/// Connect, then reconnect with exponential backoff until the gateway closes
+/// cleanly, rejects us, or [`MAX_RECONNECT`] attempts fail in a row. A URL the
+/// native client cannot open is reported once, without retrying.
+async fn run(ctx: SourceContext, url: String, token: String) {
+    let id = ctx.source_id().to_string();
+    if let Err(e) = ws::check_url(&url) {
+        let msg = format!("{e} — connect to this gateway from the webview instead.");
+        ctx.debug("error", &format!("[{id}] {msg}"));
+        ctx.status(Status::Error, Some(msg));
+        return;
+    }
+    let mut attempts = 0;
+    loop {
+        match session(&ctx, &url, &token).await {
+            Ended::Closed => {
+                ctx.debug("info", &format!("[{id}] OpenClaw disconnected cleanly."));
+                ctx.status(Status::Disconnected, None);
+                return;
+            }
+            Ended::Rejected(error) => {
+                ctx.debug(
+                    "error",
+                    &format!("[{id}] OpenClaw handshake rejected: {error}"),
+                );
+                ctx.status(Status::Error, Some(error));
+                return;
+            }
+            Ended::Lost { handshake, error } => {
+                ctx.debug(
+                    "error",
+                    &format!("[{id}] OpenClaw WebSocket error: {error}"),
+                );
+                if handshake {
+                    attempts = 0;
+                }
+                attempts += 1;
+                if attempts > MAX_RECONNECT {
+                    let msg = format!("Connection lost after {MAX_RECONNECT} reconnect attempts.");
+                    ctx.debug("error", &format!("[{id}] {msg}"));
+                    ctx.status(Status::Error, Some(msg));
+                    return;
+                }
+                let delay = backoff(attempts);
+                ctx.debug(
+                    "warn",
+                    &format!(
+                        "[{id}] OpenClaw connection lost — reconnecting in {}ms (attempt {attempts}/{MAX_RECONNECT})…",
+                        delay.as_millis()
+                    ),
+                );
+                ctx.status(
+                    Status::Connecting,
+                    Some(format!("Reconnecting (attempt {attempts})…")),
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Connect a source to an OpenClaw gateway from the backend.
+///
+/// Without an explicit `token`, it is read like [`read_openclaw_token`]
+/// (`path` / `profile`). Replaces any running connection of `source_id`.
+#[tauri::command]
+pub fn openclaw_connect(
+    app: AppHandle,
+    sources: State<'_, Sources>,
+    source_id: String,
+    url: String,
+    token: Option<String>,
+    path: Option<PathBuf>,
+    profile: Option<String>,
+) -> Result<(), CredentialError> {
+    let token = match token.filter(|t| !t.is_empty()) {
+        Some(token) => token,
+        None => read_openclaw_token(path, profile)?,
+    };
+    sources.spawn(&app, &source_id, |ctx| run(ctx, url, token));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_agent_streams_like_the_browser_parser() {
+        let tool = parse_event(&json!({
+            "type": "event", "event": "agent",
+            "payload": { "stream": "tool", "provider": "telegram",
+                         "data": { "phase": "start", "tool": "exec", "id": 42, "agentId": "main" } },
+        }))
+        .unwrap();
+        assert_eq!(tool["type"], "tool_call");
+        assert_eq!(tool["source"], "openclaw");
+        assert_eq!(
+            tool["payload"],
+            json!({ "toolName": "exec", "agentId": "main", "callId": "42",
+                    "channel": "telegram", "sessionKey": "" })
+        );
+
+        let done = parse_event(&json!({
+            "event": "agent",
+            "payload": { "stream": "tool", "data": { "phase": "done", "result": "ok" } },
+        }))
+        .unwrap();
+        assert_eq!(done["payload"]["success"], true);
+        assert_eq!(done["payload"]["output"], "ok");
+        assert_eq!(done["payload"]["agentId"], "openclaw");
+
+        let empty_delta = json!({ "event": "agent", "payload": { "stream": "assistant", "data": { "delta": "" } } });
+        assert!(parse_event(&empty_delta).is_none());
+    }
+
+    #[test]
+    fn shapes_signals_for_the_schema_before_emitting() {
+        let shape = |event: Value| schema_shaped(parse_event(&event).unwrap(), &event);
+
+        let done = shape(json!({
+            "event": "agent",
+            "payload": { "stream": "tool", "provider": "telegram",
+                         "data": { "phase": "done", "result": "ok" } },
+        }));
+        assert_eq!(done["payload"]["output"], json!({ "value": "ok" }));
+        assert_eq!(done["payload"].get("channel"), None);
+        assert_eq!(
+            done["metadata"],
+            json!({ "channel": "telegram", "sessionKey": "" })
+        );
+
+        let end = shape(json!({
+            "event": "agent",
+            "payload": { "stream": "lifecycle", "runId": "run-7", "sessionKey": "tg:1",
+                         "data": { "phase": "end" } },
+        }));
+        assert_eq!(end["type"], "completion");
+        assert_eq!(end["payload"]["taskId"], "run-7");
+        assert_eq!(end["metadata"]["sessionKey"], "tg:1");
+
+        let heartbeat = json!({ "event": "heartbeat", "payload": { "data": { "channel": "x" } } });
+        let beat = parse_event(&heartbeat).unwrap();
+        assert_eq!(schema_shaped(beat.clone(), &heartbeat), beat);
+    }
+
+    #[test]
+    fn skips_internal_events_and_falls_back_to_generic() {
+        assert!(parse_event(&json!({ "type": "event", "event": "connect.challenge" })).is_none());
+        assert!(parse_event(&json!({ "type": "event", "event": "system-presence" })).is_none());
+
+        let cron =
+            parse_event(&json!({ "event": "cron", "payload": { "data": { "jobId": "j1" } } }))
+                .unwrap();
+        assert_eq!(
+            cron["payload"],
+            json!({ "jobId": "j1", "_meta": { "cron": true, "cronJobId": "j1" } })
+        );
+
+        let usage = parse_event(&json!({
+            "event": "session", "payload": { "data": { "input_tokens": 12, "output_tokens": "3" } },
+        }))
+        .unwrap();
+        assert_eq!(usage["type"], "token_usage");
+        assert_eq!(usage["payload"]["completionTokens"], 3);
+
+        let other =
+            parse_event(&json!({ "type": "event", "event": "chat", "payload": { "x": 1 } }))
+                .unwrap();
+        assert_eq!(other["payload"], json!({ "x": 1, "eventCategory": "chat" }));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff(10), MAX_BACKOFF);
+        assert_eq!(
+            rejection_message(
+                &json!({ "type": "res", "ok": false, "error": { "message": "bad token" } })
+            ),
+            "bad token"
+        );
+    }
+}
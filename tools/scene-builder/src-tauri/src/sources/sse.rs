@@ -0,0 +1,96 @@
+//! Incremental Server-Sent Events decoder.
+//!
+//! Fed raw response chunks as they arrive; yields complete events. Lines may
+//! be split anywhere, including inside a UTF-8 sequence.
+
+/// One dispatched event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The `event:` field, if any.
+    pub event: Option<String>,
+    /// `data:` lines joined with `\n`.
+    pub data: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buf: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    /// Feed a chunk; returns the events it completed.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buf.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(eol) = self.buf.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=eol).collect();
+            let line = String::from_utf8_lossy(&line[..eol]);
+            if let Some(event) = self.line(line.trim_end_matches('\r')) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Flush an event left unterminated when the stream ended.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let rest = std::mem::take(&mut self.buf);
+        let rest = String::from_utf8_lossy(&rest);
+        let pending = self.line(rest.trim_end_matches('\r'));
+        pending.or_else(|| self.line(""))
+    }
+
+    fn line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            if self.data.is_empty() {
+                self.event = None;
+                return None;
+            }
+            return Some(SseEvent {
+                event: self.event.take(),
+                data: std::mem::take(&mut self.data).join("\n"),
+            });
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reassembles_events_split_across_chunks() {
+        let mut decoder = SseDecoder::default();
+        assert!(decoder
+            .push(b": keep-alive\n\nevent: message_st")
+            .is_empty());
+        let events = decoder.push("art\r\ndata: {\"a\":\n\ndata: caf\u{e9}".as_bytes());
+        assert_eq!(
+            events,
+            [SseEvent {
+                event: Some("message_start".into()),
+                data: "{\"a\":".into(),
+            }]
+        );
+        assert_eq!(
+            decoder.finish(),
+            Some(SseEvent {
+                event: None,
+                data: "café".into(),
+            })
+        );
+    }
+}
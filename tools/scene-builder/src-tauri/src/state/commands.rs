@@ -0,0 +1,162 @@
+//! Acknowledged delivery of backend mutations to the webview.
+//!
+//! Port of the `/__commands__/stream` + `/api/commands/pending` +
+//! `/api/commands/ack` trio from `packages/mcp-server/src/routes/scene.ts`.
+//! Every backend mutation (MCP tools, `state_execute`) is queued and
+//! announced as [`COMMAND_EVENT`]. The webview applies the state and acks;
+//! anything left unacknowledged — e.g. the window reloaded mid-flight — is
+//! handed out again by `commands_pending` when the consumer reconnects.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::broadcast;
+
+use super::store::{ChangeOrigin, StateStore};
+use crate::signals::now_ms;
+
+/// Webview event announcing a newly queued command.
+pub const COMMAND_EVENT: &str = "commands://queued";
+
+/// Upper bound on unacknowledged commands. A webview that never acks (closed
+/// devtools window, crashed renderer) must not grow the queue forever; the
+/// state itself is never lost, only the per-mutation record.
+const MAX_PENDING: usize = 1000;
+
+/// One backend mutation awaiting acknowledgement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedCommand {
+    pub id: u64,
+    /// State version produced by the mutation. Applying any state at or
+    /// past this version satisfies the command.
+    pub version: u64,
+    pub created_at: u64,
+    /// How many times the command was handed out by [`CommandQueue::pending`].
+    pub deliveries: u32,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_id: u64,
+    pending: VecDeque<QueuedCommand>,
+}
+
+/// Managed state: unacknowledged commands, oldest first.
+#[derive(Default)]
+pub struct CommandQueue {
+    inner: Mutex<Inner>,
+}
+
+impl CommandQueue {
+    /// Queue a command for the mutation that produced `version`.
+    pub fn enqueue(&self, version: u64) -> QueuedCommand {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.next_id += 1;
+        let command = QueuedCommand {
+            id: inner.next_id,
+            version,
+            created_at: now_ms(),
+            deliveries: 0,
+        };
+        if inner.pending.len() == MAX_PENDING {
+            inner.pending.pop_front();
+            eprintln!("[sajou] command queue full, dropping oldest unacknowledged command");
+        }
+        inner.pending.push_back(command.clone());
+        command
+    }
+
+    /// Every unacknowledged command, counting this as a delivery.
+    pub fn pending(&self) -> Vec<QueuedCommand> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        for command in &mut inner.pending {
+            command.deliveries += 1;
+        }
+        inner.pending.iter().cloned().collect()
+    }
+
+    /// Drop acknowledged commands. Returns how many were removed.
+    pub fn ack(&self, ids: &[u64]) -> usize {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let before = inner.pending.len();
+        inner.pending.retain(|c| !ids.contains(&c.id));
+        before - inner.pending.len()
+    }
+}
+
+/// Queue every backend mutation of `store` and announce it as
+/// [`COMMAND_EVENT`]. The queue must already be managed by `app`.
+pub fn forward_mutations(app: &AppHandle, store: &StateStore) {
+    let mut rx = store.subscribe();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(change) if change.origin == ChangeOrigin::Mutation => {
+                    let command = app.state::<CommandQueue>().enqueue(change.version);
+                    let _ = app.emit(COMMAND_EVENT, command);
+                }
+                Ok(_) => {}
+                // Missed notifications still leave the state applied; the
+                // next queued command makes the webview pull it.
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Unacknowledged commands (`GET /api/commands/pending` equivalent).
+#[tauri::command]
+pub fn commands_pending(queue: State<'_, CommandQueue>) -> Vec<QueuedCommand> {
+    queue.pending()
+}
+
+/// Acknowledge applied commands (`POST /api/commands/ack` equivalent).
+/// Returns how many were removed.
+#[tauri::command]
+pub fn commands_ack(queue: State<'_, CommandQueue>, ids: Vec<u64>) -> usize {
+    queue.ack(&ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unacknowledged_commands_are_redelivered() {
+        let queue = CommandQueue::default();
+        let first = queue.enqueue(3);
+        let second = queue.enqueue(4);
+        assert_eq!((first.id, second.id), (1, 2));
+
+        // Webview saw both, then reloaded before acking the second.
+        assert_eq!(queue.pending().len(), 2);
+        assert_eq!(queue.ack(&[first.id]), 1);
+
+        let again = queue.pending();
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0].version, 4);
+        assert_eq!(again[0].deliveries, 2);
+        assert_eq!(queue.ack(&[first.id, second.id]), 1);
+        assert!(queue.pending().is_empty());
+    }
+
+    #[test]
+    fn full_queue_drops_the_oldest() {
+        let queue = CommandQueue::default();
+        for version in 0..=MAX_PENDING as u64 {
+            queue.enqueue(version);
+        }
+        let pending = queue.pending();
+        assert_eq!(pending.len(), MAX_PENDING);
+        assert_eq!(pending[0].version, 1);
+    }
+}
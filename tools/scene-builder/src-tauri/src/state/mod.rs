@@ -0,0 +1,11 @@
+//! Authoritative scene state for the desktop app.
+//!
+//! Port of `packages/mcp-server/src/state/`: [`store`] holds the versioned
+//! `ServerState`, [`mutations`] implements the edits external tools can make,
+//! and [`commands`] delivers them to the webview until acknowledged.
+
+pub mod commands;
+pub mod mutations;
+pub mod store;
+
+pub use store::StateStore;
@@ -0,0 +1,537 @@
+//! Server-side mutations — direct state modifications.
+//!
+//! Port of `packages/mcp-server/src/state/mutations.ts`. Each function edits
+//! a [`ServerState`] in place; [`StateStore::execute`](super::StateStore::execute)
+//! wraps them so every applied command bumps the version exactly once.
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use super::store::ServerState;
+
+type Data = Map<String, Value>;
+
+/// A `{ action, type, data }` command, as sent by the old command queue.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Command {
+    pub action: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub data: Data,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// `data[key] ?? fallback`.
+fn or(data: &Data, key: &str, fallback: Value) -> Value {
+    data.get(key)
+        .filter(|v| !v.is_null())
+        .cloned()
+        .unwrap_or(fallback)
+}
+
+/// `data["id"] ?? crypto.randomUUID()`.
+fn id_or_new(data: &Data) -> Value {
+    or(data, "id", uuid::Uuid::new_v4().to_string().into())
+}
+
+/// Build a record, dropping absent (`undefined`) fields like `JSON.stringify`.
+fn record<const N: usize>(fields: [(&str, Option<Value>); N]) -> Value {
+    Value::Object(
+        fields
+            .into_iter()
+            .filter_map(|(k, v)| v.map(|v| (k.to_string(), v)))
+            .collect(),
+    )
+}
+
+/// `section[key] ?? []`, coerced to a mutable array.
+fn list<'a>(section: &'a mut Data, key: &str) -> &'a mut Vec<Value> {
+    let slot = section.entry(key).or_insert_with(|| json!([]));
+    if !slot.is_array() {
+        *slot = json!([]);
+    }
+    match slot {
+        Value::Array(items) => items,
+        _ => unreachable!(),
+    }
+}
+
+/// Remove every item whose `id` equals `id`.
+fn remove_by_id(items: &mut Vec<Value>, id: Option<&Value>) {
+    items.retain(|item| item.get("id") != id);
+}
+
+/// Find the object with the given `id`.
+fn find_by_id<'a>(items: &'a mut [Value], id: Option<&Value>) -> Option<&'a mut Data> {
+    items
+        .iter_mut()
+        .filter_map(Value::as_object_mut)
+        .find(|item| item.get("id") == id)
+}
+
+/// `{ ...item, ...data }` on the item with `data.id`.
+fn spread_by_id(items: &mut [Value], data: &Data) {
+    if let Some(item) = find_by_id(items, data.get("id")) {
+        item.extend(data.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+}
+
+/// Copy each listed key of `data` that is present (even if null).
+fn copy_present(target: &mut Data, data: &Data, keys: &[&str]) {
+    for &key in keys {
+        if let Some(value) = data.get(key) {
+            target.insert(key.to_string(), value.clone());
+        }
+    }
+}
+
+/// The array elements of `value`, or an empty slice.
+fn items(value: Option<&Value>) -> &[Value] {
+    value.and_then(Value::as_array).map_or(&[], Vec::as_slice)
+}
+
+// ---------------------------------------------------------------------------
+// Entity mutations
+// ---------------------------------------------------------------------------
+
+/// Place a new entity on the scene.
+pub fn add_entity(s: &mut ServerState, data: &Data) {
+    list(&mut s.scene, "entities").push(record([
+        ("id", Some(id_or_new(data))),
+        ("entityId", data.get("entityId").cloned()),
+        ("semanticId", data.get("semanticId").cloned()),
+        ("x", Some(or(data, "x", 0.into()))),
+        ("y", Some(or(data, "y", 0.into()))),
+        ("scale", Some(or(data, "scale", 1.into()))),
+        ("rotation", Some(or(data, "rotation", 0.into()))),
+        ("layerId", Some(or(data, "layerId", "midground".into()))),
+        ("zIndex", Some(or(data, "zIndex", 0.into()))),
+        ("opacity", Some(or(data, "opacity", 1.into()))),
+        ("flipH", Some(or(data, "flipH", false.into()))),
+        ("flipV", Some(or(data, "flipV", false.into()))),
+        ("locked", Some(or(data, "locked", false.into()))),
+        ("visible", Some(or(data, "visible", true.into()))),
+        ("activeState", Some(or(data, "activeState", "idle".into()))),
+    ]));
+}
+
+/// Remove an entity by instance ID.
+pub fn remove_entity(s: &mut ServerState, id: Option<&Value>) {
+    remove_by_id(list(&mut s.scene, "entities"), id);
+}
+
+/// Update an entity by spreading partial data.
+pub fn update_entity(s: &mut ServerState, data: &Data) {
+    spread_by_id(list(&mut s.scene, "entities"), data);
+}
+
+// ---------------------------------------------------------------------------
+// Choreography mutations
+// ---------------------------------------------------------------------------
+
+/// Add a choreography definition.
+pub fn add_choreography(s: &mut ServerState, data: &Data) {
+    list(&mut s.choreographies, "choreographies").push(record([
+        ("id", Some(id_or_new(data))),
+        ("on", Some(or(data, "on", "".into()))),
+        ("when", Some(or(data, "when", Value::Null))),
+        ("interrupts", Some(or(data, "interrupts", false.into()))),
+        ("steps", Some(or(data, "steps", json!([])))),
+        ("nodeX", Some(or(data, "nodeX", 0.into()))),
+        ("nodeY", Some(or(data, "nodeY", 0.into()))),
+        ("collapsed", Some(or(data, "collapsed", false.into()))),
+        (
+            "defaultTargetEntityId",
+            Some(or(data, "defaultTargetEntityId", Value::Null)),
+        ),
+    ]));
+}
+
+/// Remove a choreography by ID. Also removes wires targeting it.
+pub fn remove_choreography(s: &mut ServerState, id: Option<&Value>) {
+    remove_by_id(list(&mut s.choreographies, "choreographies"), id);
+    list(&mut s.wiring, "wires")
+        .retain(|w| !(w.get("toZone") == Some(&json!("choreographer")) && w.get("toId") == id));
+}
+
+/// Update a choreography by spreading partial data.
+pub fn update_choreography(s: &mut ServerState, data: &Data) {
+    spread_by_id(list(&mut s.choreographies, "choreographies"), data);
+}
+
+// ---------------------------------------------------------------------------
+// Binding mutations
+// ---------------------------------------------------------------------------
+
+/// Add a binding.
+pub fn add_binding(s: &mut ServerState, data: &Data) {
+    list(&mut s.bindings, "bindings").push(record([
+        ("id", Some(id_or_new(data))),
+        ("targetEntityId", data.get("targetEntityId").cloned()),
+        ("property", data.get("property").cloned()),
+        (
+            "sourceChoreographyId",
+            data.get("sourceChoreographyId").cloned(),
+        ),
+        ("sourceType", Some(or(data, "sourceType", "direct".into()))),
+        ("mapping", data.get("mapping").cloned()),
+        ("action", data.get("action").cloned()),
+        ("sourceField", data.get("sourceField").cloned()),
+        ("transition", data.get("transition").cloned()),
+    ]));
+}
+
+/// Remove a binding by ID.
+pub fn remove_binding(s: &mut ServerState, id: Option<&Value>) {
+    remove_by_id(list(&mut s.bindings, "bindings"), id);
+}
+
+// ---------------------------------------------------------------------------
+// Wire mutations
+// ---------------------------------------------------------------------------
+
+/// Add a wire connection.
+pub fn add_wire(s: &mut ServerState, data: &Data) {
+    list(&mut s.wiring, "wires").push(record([
+        ("id", Some(id_or_new(data))),
+        ("fromZone", data.get("fromZone").cloned()),
+        ("fromId", data.get("fromId").cloned()),
+        ("toZone", data.get("toZone").cloned()),
+        ("toId", data.get("toId").cloned()),
+        ("mapping", Some(or(data, "mapping", Value::Null))),
+    ]));
+}
+
+/// Remove a wire by ID.
+pub fn remove_wire(s: &mut ServerState, id: Option<&Value>) {
+    remove_by_id(list(&mut s.wiring, "wires"), id);
+}
+
+// ---------------------------------------------------------------------------
+// Signal source mutations
+// ---------------------------------------------------------------------------
+
+/// Add a signal source.
+pub fn add_signal_source(s: &mut ServerState, data: &Data) {
+    list(&mut s.signal_sources, "sources").push(record([
+        ("id", Some(id_or_new(data))),
+        ("name", Some(or(data, "name", "New Source".into()))),
+        ("protocol", Some(or(data, "protocol", "websocket".into()))),
+        ("url", Some(or(data, "url", "".into()))),
+        ("status", Some(or(data, "status", "disconnected".into()))),
+        ("error", Some(Value::Null)),
+        ("category", Some(or(data, "category", "remote".into()))),
+        ("eventsPerSecond", Some(0.into())),
+        ("streaming", Some(false.into())),
+    ]));
+}
+
+/// Remove a signal source by ID. Also removes its wires.
+pub fn remove_signal_source(s: &mut ServerState, id: Option<&Value>) {
+    remove_by_id(list(&mut s.signal_sources, "sources"), id);
+    list(&mut s.wiring, "wires")
+        .retain(|w| !(w.get("fromZone") == Some(&json!("signal")) && w.get("fromId") == id));
+}
+
+// ---------------------------------------------------------------------------
+// Shader mutations
+// ---------------------------------------------------------------------------
+
+/// Parse raw uniform (or sketch param) data from an API call.
+fn parse_params(raw: &[Value], with_object_id: bool) -> Value {
+    raw.iter()
+        .map(|u| {
+            let u = u.as_object().cloned().unwrap_or_default();
+            let value = u.get("value").cloned();
+            let object_id = with_object_id.then(|| u.get("objectId").cloned()).flatten();
+            record([
+                ("name", u.get("name").cloned()),
+                ("type", Some(or(&u, "type", "float".into()))),
+                ("control", Some(or(&u, "control", "slider".into()))),
+                ("value", value.clone()),
+                (
+                    "defaultValue",
+                    u.get("defaultValue")
+                        .filter(|v| !v.is_null())
+                        .cloned()
+                        .or(value),
+                ),
+                ("min", Some(or(&u, "min", 0.into()))),
+                ("max", Some(or(&u, "max", 1.into()))),
+                ("step", Some(or(&u, "step", 0.01.into()))),
+                ("objectId", object_id),
+                ("bind", u.get("bind").cloned()),
+            ])
+        })
+        .collect()
+}
+
+/// Parse raw shader object data from an API call.
+fn parse_objects(raw: &[Value]) -> Value {
+    raw.iter()
+        .map(|o| {
+            record([
+                ("id", o.get("id").cloned()),
+                ("label", o.get("label").cloned()),
+            ])
+        })
+        .collect()
+}
+
+/// Add a shader.
+pub fn add_shader(s: &mut ServerState, data: &Data) {
+    list(&mut s.shaders, "shaders").push(record([
+        ("id", Some(id_or_new(data))),
+        ("name", Some(or(data, "name", "Untitled".into()))),
+        ("mode", Some(or(data, "mode", "fragment".into()))),
+        ("vertexSource", Some(or(data, "vertexSource", "".into()))),
+        (
+            "fragmentSource",
+            Some(or(data, "fragmentSource", "".into())),
+        ),
+        (
+            "uniforms",
+            Some(parse_params(items(data.get("uniforms")), true)),
+        ),
+        ("objects", Some(parse_objects(items(data.get("objects"))))),
+        ("passes", Some(or(data, "passes", 1.into()))),
+        (
+            "bufferResolution",
+            Some(or(data, "bufferResolution", 1.into())),
+        ),
+    ]));
+}
+
+/// Update an existing shader.
+pub fn update_shader(s: &mut ServerState, data: &Data) {
+    let Some(shader) = find_by_id(list(&mut s.shaders, "shaders"), data.get("id")) else {
+        return;
+    };
+    copy_present(
+        shader,
+        data,
+        &[
+            "name",
+            "mode",
+            "vertexSource",
+            "fragmentSource",
+            "passes",
+            "bufferResolution",
+        ],
+    );
+    if let Some(uniforms) = data.get("uniforms") {
+        shader.insert("uniforms".into(), parse_params(items(Some(uniforms)), true));
+    }
+    if let Some(objects) = data.get("objects") {
+        shader.insert("objects".into(), parse_objects(items(Some(objects))));
+    }
+}
+
+/// Remove a shader by ID.
+pub fn remove_shader(s: &mut ServerState, id: Option<&Value>) {
+    remove_by_id(list(&mut s.shaders, "shaders"), id);
+}
+
+/// Set a single uniform value on a shader.
+pub fn set_uniform(
+    s: &mut ServerState,
+    id: Option<&Value>,
+    name: Option<&Value>,
+    value: Option<&Value>,
+) {
+    let Some(shader) = find_by_id(list(&mut s.shaders, "shaders"), id) else {
+        return;
+    };
+    let uniforms = with_value(items(shader.get("uniforms")), name, value);
+    shader.insert("uniforms".into(), parse_params(&uniforms, true));
+}
+
+/// `params.map(p => p.name === name ? { ...p, value } : p)`.
+fn with_value(params: &[Value], name: Option<&Value>, value: Option<&Value>) -> Vec<Value> {
+    params
+        .iter()
+        .cloned()
+        .map(|mut p| {
+            if let Some(p) = p.as_object_mut().filter(|p| p.get("name") == name) {
+                match value {
+                    Some(v) => p.insert("value".into(), v.clone()),
+                    None => p.remove("value"),
+                };
+            }
+            p
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Sketch mutations (p5.js + Three.js)
+// ---------------------------------------------------------------------------
+
+/// Valid sketch runtime modes.
+const VALID_SKETCH_MODES: [&str; 2] = ["p5", "threejs"];
+
+/// Validate and return sketch mode, defaulting to "p5".
+fn validate_sketch_mode(raw: Option<&Value>) -> Value {
+    match raw.and_then(Value::as_str) {
+        Some(mode) if VALID_SKETCH_MODES.contains(&mode) => mode.into(),
+        _ => "p5".into(),
+    }
+}
+
+/// Add a p5 sketch.
+pub fn add_p5_sketch(s: &mut ServerState, data: &Data) {
+    list(&mut s.p5, "sketches").push(record([
+        ("id", Some(id_or_new(data))),
+        ("name", Some(or(data, "name", "Untitled".into()))),
+        ("source", Some(or(data, "source", "".into()))),
+        (
+            "params",
+            Some(parse_params(items(data.get("params")), false)),
+        ),
+        ("width", Some(or(data, "width", 0.into()))),
+        ("height", Some(or(data, "height", 0.into()))),
+        ("mode", Some(validate_sketch_mode(data.get("mode")))),
+    ]));
+}
+
+/// Update a p5 sketch.
+pub fn update_p5_sketch(s: &mut ServerState, data: &Data) {
+    let Some(sketch) = find_by_id(list(&mut s.p5, "sketches"), data.get("id")) else {
+        return;
+    };
+    copy_present(sketch, data, &["name", "source", "width", "height"]);
+    if let Some(mode) = data.get("mode") {
+        sketch.insert("mode".into(), validate_sketch_mode(Some(mode)));
+    }
+    if let Some(params) = data.get("params") {
+        sketch.insert("params".into(), parse_params(items(Some(params)), false));
+    }
+}
+
+/// Remove a p5 sketch by ID.
+pub fn remove_p5_sketch(s: &mut ServerState, id: Option<&Value>) {
+    remove_by_id(list(&mut s.p5, "sketches"), id);
+}
+
+/// Set a single param value on a p5 sketch.
+pub fn set_p5_param(
+    s: &mut ServerState,
+    id: Option<&Value>,
+    name: Option<&Value>,
+    value: Option<&Value>,
+) {
+    let Some(sketch) = find_by_id(list(&mut s.p5, "sketches"), id) else {
+        return;
+    };
+    let params = with_value(items(sketch.get("params")), name, value);
+    sketch.insert("params".into(), parse_params(&params, false));
+}
+
+// ---------------------------------------------------------------------------
+// Generic command dispatch
+// ---------------------------------------------------------------------------
+
+/// Execute a command in the same format as the old command-queue.
+/// Unknown `(type, action)` pairs are ignored.
+pub fn execute_command(s: &mut ServerState, cmd: &Command) {
+    let data = &cmd.data;
+    let id = data.get("id");
+    match (cmd.kind.as_str(), cmd.action.as_str()) {
+        ("entity", "add") => add_entity(s, data),
+        ("entity", "remove") => remove_entity(s, id),
+        ("entity", "update") => update_entity(s, data),
+        ("choreography", "add") => add_choreography(s, data),
+        ("choreography", "remove") => remove_choreography(s, id),
+        ("choreography", "update") => update_choreography(s, data),
+        ("binding", "add") => add_binding(s, data),
+        ("binding", "remove") => remove_binding(s, id),
+        ("wire", "add") => add_wire(s, data),
+        ("wire", "remove") => remove_wire(s, id),
+        ("source", "add") => add_signal_source(s, data),
+        ("source", "remove") => remove_signal_source(s, id),
+        ("shader", "add") => add_shader(s, data),
+        ("shader", "update") => update_shader(s, data),
+        ("shader", "remove") => remove_shader(s, id),
+        ("shader", "set-uniform") => set_uniform(s, id, data.get("uniformName"), data.get("value")),
+        ("p5", "add") => add_p5_sketch(s, data),
+        ("p5", "update") => update_p5_sketch(s, data),
+        ("p5", "remove") => remove_p5_sketch(s, id),
+        ("p5", "set-param") => set_p5_param(s, id, data.get("paramName"), data.get("value")),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(value: Value) -> Data {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn add_entity_fills_defaults() {
+        let mut s = ServerState::default();
+        add_entity(
+            &mut s,
+            &data(json!({ "id": "e1", "entityId": "peon", "x": 10 })),
+        );
+        assert_eq!(
+            s.scene["entities"][0],
+            json!({
+                "id": "e1", "entityId": "peon", "x": 10, "y": 0, "scale": 1, "rotation": 0,
+                "layerId": "midground", "zIndex": 0, "opacity": 1, "flipH": false,
+                "flipV": false, "locked": false, "visible": true, "activeState": "idle",
+            })
+        );
+    }
+
+    #[test]
+    fn removing_a_source_drops_its_wires() {
+        let mut s = ServerState::default();
+        add_signal_source(&mut s, &data(json!({ "id": "src" })));
+        add_wire(
+            &mut s,
+            &data(json!({ "id": "a", "fromZone": "signal", "fromId": "src" })),
+        );
+        add_wire(
+            &mut s,
+            &data(json!({ "id": "b", "fromZone": "signal", "fromId": "other" })),
+        );
+
+        remove_signal_source(&mut s, Some(&json!("src")));
+
+        assert_eq!(s.signal_sources["sources"], json!([]));
+        let wires = s.wiring["wires"].as_array().unwrap();
+        assert_eq!(wires.len(), 1);
+        assert_eq!(wires[0]["id"], "b");
+    }
+
+    #[test]
+    fn set_uniform_updates_one_value() {
+        let mut s = ServerState::default();
+        add_shader(
+            &mut s,
+            &data(json!({
+                "id": "sh",
+                "uniforms": [{ "name": "uSpeed", "value": 1 }, { "name": "uGlow", "value": 0.5 }],
+            })),
+        );
+        execute_command(
+            &mut s,
+            &Command {
+                action: "set-uniform".into(),
+                kind: "shader".into(),
+                data: data(json!({ "id": "sh", "uniformName": "uSpeed", "value": 3 })),
+            },
+        );
+        let uniforms = &s.shaders["shaders"][0]["uniforms"];
+        assert_eq!(uniforms[0]["value"], 3);
+        assert_eq!(uniforms[0]["defaultValue"], 1);
+        assert_eq!(uniforms[1]["value"], 0.5);
+    }
+}
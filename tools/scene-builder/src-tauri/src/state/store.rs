@@ -0,0 +1,296 @@
+//! Server-authoritative in-memory state store.
+//!
+//! Single source of truth for the scene while the desktop app runs. Rust
+//! features read and mutate it directly; the webview pushes snapshots and
+//! pulls state back through the `state_*` commands. Every write bumps a
+//! monotonically increasing version and is announced as a [`StateChange`].
+
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::broadcast;
+
+use super::mutations::{self, Command};
+use crate::signals::now_ms;
+
+/// Webview event announcing a new state version.
+pub const STATE_EVENT: &str = "state://changed";
+
+/// Full server state — same shape as the client state-sync snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerState {
+    pub scene: Map<String, Value>,
+    pub choreographies: Map<String, Value>,
+    pub wiring: Map<String, Value>,
+    pub bindings: Map<String, Value>,
+    pub shaders: Map<String, Value>,
+    pub p5: Map<String, Value>,
+    pub signal_sources: Map<String, Value>,
+    pub editor: Map<String, Value>,
+}
+
+/// Convert a `json!` object literal into a map.
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+impl Default for ServerState {
+    fn default() -> Self {
+        Self {
+            scene: object(json!({
+                "dimensions": { "width": 960, "height": 640 },
+                "background": { "color": "#1a1a2e" },
+                "layers": [
+                    { "id": "background", "name": "Background", "order": 0, "visible": true },
+                    { "id": "midground", "name": "Midground", "order": 1, "visible": true },
+                    { "id": "foreground", "name": "Foreground", "order": 2, "visible": true },
+                ],
+                "entities": [],
+                "positions": [],
+                "routes": [],
+                "zoneTypes": [],
+                "lighting": null,
+                "particles": [],
+            })),
+            choreographies: object(json!({ "choreographies": [] })),
+            wiring: object(json!({ "wires": [] })),
+            bindings: object(json!({ "bindings": [] })),
+            shaders: object(json!({ "shaders": [] })),
+            p5: object(json!({ "sketches": [] })),
+            signal_sources: object(json!({ "sources": [] })),
+            editor: Map::new(),
+        }
+    }
+}
+
+impl ServerState {
+    /// Replace every section present (and non-null) in `snapshot`.
+    pub fn apply_snapshot(&mut self, snapshot: &Map<String, Value>) {
+        let sections: [(&str, &mut Map<String, Value>); 8] = [
+            ("scene", &mut self.scene),
+            ("choreographies", &mut self.choreographies),
+            ("wiring", &mut self.wiring),
+            ("bindings", &mut self.bindings),
+            ("shaders", &mut self.shaders),
+            ("p5", &mut self.p5),
+            ("signalSources", &mut self.signal_sources),
+            ("editor", &mut self.editor),
+        ];
+        for (key, section) in sections {
+            if let Some(Value::Object(value)) = snapshot.get(key) {
+                *section = value.clone();
+            }
+        }
+    }
+}
+
+/// What caused a version bump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeOrigin {
+    /// The webview pushed a full snapshot.
+    Push,
+    /// A mutation from a backend feature (MCP, REST, commands).
+    Mutation,
+    /// The state was reset to empty.
+    Reset,
+}
+
+/// Notification sent on every state change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChange {
+    pub version: u64,
+    pub origin: ChangeOrigin,
+}
+
+/// A versioned snapshot, as returned to the webview.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionedState {
+    pub version: u64,
+    /// Timestamp of the last mutation, or `None` if pristine.
+    pub last_mutation_at: Option<u64>,
+    pub data: ServerState,
+}
+
+struct Inner {
+    state: ServerState,
+    version: u64,
+    last_mutation_at: Option<u64>,
+}
+
+/// Managed state: the store plus its change channel.
+pub struct StateStore {
+    inner: RwLock<Inner>,
+    changes: broadcast::Sender<StateChange>,
+}
+
+impl Default for StateStore {
+    fn default() -> Self {
+        let (changes, _) = broadcast::channel(256);
+        Self {
+            inner: RwLock::new(Inner {
+                state: ServerState::default(),
+                version: 0,
+                last_mutation_at: None,
+            }),
+            changes,
+        }
+    }
+}
+
+impl StateStore {
+    /// Subscribe to state changes.
+    pub fn subscribe(&self) -> broadcast::Receiver<StateChange> {
+        self.changes.subscribe()
+    }
+
+    /// Run `f` against the current state.
+    pub fn read<T>(&self, f: impl FnOnce(&ServerState) -> T) -> T {
+        let inner = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        f(&inner.state)
+    }
+
+    /// Full versioned snapshot.
+    pub fn snapshot(&self) -> VersionedState {
+        let inner = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        VersionedState {
+            version: inner.version,
+            last_mutation_at: inner.last_mutation_at,
+            data: inner.state.clone(),
+        }
+    }
+
+    /// Mutate the state in place, bump the version and notify.
+    pub fn mutate<T>(
+        &self,
+        origin: ChangeOrigin,
+        f: impl FnOnce(&mut ServerState) -> T,
+    ) -> (u64, T) {
+        let (version, result) = {
+            let mut inner = self.inner.write().unwrap_or_else(|e| e.into_inner());
+            let result = f(&mut inner.state);
+            inner.version += 1;
+            inner.last_mutation_at = Some(now_ms());
+            (inner.version, result)
+        };
+        // No receivers is fine — nobody is listening yet.
+        let _ = self.changes.send(StateChange { version, origin });
+        (version, result)
+    }
+
+    /// Replace sections from a full snapshot pushed by the webview.
+    pub fn set_full_state(&self, snapshot: &Map<String, Value>) -> u64 {
+        self.mutate(ChangeOrigin::Push, |s| s.apply_snapshot(snapshot))
+            .0
+    }
+
+    /// Execute a generic `{ action, type, data }` command.
+    pub fn execute(&self, command: &Command) -> u64 {
+        self.mutate(ChangeOrigin::Mutation, |s| {
+            mutations::execute_command(s, command)
+        })
+        .0
+    }
+
+    /// Reset to empty state (e.g. "New scene").
+    pub fn reset(&self) -> u64 {
+        self.mutate(ChangeOrigin::Reset, |s| *s = ServerState::default())
+            .0
+    }
+}
+
+/// Forward store changes to the webview as [`STATE_EVENT`].
+pub fn forward_changes(app: &AppHandle, store: &StateStore) {
+    let mut rx = store.subscribe();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(change) => {
+                    let _ = app.emit(STATE_EVENT, change);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Full state with its version (`GET /api/state/full` equivalent).
+#[tauri::command]
+pub fn state_pull(store: State<'_, StateStore>) -> VersionedState {
+    store.snapshot()
+}
+
+/// Replace state from a webview snapshot (`POST /api/state/push` equivalent).
+/// Returns the new version.
+#[tauri::command]
+pub fn state_push(store: State<'_, StateStore>, snapshot: Map<String, Value>) -> u64 {
+    store.set_full_state(&snapshot)
+}
+
+/// Apply a single mutation. Returns the new version.
+#[tauri::command]
+pub fn state_execute(store: State<'_, StateStore>, command: Command) -> u64 {
+    store.execute(&command)
+}
+
+/// Reset to the empty scene. Returns the new version.
+#[tauri::command]
+pub fn state_reset(store: State<'_, StateStore>) -> u64 {
+    store.reset()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutations_bump_version_and_notify() {
+        let store = StateStore::default();
+        let mut rx = store.subscribe();
+        assert_eq!(store.snapshot().last_mutation_at, None);
+
+        let version = store.execute(&Command {
+            action: "add".into(),
+            kind: "wire".into(),
+            data: object(json!({ "id": "w1", "fromZone": "signal", "fromId": "s1" })),
+        });
+
+        assert_eq!(version, 1);
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            StateChange {
+                version: 1,
+                origin: ChangeOrigin::Mutation
+            }
+        );
+        let snapshot = store.snapshot();
+        assert!(snapshot.last_mutation_at.is_some());
+        assert_eq!(snapshot.data.wiring["wires"][0]["id"], "w1");
+    }
+
+    #[test]
+    fn push_keeps_missing_sections() {
+        let store = StateStore::default();
+        store.set_full_state(&object(json!({
+            "editor": { "activeTool": "select" },
+            "scene": null,
+        })));
+        let snapshot = store.snapshot();
+        assert_eq!(snapshot.data.editor["activeTool"], "select");
+        assert_eq!(snapshot.data.scene, ServerState::default().scene);
+    }
+}
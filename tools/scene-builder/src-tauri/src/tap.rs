@@ -0,0 +1,392 @@
+//! Claude Code tap hooks — install/uninstall in `.claude/settings.local.json`.
+//!
+//! Port of `packages/mcp-server/src/routes/tap.ts`. The project directory is
+//! picked by the user instead of being derived from the working directory.
+//! Every directory we touch is recorded in a ledger under the app data dir,
+//! so hooks are removed on exit and, after a crash, on the next launch.
+
+use std::collections::BTreeSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::project::files::write_atomic;
+use crate::server::ServerInfo;
+
+/// Tag used to identify sajou-tap hooks in settings.
+const TAP_HOOK_TAG: &str = "sajou-tap";
+
+/// Hook event names that sajou-tap installs.
+const TAP_HOOK_EVENTS: [&str; 6] = [
+    "PreToolUse",
+    "PostToolUse",
+    "PostToolUseFailure",
+    "SubagentStart",
+    "SubagentStop",
+    "Stop",
+];
+
+/// Ledger file name, in the app data dir.
+const LEDGER_FILE: &str = "tap-hooks.json";
+
+/// Persisted tap bookkeeping.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TapLedger {
+    /// Project directories that currently carry sajou hooks.
+    pub installed: BTreeSet<PathBuf>,
+    /// Last project chosen by the user, reused on auto-connect.
+    pub last_project_dir: Option<PathBuf>,
+}
+
+/// Managed state: the in-memory ledger plus where it is persisted.
+pub struct TapState {
+    ledger: Mutex<TapLedger>,
+    path: Option<PathBuf>,
+}
+
+impl TapState {
+    fn save(&self, ledger: &TapLedger) {
+        let Some(path) = &self.path else { return };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let raw = serde_json::to_string_pretty(ledger).unwrap_or_default();
+        if let Err(e) = std::fs::write(path, raw) {
+            eprintln!("[sajou] cannot write {}: {e}", path.display());
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// settings.local.json editing
+// ---------------------------------------------------------------------------
+
+fn settings_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".claude").join("settings.local.json")
+}
+
+/// Read settings, treating a missing file as empty. A malformed one is an
+/// error: it belongs to the user and must not be overwritten.
+fn read_settings(path: &Path) -> io::Result<Map<String, Value>> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => return Err(e),
+    };
+    serde_json::from_str(&raw).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed {}: {e}", path.display()),
+        )
+    })
+}
+
+fn write_settings(path: &Path, settings: &Map<String, Value>) -> io::Result<()> {
+    let mut raw = serde_json::to_string_pretty(settings).map_err(io::Error::other)?;
+    raw.push('\n');
+    write_atomic(path, raw.as_bytes())
+}
+
+/// Whether a hook group contains an entry installed by sajou-tap.
+fn is_tap_group(group: &Value) -> bool {
+    group["hooks"]
+        .as_array()
+        .is_some_and(|hooks| hooks.iter().any(|h| h["statusMessage"] == TAP_HOOK_TAG))
+}
+
+/// Merge tap hooks into a settings object, replacing any previous tap entries.
+pub fn merge_tap_hooks(settings: &mut Map<String, Value>, command: &str) {
+    let hooks = settings
+        .entry("hooks")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !hooks.is_object() {
+        *hooks = Value::Object(Map::new());
+    }
+    let Some(hooks) = hooks.as_object_mut() else {
+        return;
+    };
+
+    for event in TAP_HOOK_EVENTS {
+        let groups = hooks.entry(event).or_insert_with(|| json!([]));
+        if !groups.is_array() {
+            *groups = json!([]);
+        }
+        if let Some(groups) = groups.as_array_mut() {
+            groups.retain(|g| !is_tap_group(g));
+            groups.push(json!({
+                "hooks": [{
+                    "type": "command",
+                    "command": command,
+                    "async": true,
+                    "timeout": 5,
+                    "statusMessage": TAP_HOOK_TAG,
+                }],
+            }));
+        }
+    }
+}
+
+/// Remove tap hooks, dropping emptied events and an emptied `hooks` key.
+/// Returns `false` when there was no `hooks` key to clean.
+pub fn remove_tap_hooks(settings: &mut Map<String, Value>) -> bool {
+    let Some(Value::Object(hooks)) = settings.get("hooks") else {
+        return false;
+    };
+
+    let mut cleaned = Map::new();
+    for (event, groups) in hooks {
+        let filtered: Vec<Value> = groups
+            .as_array()
+            .map(|gs| gs.iter().filter(|g| !is_tap_group(g)).cloned().collect())
+            .unwrap_or_default();
+        if !filtered.is_empty() {
+            cleaned.insert(event.clone(), Value::Array(filtered));
+        }
+    }
+
+    if cleaned.is_empty() {
+        settings.remove("hooks");
+    } else {
+        settings.insert("hooks".into(), Value::Object(cleaned));
+    }
+    true
+}
+
+/// Install sajou-tap hooks into `<project_dir>/.claude/settings.local.json`.
+pub fn install_tap_hooks(project_dir: &Path, command: &str) -> io::Result<()> {
+    let path = settings_path(project_dir);
+    let mut settings = read_settings(&path)?;
+    merge_tap_hooks(&mut settings, command);
+    write_settings(&path, &settings)
+}
+
+/// Remove sajou-tap hooks from `<project_dir>/.claude/settings.local.json`.
+pub fn uninstall_tap_hooks(project_dir: &Path) -> io::Result<()> {
+    let path = settings_path(project_dir);
+    let mut settings = read_settings(&path)?;
+    if remove_tap_hooks(&mut settings) {
+        write_settings(&path, &settings)?;
+    }
+    Ok(())
+}
+
+/// Native `sajou-emit` shipped next to the app executable, if present.
+fn native_emitter() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let emitter = exe.with_file_name(format!("sajou-emit{}", std::env::consts::EXE_SUFFIX));
+    emitter.is_file().then_some(emitter)
+}
+
+/// Command written into hooks. Prefers the bundled native emitter (no Node
+/// cold start) and targets the embedded server explicitly so a fallback
+/// port is still reached.
+fn hook_command(app: &AppHandle) -> String {
+    let emitter = match native_emitter() {
+        Some(path) => format!("\"{}\" --stdin", path.display()),
+        None => "npx sajou-emit --stdin".into(),
+    };
+    match app.try_state::<ServerInfo>() {
+        Some(info) => format!("{emitter} --endpoint {}/api/signal", info.base_url()),
+        None => emitter,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Lifecycle
+// ---------------------------------------------------------------------------
+
+/// Load the ledger and remove hooks left behind by a previous run that
+/// did not exit cleanly.
+pub fn init(app: &AppHandle) {
+    let path = app.path().app_data_dir().ok().map(|d| d.join(LEDGER_FILE));
+    let mut ledger: TapLedger = path
+        .as_ref()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+
+    for dir in std::mem::take(&mut ledger.installed) {
+        match uninstall_tap_hooks(&dir) {
+            Ok(()) => eprintln!("[sajou] removed stale tap hooks in {}", dir.display()),
+            Err(e) => eprintln!("[sajou] cannot clean tap hooks in {}: {e}", dir.display()),
+        }
+    }
+
+    let state = TapState {
+        ledger: Mutex::new(ledger.clone()),
+        path,
+    };
+    state.save(&ledger);
+    app.manage(state);
+}
+
+/// Remove every hook installed during this session. Called on app exit.
+pub fn cleanup(app: &AppHandle) {
+    let Some(state) = app.try_state::<TapState>() else {
+        return;
+    };
+    let mut ledger = state.ledger.lock().unwrap_or_else(|e| e.into_inner());
+    for dir in std::mem::take(&mut ledger.installed) {
+        let _ = uninstall_tap_hooks(&dir);
+    }
+    state.save(&ledger);
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Installed projects and the last chosen project, for the connection UI.
+#[tauri::command]
+pub fn tap_status(state: State<'_, TapState>) -> TapLedger {
+    state
+        .ledger
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Ask the user for the Claude Code project to tap.
+#[tauri::command]
+pub async fn tap_pick_project(app: AppHandle) -> Option<PathBuf> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Choose the Claude Code project to connect")
+        .pick_folder(move |folder| {
+            let _ = tx.send(folder.and_then(|f| f.into_path().ok()));
+        });
+    rx.await.ok().flatten()
+}
+
+/// Install hooks in `project_dir` and remember it for cleanup and auto-connect.
+#[tauri::command]
+pub fn tap_install_hooks(
+    app: AppHandle,
+    state: State<'_, TapState>,
+    project_dir: PathBuf,
+) -> Result<(), String> {
+    if !project_dir.is_dir() {
+        return Err(format!("not a directory: {}", project_dir.display()));
+    }
+    // Recorded before writing, so hooks left by a crash mid-install are
+    // still found and removed on the next launch.
+    let mut ledger = state.ledger.lock().unwrap_or_else(|e| e.into_inner());
+    let recorded = ledger.installed.insert(project_dir.clone());
+    state.save(&ledger);
+
+    if let Err(e) = install_tap_hooks(&project_dir, &hook_command(&app)) {
+        if recorded {
+            ledger.installed.remove(&project_dir);
+            state.save(&ledger);
+        }
+        return Err(format!(
+            "cannot install hooks in {}: {e}",
+            project_dir.display()
+        ));
+    }
+    ledger.last_project_dir = Some(project_dir);
+    state.save(&ledger);
+    Ok(())
+}
+
+/// Remove hooks from `project_dir`, or from every tapped project if omitted.
+#[tauri::command]
+pub fn tap_uninstall_hooks(
+    state: State<'_, TapState>,
+    project_dir: Option<PathBuf>,
+) -> Result<(), String> {
+    let mut ledger = state.ledger.lock().unwrap_or_else(|e| e.into_inner());
+    let targets: Vec<PathBuf> = match project_dir {
+        Some(dir) => vec![dir],
+        None => ledger.installed.iter().cloned().collect(),
+    };
+
+    let mut result = Ok(());
+    for dir in targets {
+        match uninstall_tap_hooks(&dir) {
+            Ok(()) => {
+                ledger.installed.remove(&dir);
+            }
+            Err(e) => result = Err(format!("cannot remove hooks in {}: {e}", dir.display())),
+        }
+    }
+    state.save(&ledger);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_preserves_foreign_hooks_and_dedupes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = settings_path(dir.path());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            r#"{"model":"opus","hooks":{"Stop":[{"hooks":[{"type":"command","command":"say done"}]}]}}"#,
+        )
+        .unwrap();
+
+        install_tap_hooks(dir.path(), "sajou-emit --stdin").unwrap();
+        install_tap_hooks(dir.path(), "sajou-emit --stdin").unwrap();
+
+        let settings = read_settings(&path).unwrap();
+        assert_eq!(settings.keys().next().unwrap(), "model");
+        let stop = settings["hooks"]["Stop"].as_array().unwrap();
+        assert_eq!(stop.len(), 2);
+        assert_eq!(stop[0]["hooks"][0]["command"], "say done");
+        for event in TAP_HOOK_EVENTS {
+            let tapped = settings["hooks"][event]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|g| is_tap_group(g))
+                .count();
+            assert_eq!(tapped, 1, "{event}");
+        }
+    }
+
+    #[test]
+    fn uninstall_restores_original_shape() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = settings_path(dir.path());
+
+        install_tap_hooks(dir.path(), "sajou-emit --stdin").unwrap();
+        uninstall_tap_hooks(dir.path()).unwrap();
+
+        let settings = read_settings(&path).unwrap();
+        assert!(!settings.contains_key("hooks"));
+    }
+
+    #[test]
+    fn malformed_settings_are_left_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = settings_path(dir.path());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, r#"{"model":"opus","#).unwrap();
+
+        let err = install_tap_hooks(dir.path(), "sajou-emit --stdin").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(uninstall_tap_hooks(dir.path()).is_err());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            r#"{"model":"opus","#
+        );
+    }
+
+    #[test]
+    fn uninstall_without_settings_is_a_no_op() {
+        let dir = tempfile::tempdir().unwrap();
+        uninstall_tap_hooks(dir.path()).unwrap();
+        assert!(!settings_path(dir.path()).exists());
+    }
+}
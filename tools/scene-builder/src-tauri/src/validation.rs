@@ -0,0 +1,349 @@
+//! Schema validation of incoming signals.
+//!
+//! Every envelope entering the backend — `POST /api/signal`, MCP
+//! `emit_signal`, native sources — is checked against `signal.schema.json`
+//! through the typed model in [`crate::signal_model`]. The policy decides
+//! what happens to an invalid one: `reject` drops it into a rolling log the
+//! webview can read, `warn` passes it on with the errors in
+//! `metadata.validationErrors`, `pass` skips validation. The policy lives in
+//! `signal-validation.json` in the app config dir.
+
+use std::collections::VecDeque;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager, State};
+
+use crate::signal_model::SignalEnvelope;
+use crate::signals::now_ms;
+
+/// Policy file name in the app config dir.
+const POLICY_FILE: &str = "signal-validation.json";
+
+/// Rejected signals kept for inspection, oldest dropped first.
+const LOG_CAPACITY: usize = 200;
+
+/// What to do with a signal that fails validation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Policy {
+    /// Drop it and record it in the rejection log.
+    Reject,
+    /// Let it through with `metadata.validationErrors`.
+    #[default]
+    Warn,
+    /// Do not validate.
+    Pass,
+}
+
+/// A signal the `reject` policy dropped.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rejection {
+    /// When it arrived (Unix ms).
+    pub received_at: u64,
+    /// Ingestion path: `http`, `mcp` or `source:<id>`.
+    pub via: String,
+    pub errors: Vec<String>,
+    pub signal: Value,
+}
+
+/// Schema errors of `envelope`; empty when it is valid.
+pub fn check(envelope: &Value) -> Vec<String> {
+    match SignalEnvelope::from_value(envelope.clone()) {
+        Ok(_) => Vec::new(),
+        Err(e) => vec![e.to_string()],
+    }
+}
+
+/// Validation policy and rejection log, shared by every ingestion path.
+pub struct Validator {
+    path: Option<PathBuf>,
+    policy: Mutex<Policy>,
+    rejected: Mutex<VecDeque<Rejection>>,
+}
+
+impl Validator {
+    /// Validator with the policy stored at `path` (default when missing).
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let policy = path.as_deref().map(read_policy).unwrap_or_default();
+        Self {
+            path,
+            policy: Mutex::new(policy),
+            rejected: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Validator for the app, with its policy file in the app config dir.
+    pub fn for_app(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|d| d.join(POLICY_FILE));
+        Self::load(path)
+    }
+
+    pub fn policy(&self) -> Policy {
+        *self.policy.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Switch policy and remember it.
+    pub fn set_policy(&self, policy: Policy) -> io::Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, json!({ "policy": policy }).to_string())?;
+        }
+        *self.policy.lock().unwrap_or_else(|e| e.into_inner()) = policy;
+        Ok(())
+    }
+
+    /// Apply the policy to an envelope arriving `via` an ingestion path.
+    /// Returns the envelope to forward, or the errors it was rejected for.
+    pub fn admit(&self, mut envelope: Value, via: &str) -> Result<Value, Vec<String>> {
+        let policy = self.policy();
+        if policy == Policy::Pass {
+            return Ok(envelope);
+        }
+        let errors = check(&envelope);
+        if errors.is_empty() {
+            return Ok(envelope);
+        }
+        if policy == Policy::Warn {
+            annotate(&mut envelope, &errors);
+            return Ok(envelope);
+        }
+
+        eprintln!("[sajou] rejected signal via {via}: {}", errors.join("; "));
+        let mut log = self.rejected.lock().unwrap_or_else(|e| e.into_inner());
+        if log.len() == LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(Rejection {
+            received_at: now_ms(),
+            via: via.to_string(),
+            errors: errors.clone(),
+            signal: envelope,
+        });
+        Err(errors)
+    }
+
+    /// Rejected signals, oldest first.
+    pub fn rejected(&self) -> Vec<Rejection> {
+        let log = self.rejected.lock().unwrap_or_else(|e| e.into_inner());
+        log.iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.rejected
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+fn read_policy(path: &Path) -> Policy {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .and_then(|file| serde_json::from_value(file["policy"].clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Put `errors` in the envelope's `metadata.validationErrors`.
+fn annotate(envelope: &mut Value, errors: &[String]) {
+    let Some(fields) = envelope.as_object_mut() else {
+        return;
+    };
+    let metadata = fields
+        .entry("metadata")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !metadata.is_object() {
+        *metadata = Value::Object(Map::new());
+    }
+    metadata["validationErrors"] = json!(errors);
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub fn signal_validation_policy(validator: State<'_, Validator>) -> Policy {
+    validator.policy()
+}
+
+#[tauri::command]
+pub fn set_signal_validation_policy(
+    validator: State<'_, Validator>,
+    policy: Policy,
+) -> Result<Policy, String> {
+    validator.set_policy(policy).map_err(|e| e.to_string())?;
+    Ok(policy)
+}
+
+/// The rolling log of rejected signals, oldest first.
+#[tauri::command]
+pub fn rejected_signals(validator: State<'_, Validator>) -> Vec<Rejection> {
+    validator.rejected()
+}
+
+#[tauri::command]
+pub fn clear_rejected_signals(validator: State<'_, Validator>) {
+    validator.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::{
+        anthropic, gemini, ollama, openai, openclaw, prompt_dispatch, StreamItem,
+    };
+
+    fn state_change(to: &str) -> Value {
+        json!({
+            "id": "s1",
+            "type": "agent_state_change",
+            "timestamp": 1,
+            "source": "adapter:test",
+            "payload": { "agentId": "a1", "from": "idle", "to": to },
+        })
+    }
+
+    #[test]
+    fn applies_the_policy_to_invalid_signals() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(POLICY_FILE);
+        let validator = Validator::load(Some(path.clone()));
+        assert_eq!(validator.policy(), Policy::Warn);
+
+        assert_eq!(
+            validator.admit(state_change("acting"), "http"),
+            Ok(state_change("acting"))
+        );
+        let warned = validator.admit(state_change("sleeping"), "http").unwrap();
+        assert!(warned["metadata"]["validationErrors"][0]
+            .as_str()
+            .is_some_and(|e| e.contains("sleeping")));
+
+        validator.set_policy(Policy::Reject).unwrap();
+        assert!(validator
+            .admit(state_change("sleeping"), "source:s")
+            .is_err());
+        let log = validator.rejected();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].via, "source:s");
+        assert_eq!(Validator::load(Some(path)).policy(), Policy::Reject);
+
+        validator.set_policy(Policy::Pass).unwrap();
+        assert_eq!(
+            validator.admit(state_change("sleeping"), "mcp"),
+            Ok(state_change("sleeping"))
+        );
+    }
+
+    fn signals(body: &str, mut push: impl FnMut(&[u8]) -> Vec<StreamItem>) -> Vec<Value> {
+        push(body.as_bytes())
+            .into_iter()
+            .filter_map(|item| match item {
+                StreamItem::Signal { signal, .. } => Some(signal),
+                StreamItem::Log { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn native_producers_emit_valid_signals() {
+        let mut produced = vec![prompt_dispatch("Hello", "llama3", "flow-1")];
+
+        let mut stream = openai::ChatStream::new("llama3", "flow-1");
+        produced.extend(signals(
+            concat!(
+                "data: {\"choices\":[{\"delta\":{\"reasoning_content\":\"Hm\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"},\"finish_reason\":\"stop\"}]}\n\n",
+                "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":2}}\n\n",
+                "data: {\"error\":{\"message\":\"overloaded\"}}\n\n",
+                "data: [DONE]\n\n",
+            ),
+            |bytes| stream.push(bytes),
+        ));
+
+        let mut stream = anthropic::MessageStream::new("claude", "flow-1");
+        produced.extend(signals(
+            concat!(
+                "event: message_start\ndata: {\"message\":{\"model\":\"claude\"}}\n\n",
+                "event: content_block_start\ndata: {\"content_block\":{\"type\":\"tool_use\",\"id\":\"t1\",\"name\":\"grep\"}}\n\n",
+                "event: content_block_delta\ndata: {\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"Hm\"}}\n\n",
+                "event: content_block_delta\ndata: {\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi\"}}\n\n",
+                "event: message_delta\ndata: {\"usage\":{\"output_tokens\":15}}\n\n",
+                "event: message_stop\ndata: {}\n\n",
+                "event: error\ndata: {\"error\":{\"type\":\"overloaded_error\",\"message\":\"busy\"}}\n\n",
+            ),
+            |bytes| stream.push(bytes),
+        ));
+
+        let mut stream = gemini::ContentStream::new("gemini-2.5-pro", "flow-1");
+        produced.extend(signals(
+            concat!(
+                "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Hm\",\"thought\":true},",
+                "{\"functionCall\":{\"name\":\"get_weather\",\"args\":{\"city\":\"Lyon\"}}}]}}]}\n\n",
+                "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Hi\"}]},\"finishReason\":\"STOP\"}],",
+                "\"usageMetadata\":{\"promptTokenCount\":2,\"candidatesTokenCount\":2}}\n\n",
+                "data: {\"promptFeedback\":{\"blockReason\":\"SAFETY\"}}\n\n",
+            ),
+            |bytes| stream.push(bytes),
+        ));
+
+        let mut stream = ollama::ChatStream::new("qwen3", "flow-1");
+        produced.extend(signals(
+            concat!(
+                "{\"model\":\"qwen3\",\"message\":{\"content\":\"\",\"thinking\":\"Hm\"},\"done\":false}\n",
+                "{\"model\":\"qwen3\",\"message\":{\"content\":\"Hi\"},\"done\":false}\n",
+                "{\"model\":\"qwen3\",\"done\":true,\"eval_count\":1,\"total_duration\":5000000}\n",
+                "{\"error\":\"model not found\"}\n",
+            ),
+            |bytes| stream.push(bytes),
+        ));
+
+        let agent = |stream: &str, data: Value| {
+            json!({ "type": "event", "event": "agent", "payload": {
+                "stream": stream, "provider": "telegram", "label": "Ops", "sessionKey": "tg:1",
+                "data": data,
+            } })
+        };
+        let events = [
+            agent("lifecycle", json!({ "agentId": "main", "phase": "start" })),
+            agent("lifecycle", json!({ "phase": "end" })),
+            agent("lifecycle", json!({ "phase": "error", "message": "boom" })),
+            agent(
+                "tool",
+                json!({ "phase": "start", "toolName": "exec", "callId": "c1" }),
+            ),
+            agent(
+                "tool",
+                json!({ "phase": "end", "tool": "exec", "result": "ok" }),
+            ),
+            agent(
+                "tool",
+                json!({ "phase": "end", "tool": "exec", "output": { "code": 0 } }),
+            ),
+            agent("assistant", json!({ "delta": "Hi" })),
+            agent("thinking", json!({ "delta": "Hm" })),
+            json!({ "type": "exec.approval.requested", "payload": { "data": { "agentId": "main" } } }),
+            json!({ "event": "session", "payload": { "data": { "input_tokens": 5, "output_tokens": 7 } } }),
+        ];
+        produced.extend(events.iter().filter_map(|event| {
+            openclaw::parse_event(event).map(|signal| openclaw::schema_shaped(signal, event))
+        }));
+
+        assert_eq!(produced.len(), 35);
+        for signal in &produced {
+            assert_eq!(check(signal), Vec::<String>::new(), "{signal}");
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Minimal WebSocket client (RFC 6455) for loopback gateways.
+//!
+//! Only what native sources need: a `ws://` handshake (bounded by a connect
+//! timeout, with `Sec-WebSocket-Accept` checked), masked text frames out,
+//! text/continuation frames in, ping/pong/close handling, and an idle check
+//! that pings a silent peer and gives up on a half-open socket. TLS
+//! (`wss://`) is left to the webview transports; [`check_url`] tells callers
+//! up front.
+
+use std::io;
+use std::time::Duration;
+
+use base64::Engine;
+use sha1::{Digest, Sha1};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use sajou_client::endpoint::split_url;
+
+/// Largest message accepted from a peer.
+const MAX_MESSAGE: usize = 16 * 1024 * 1024;
+
+/// Largest handshake response accepted.
+const MAX_HANDSHAKE: usize = 16 * 1024;
+
+/// Time allowed for the TCP connection and the handshake.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Silence after which the peer is pinged, and then given up on.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Appended to the key to derive `Sec-WebSocket-Accept` (RFC 6455 §4.2.2).
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+// Frame opcodes.
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// The `Sec-WebSocket-Accept` a server must answer `key` with.
+fn accept_key(key: &str) -> String {
+    let digest = Sha1::digest(format!("{key}{ACCEPT_GUID}").as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(digest)
+}
+
+/// The value of header `name` in a response head, matched case-insensitively.
+fn header<'h>(head: &'h str, name: &str) -> Option<&'h str> {
+    head.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+/// Fails with [`io::ErrorKind::Unsupported`] unless `url` can be opened
+/// natively, i.e. it is a plain `ws://` URL.
+pub(crate) fn check_url(url: &str) -> io::Result<()> {
+    if url.starts_with("ws://") {
+        return Ok(());
+    }
+    let reason = if url.starts_with("wss://") {
+        "TLS (wss://) is not supported natively"
+    } else {
+        "only ws:// URLs are supported natively"
+    };
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{reason}: {url}"),
+    ))
+}
+
+/// An open client connection.
+pub(crate) struct WsClient {
+    stream: TcpStream,
+    /// Bytes read from the socket but not consumed yet.
+    buf: Vec<u8>,
+    /// Silence allowed before a ping, and again before giving up.
+    idle: Duration,
+}
+
+impl WsClient {
+    /// Open `ws://host[:port][/path]` and complete the upgrade handshake,
+    /// giving up after [`CONNECT_TIMEOUT`].
+    pub async fn connect(url: &str) -> io::Result<Self> {
+        tokio::time::timeout(CONNECT_TIMEOUT, Self::open(url))
+            .await
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("no WebSocket handshake from {url} within {CONNECT_TIMEOUT:?}"),
+                )
+            })?
+    }
+
+    async fn open(url: &str) -> io::Result<Self> {
+        check_url(url)?;
+        let http = format!("http://{}", &url["ws://".len()..]);
+        let (host, port, path) = split_url(&http)?;
+        let stream = TcpStream::connect((host.as_str(), port)).await?;
+        let host = if host.contains(':') {
+            format!("[{host}]")
+        } else {
+            host
+        };
+        let mut client = Self {
+            stream,
+            buf: Vec::new(),
+            idle: IDLE_TIMEOUT,
+        };
+
+        let key = base64::engine::general_purpose::STANDARD.encode(uuid::Uuid::new_v4().as_bytes());
+        let request = format!(
+            "GET {path} HTTP/1.1\r\nHost: {host}:{port}\r\nUpgrade: websocket\r\n\
+             Connection: Upgrade\r\nSec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\r\n"
+        );
+        client.stream.write_all(request.as_bytes()).await?;
+
+        let end = loop {
+            if let Some(i) = client.buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break i + 4;
+            }
+            if client.buf.len() > MAX_HANDSHAKE {
+                return Err(invalid("handshake response too large"));
+            }
+            client.read_more().await?;
+        };
+        let head = String::from_utf8_lossy(&client.buf[..end]).into_owned();
+        client.buf.drain(..end);
+        let status = head.split_whitespace().nth(1).unwrap_or_default();
+        if status != "101" {
+            let line = head.lines().next().unwrap_or_default();
+            return Err(invalid(format!("upgrade refused: {line}")));
+        }
+        if header(&head, "Sec-WebSocket-Accept") != Some(accept_key(&key).as_str()) {
+            return Err(invalid(
+                "upgrade answered with a wrong Sec-WebSocket-Accept",
+            ));
+        }
+        Ok(client)
+    }
+
+    /// Read whatever the socket has next. After `idle` of silence the peer
+    /// is pinged; after another `idle` the connection counts as lost.
+    async fn read_more(&mut self) -> io::Result<()> {
+        let mut chunk = [0u8; 8192];
+        let mut pinged = false;
+        let n = loop {
+            match tokio::time::timeout(self.idle, self.stream.read(&mut chunk)).await {
+                Ok(read) => break read?,
+                Err(_) if !pinged => {
+                    self.write_frame(OP_PING, &[]).await?;
+                    pinged = true;
+                }
+                Err(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("no data from the peer within {:?}", self.idle * 2),
+                    ))
+                }
+            }
+        };
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.buf.extend_from_slice(&chunk[..n]);
+        Ok(())
+    }
+
+    /// Take exactly `n` buffered bytes, reading more as needed.
+    async fn take(&mut self, n: usize) -> io::Result<Vec<u8>> {
+        while self.buf.len() < n {
+            self.read_more().await?;
+        }
+        Ok(self.buf.drain(..n).collect())
+    }
+
+    /// Read one frame: `(fin, opcode, payload)`.
+    async fn read_frame(&mut self) -> io::Result<(bool, u8, Vec<u8>)> {
+        let head = self.take(2).await?;
+        let fin = head[0] & 0x80 != 0;
+        let opcode = head[0] & 0x0f;
+        let masked = head[1] & 0x80 != 0;
+        let len = match head[1] & 0x7f {
+            126 => u16::from_be_bytes(self.take(2).await?.try_into().unwrap_or_default()) as u64,
+            127 => u64::from_be_bytes(self.take(8).await?.try_into().unwrap_or_default()),
+            n => n as u64,
+        };
+        if len > MAX_MESSAGE as u64 {
+            return Err(invalid("frame too large"));
+        }
+        let mask = if masked {
+            Some(self.take(4).await?)
+        } else {
+            None
+        };
+        let mut payload = self.take(len as usize).await?;
+        if let Some(mask) = mask {
+            for (i, b) in payload.iter_mut().enumerate() {
+                *b ^= mask[i % 4];
+            }
+        }
+        Ok((fin, opcode, payload))
+    }
+
+    async fn write_frame(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let mut frame = vec![0x80 | opcode];
+        match payload.len() {
+            n if n < 126 => frame.push(0x80 | n as u8),
+            n if n <= u16::MAX as usize => {
+                frame.push(0x80 | 126);
+                frame.extend_from_slice(&(n as u16).to_be_bytes());
+            }
+            n => {
+                frame.push(0x80 | 127);
+                frame.extend_from_slice(&(n as u64).to_be_bytes());
+            }
+        }
+        // Client frames must be masked.
+        let mask: [u8; 4] = uuid::Uuid::new_v4().as_bytes()[..4]
+            .try_into()
+            .unwrap_or_default();
+        frame.extend_from_slice(&mask);
+        frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        self.stream.write_all(&frame).await
+    }
+
+    /// Send a text message.
+    pub async fn send_text(&mut self, text: &str) -> io::Result<()> {
+        self.write_frame(OP_TEXT, text.as_bytes()).await
+    }
+
+    /// Next message. `Ok(None)` when the peer closed the connection cleanly.
+    pub async fn recv(&mut self) -> io::Result<Option<String>> {
+        let mut message = Vec::new();
+        loop {
+            let (fin, opcode, payload) = self.read_frame().await?;
+            match opcode {
+                OP_TEXT | OP_BINARY | OP_CONTINUATION => {
+                    if message.len() + payload.len() > MAX_MESSAGE {
+                        return Err(invalid("message too large"));
+                    }
+                    message.extend_from_slice(&payload);
+                    if fin {
+                        return Ok(Some(String::from_utf8_lossy(&message).into_owned()));
+                    }
+                }
+                OP_PING => self.write_frame(OP_PONG, &payload).await?,
+                OP_PONG => {}
+                OP_CLOSE => {
+                    // Echo the status code back, as the spec requires.
+                    let _ = self
+                        .write_frame(OP_CLOSE, &payload[..payload.len().min(2)])
+                        .await;
+                    return Ok(None);
+                }
+                other => return Err(invalid(format!("unknown opcode {other:#x}"))),
+            }
+        }
+    }
+
+    /// Start a normal closure.
+    pub async fn close(&mut self) {
+        let _ = self.write_frame(OP_CLOSE, &1000u16.to_be_bytes()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use super::*;
+
+    #[test]
+    fn exchanges_text_and_answers_pings() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/gw", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            // Handshake, a ping, then "hello" split across two frames, then close.
+            let head = String::from_utf8_lossy(&request).into_owned();
+            let accept = accept_key(header(&head, "sec-websocket-key").unwrap());
+            write!(
+                stream,
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+                 sec-websocket-accept: {accept}\r\n\r\n"
+            )
+            .unwrap();
+            stream.write_all(&[0x89, 0x01, b'p']).unwrap();
+            stream.write_all(&[0x01, 0x03, b'h', b'e', b'l']).unwrap();
+            stream.write_all(&[0x80, 0x02, b'l', b'o']).unwrap();
+
+            // Client's pong: masked, payload "p".
+            let mut pong = [0u8; 7];
+            stream.read_exact(&mut pong).unwrap();
+            assert_eq!(pong[0], 0x8A);
+            assert_eq!(pong[1], 0x81);
+            assert_eq!(pong[6] ^ pong[2], b'p');
+
+            stream.write_all(&[0x88, 0x02, 0x03, 0xE8]).unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let (first, second) = tauri::async_runtime::block_on(async {
+            let mut client = WsClient::connect(&url).await.unwrap();
+            (client.recv().await.unwrap(), client.recv().await.unwrap())
+        });
+        assert_eq!(first.as_deref(), Some("hello"));
+        assert_eq!(second, None);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /gw HTTP/1.1\r\n"));
+        assert!(request.contains("Sec-WebSocket-Version: 13\r\n"));
+    }
+
+    #[test]
+    fn pings_a_silent_peer_then_gives_up() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/gw", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let head = String::from_utf8_lossy(&request).into_owned();
+            let accept = accept_key(header(&head, "sec-websocket-key").unwrap());
+            write!(
+                stream,
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+                 sec-websocket-accept: {accept}\r\n\r\n"
+            )
+            .unwrap();
+
+            // Client's ping: masked, empty. Never answered.
+            let mut ping = [0u8; 6];
+            stream.read_exact(&mut ping).unwrap();
+            assert_eq!(&ping[..2], &[0x89, 0x80]);
+            // Keep the socket open until the client gives up.
+            let _ = stream.read(&mut buf);
+        });
+
+        let error = tauri::async_runtime::block_on(async {
+            let mut client = WsClient::connect(&url).await.unwrap();
+            client.idle = Duration::from_millis(50);
+            client.recv().await.unwrap_err()
+        });
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn rejects_tls_urls_up_front() {
+        assert!(check_url("ws://127.0.0.1:18789").is_ok());
+        let error = check_url("wss://gateway.example.com").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert!(error.to_string().contains("TLS"));
+    }
+
+    #[test]
+    fn checks_the_accept_key() {
+        // The example handshake of RFC 6455 §1.3.
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/gw", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 101 Switching Protocols\r\nSec-WebSocket-Accept: nope\r\n\r\n",
+                )
+                .unwrap();
+        });
+        let result = tauri::async_runtime::block_on(WsClient::connect(&url));
+        assert_eq!(result.err().unwrap().kind(), io::ErrorKind::InvalidData);
+        server.join().unwrap();
+    }
+}
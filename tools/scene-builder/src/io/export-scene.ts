@@ -5,6 +5,10 @@
  * into a .sajou archive (ZIP format via fflate) and triggers a browser download.
  * A dialog lets the user choose which sections to include.
  *
+ * In the desktop app the archive is built by the Rust backend
+ * (`src-tauri/src/scene/export.rs`) from the saved project folder and
+ * written to a file picked in a native dialog, streaming assets from disk.
+ *
  * Archive structure (all sections selected):
  *   scene.json            — scene layout (dimensions, background, layers, placed entities, positions, routes)
  *   entities.json         — entity definitions (visual config, defaults, tags)
@@ -24,6 +28,8 @@ import { getBindingState } from "../state/binding-store.js";
 import { getShaderState } from "../shader-editor/shader-state.js";
 import type { ShaderEditorState } from "../shader-editor/shader-types.js";
 import { getSketchState } from "../sketch-editor/sketch-state.js";
+import { flushSaves } from "../state/persistence.js";
+import { isTauri } from "../utils/platform-fetch.js";
 import type { SketchEditorState } from "../sketch-editor/sketch-types.js";
 import { showExportDialog } from "./export-dialog.js";
 import type { ExportSelection, ExportSummary } from "./export-dialog.js";
@@ -240,6 +246,50 @@ function computeExportSummary(): ExportSummary {
   };
 }
 
+// ---------------------------------------------------------------------------
+// Native export (desktop app)
+// ---------------------------------------------------------------------------
+
+/** Mirrors `Progress` in `src-tauri/src/scene/export.rs`. */
+export interface ExportProgress {
+  /** ZIP path of the entry just written. */
+  entry: string;
+  done: number;
+  total: number;
+}
+
+/** Mirrors `Report` in `src-tauri/src/scene/export.rs`. */
+interface NativeExportReport {
+  entries: string[];
+  bytes: number;
+  missingAssets: string[];
+}
+
+/** Build the archive in the backend from the saved stores. */
+async function exportSceneNative(
+  selection: ExportSelection,
+  onProgress?: (progress: ExportProgress) => void,
+): Promise<void> {
+  // The backend reads the project folder, so it must hold the latest state.
+  await flushSaves();
+  const { invoke } = await import("@tauri-apps/api/core");
+  const { listen } = await import("@tauri-apps/api/event");
+  const unlisten = await listen<ExportProgress>("scene://export-progress", (event) => {
+    onProgress?.(event.payload);
+  });
+  try {
+    const report = await invoke<NativeExportReport | null>("scene_export", {
+      selection,
+      path: null,
+    });
+    if (report && report.missingAssets.length > 0) {
+      console.warn("[scene-builder] Export skipped missing assets:", report.missingAssets);
+    }
+  } finally {
+    unlisten();
+  }
+}
+
 // ---------------------------------------------------------------------------
 // Public API
 // ---------------------------------------------------------------------------
@@ -248,14 +298,22 @@ function computeExportSummary(): ExportSummary {
  * Export the current scene as a ZIP file.
  *
  * Shows a selection dialog, then gathers the chosen sections
- * into a ZIP archive and triggers a browser download.
+ * into a ZIP archive and triggers a browser download. In the desktop app
+ * the backend writes the archive; `onProgress` follows it entry by entry.
  */
-export async function exportScene(): Promise<void> {
+export async function exportScene(
+  onProgress?: (progress: ExportProgress) => void,
+): Promise<void> {
   // Phase 1 — Compute summary and show selection dialog
   const summary = computeExportSummary();
   const selection = await showExportDialog(summary);
   if (!selection) return; // User cancelled
 
+  if (isTauri()) {
+    await exportSceneNative(selection, onProgress);
+    return;
+  }
+
   // Phase 2 — Gather selected sections
   const zipData: Record<string, Uint8Array> = {};
 
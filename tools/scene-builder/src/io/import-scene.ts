@@ -11,6 +11,11 @@
  *   3. showImportDialog()  — user selects sections
  *   4. applyImport()       — selective reset + populate
  *
+ * In the desktop app, phase 2 runs in the Rust backend
+ * (`src-tauri/src/scene/import.rs`): it picks the file in a native dialog,
+ * refuses unsafe or oversized archives, validates each JSON file, and
+ * returns only the ticked sections, which then go through the same apply.
+ *
  * Expected ZIP structure:
  *   scene.json            — scene layout
  *   entities.json         — entity definitions
@@ -28,6 +33,8 @@ import { setWiringState, resetWiringState } from "../state/wiring-state.js";
 import { setBindingState, resetBindingState } from "../state/binding-store.js";
 import { clearHistory } from "../state/undo.js";
 import { forcePersistAll } from "../state/persistence.js";
+import { fromBase64 } from "../state/persistence-fs.js";
+import { isTauri } from "../utils/platform-fetch.js";
 import { autoWireConnectedSources } from "../state/auto-wire.js";
 import { setShaderState, resetShaderState } from "../shader-editor/shader-state.js";
 import { setSketchState, resetSketchState } from "../sketch-editor/sketch-state.js";
@@ -432,6 +439,65 @@ async function applyImport(contents: ZipContents, selection: ImportSelection): P
   autoWireConnectedSources();
 }
 
+// ---------------------------------------------------------------------------
+// Native import (desktop app)
+// ---------------------------------------------------------------------------
+
+/** Mirrors `Inspection` in `src-tauri/src/scene/mod.rs`. */
+interface NativeInspection {
+  path: string;
+  summary: ZipSummary;
+  /** Optional files left out because they failed validation. */
+  warnings: string[];
+}
+
+/** Mirrors `Sections` in `src-tauri/src/scene/import.rs`. */
+interface NativeSections {
+  scene: SceneExportJson | null;
+  entities: EntityExportJson | null;
+  choreographies: ChoreographyExportJson | null;
+  shaders: ShaderEditorState["shaders"] | null;
+  p5: SketchEditorState["sketches"] | null;
+  assets: { path: string; data: string }[];
+}
+
+async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke: tauriInvoke } = await import("@tauri-apps/api/core");
+  return tauriInvoke<T>(cmd, args);
+}
+
+/** Pick, check and read the archive in the backend, then apply as usual. */
+async function importSceneNative(): Promise<void> {
+  const inspection = await invoke<NativeInspection | null>("scene_import_inspect", { path: null });
+  if (!inspection) return;
+  for (const warning of inspection.warnings) {
+    console.warn(`[scene-builder] Import skipped ${warning}`);
+  }
+
+  const selection = await showImportDialog(inspection.summary);
+  if (!selection) return; // User cancelled
+
+  const sections = await invoke<NativeSections>("scene_import", {
+    path: inspection.path,
+    selection,
+  });
+  const assetEntries: Record<string, Uint8Array> = {};
+  for (const asset of sections.assets) {
+    assetEntries[asset.path] = new Uint8Array(fromBase64(asset.data));
+  }
+
+  // Unticked sections come back null; applyImport only reads ticked ones.
+  await applyImport({
+    sceneJson: sections.scene as SceneExportJson,
+    entitiesJson: sections.entities as EntityExportJson,
+    choreoJson: sections.choreographies,
+    shaderDefs: sections.shaders,
+    p5Sketches: sections.p5,
+    assetFiles: extractAssets(assetEntries),
+    summary: inspection.summary,
+  }, selection);
+}
+
 // ---------------------------------------------------------------------------
 // Public API
 // ---------------------------------------------------------------------------
@@ -446,6 +512,11 @@ async function applyImport(contents: ZipContents, selection: ImportSelection): P
  *   4. Selective apply + auto-wire
  */
 export async function importScene(): Promise<void> {
+  if (isTauri()) {
+    await importSceneNative();
+    return;
+  }
+
   // Phase 1 — File picker
   const file = await pickZipFile();
   if (!file) return;
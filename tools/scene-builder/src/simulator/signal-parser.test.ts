@@ -6,6 +6,7 @@
  *   - WebSocket/SSE message parsing (known types, unknown types, meta, errors)
  *   - OpenAI SSE chunks (text_delta, reasoning, finish, error)
  *   - Anthropic SSE events (all event types)
+ *   - The fixture corpus shared with the Rust port (`src-tauri/src/signal_parser.rs`)
  */
 
 import { describe, it, expect, beforeEach } from "vitest";
@@ -14,9 +15,11 @@ import {
   parseMessage,
   parseOpenAIChunk,
   parseAnthropicEvent,
+  parseOpenClawEvent,
   resetIdCounter,
   KNOWN_TYPES,
 } from "./signal-parser.js";
+import corpus from "./fixtures/signal-parser.json";
 
 // ---------------------------------------------------------------------------
 // Setup
@@ -328,6 +331,21 @@ describe("parseOpenAIChunk", () => {
     expect(result.done).toBe(false);
   });
 
+  it("emits token_usage from the final usage chunk", () => {
+    const chunk = {
+      model: "qwen3-8b",
+      choices: [],
+      usage: { prompt_tokens: 12, completion_tokens: 34, total_tokens: 46 },
+    };
+    const result = parseOpenAIChunk(chunk, model, correlationId, 3);
+    expect(result.signals).toHaveLength(1);
+    expect(result.signals[0]!.type).toBe("token_usage");
+    expect(result.signals[0]!.payload["promptTokens"]).toBe(12);
+    expect(result.signals[0]!.payload["completionTokens"]).toBe(34);
+    expect(result.signals[0]!.payload["model"]).toBe("qwen3-8b");
+    expect(result.tokenCount).toBe(3);
+  });
+
   it("handles missing choices", () => {
     const chunk = { id: "chatcmpl-1" };
     const result = parseOpenAIChunk(chunk, model, correlationId, 0);
@@ -552,3 +570,70 @@ describe("cross-format consistency", () => {
     expect(KNOWN_TYPES.size).toBe(14);
   });
 });
+
+// ---------------------------------------------------------------------------
+// Shared fixture corpus (also run by the Rust port)
+// ---------------------------------------------------------------------------
+
+/** Placeholder for IDs and timestamps the parsers generate. */
+const GENERATED = "<generated>";
+
+/** Drop `undefined` fields the way both implementations serialise them. */
+function wire(value: unknown): unknown {
+  return value === undefined ? undefined : JSON.parse(JSON.stringify(value));
+}
+
+/** `actual` matches `expected`, where {@link GENERATED} stands for any truthy value. */
+function matches(expected: unknown, actual: unknown): boolean {
+  if (expected === GENERATED) return Boolean(actual);
+  if (Array.isArray(expected)) {
+    return Array.isArray(actual)
+      && expected.length === actual.length
+      && expected.every((e, i) => matches(e, actual[i]));
+  }
+  if (expected && typeof expected === "object") {
+    if (!actual || typeof actual !== "object" || Array.isArray(actual)) return false;
+    const e = expected as Record<string, unknown>;
+    const a = actual as Record<string, unknown>;
+    return Object.keys(e).length === Object.keys(a).length
+      && Object.keys(e).every((k) => k in a && matches(e[k], a[k]));
+  }
+  return expected === actual;
+}
+
+function expectMatch(expected: unknown, actual: unknown): void {
+  const wired = wire(actual);
+  if (!matches(expected, wired)) expect(wired).toEqual(expected);
+}
+
+describe("shared fixture corpus", () => {
+  it("lists the same known types", () => {
+    expect([...KNOWN_TYPES]).toEqual(corpus.knownTypes);
+  });
+
+  it.each(corpus.normalizeHttpPost.map((c) => [c.name, c] as const))("normalizeHttpPost: %s", (_, c) => {
+    expectMatch(c.expected, normalizeHttpPost(c.input as Record<string, unknown>));
+  });
+
+  it.each(corpus.parseMessage.map((c) => [c.name, c] as const))("parseMessage: %s", (_, c) => {
+    expectMatch(c.expected, parseMessage(c.input));
+  });
+
+  it.each(corpus.openaiChunk.map((c) => [c.name, c] as const))("parseOpenAIChunk: %s", (_, c) => {
+    expectMatch(
+      c.expected,
+      parseOpenAIChunk(c.chunk as Record<string, unknown>, c.model, c.correlationId, c.tokenCount),
+    );
+  });
+
+  it.each(corpus.anthropicEvent.map((c) => [c.name, c] as const))("parseAnthropicEvent: %s", (_, c) => {
+    expectMatch(
+      c.expected,
+      parseAnthropicEvent(c.eventType, c.data as Record<string, unknown>, c.model, c.correlationId),
+    );
+  });
+
+  it.each(corpus.openclawEvent.map((c) => [c.name, c] as const))("parseOpenClawEvent: %s", (_, c) => {
+    expectMatch(c.expected, parseOpenClawEvent(c.event as Record<string, unknown>));
+  });
+});
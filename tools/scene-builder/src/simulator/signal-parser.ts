@@ -4,9 +4,13 @@
  * These are the parsing rules used by the scene-builder to handle signals
  * from all input sources: HTTP POST, WebSocket, SSE, OpenAI, Anthropic.
  *
- * Extracted as pure functions for unit testing and reuse.
+ * Extracted as pure functions for unit testing and reuse. The desktop
+ * backend's port (`src-tauri/src/signal_parser.rs`) is held to the same
+ * fixture corpus, `fixtures/signal-parser.json`.
  */
 
+import { KNOWN_SIGNAL_TYPES } from "@sajou/schema";
+
 // ---------------------------------------------------------------------------
 // Types
 // ---------------------------------------------------------------------------
@@ -47,22 +51,8 @@ export type OpenClawEventResult = ParsedSignal | null;
 // Known signal types
 // ---------------------------------------------------------------------------
 
-export const KNOWN_TYPES = new Set<string>([
-  "task_dispatch",
-  "tool_call",
-  "tool_result",
-  "token_usage",
-  "agent_state_change",
-  "error",
-  "completion",
-  "text_delta",
-  "thinking",
-  "midi.note_on",
-  "midi.note_off",
-  "midi.control_change",
-  "midi.pitch_bend",
-  "midi.program_change",
-]);
+/** Types passed through as typed signals, shared with the tap via `@sajou/schema`. */
+export const KNOWN_TYPES = new Set<string>(KNOWN_SIGNAL_TYPES);
 
 // ---------------------------------------------------------------------------
 // HTTP POST normalisation
@@ -173,6 +163,7 @@ export function parseMessage(raw: string): ParseResult {
  * - `choices[0].delta.content` → `text_delta` signal
  * - `choices[0].delta.reasoning_content` → `thinking` signal (GLM/DeepSeek)
  * - `choices[0].finish_reason === "stop"` → `completion` signal
+ * - `usage` (sent with `stream_options.include_usage`) → `token_usage` signal
  * - `error` field → `error` signal
  */
 export function parseOpenAIChunk(
@@ -204,6 +195,23 @@ export function parseOpenAIChunk(
     return { signals, done: true, tokenCount: count };
   }
 
+  const usage = chunk["usage"] as Record<string, unknown> | null | undefined;
+  if (usage) {
+    signals.push({
+      id: generateId(),
+      type: "token_usage",
+      timestamp: Date.now(),
+      source: model,
+      correlationId,
+      payload: {
+        agentId: String(chunk["model"] ?? model),
+        promptTokens: Number(usage["prompt_tokens"] ?? 0),
+        completionTokens: Number(usage["completion_tokens"] ?? 0),
+        model: String(chunk["model"] ?? model),
+      },
+    });
+  }
+
   const choices = chunk["choices"] as Array<Record<string, unknown>> | undefined;
   if (!choices || choices.length === 0) {
     return { signals, done, tokenCount: count };
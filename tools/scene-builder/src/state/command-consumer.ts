@@ -9,10 +9,16 @@
  *
  * This is the reverse channel of state-sync.ts: state-sync pushes state OUT,
  * command-consumer pulls state changes IN.
+ *
+ * In the desktop app the Rust backend queues every mutation and emits
+ * `commands://queued`. The consumer pulls state with `state_pull`, then acks
+ * the commands it covered; unacked commands (e.g. the window reloaded
+ * mid-apply) are redelivered by `commands_pending` on the next connect.
  */
 
 import { notifyServerContact, notifyServerLost } from "./server-connection.js";
-import { serverUrl } from "./server-config.js";
+import { serverUrl, pullNativeState } from "./server-config.js";
+import { isTauri } from "../utils/platform-fetch.js";
 import { setSceneState } from "./scene-state.js";
 import { setChoreographyState } from "./choreography-state.js";
 import { setWiringState } from "./wiring-state.js";
@@ -34,6 +40,26 @@ let pollTimer: ReturnType<typeof setInterval> | null = null;
 /** Active EventSource connection. */
 let eventSource: EventSource | null = null;
 
+/** Tauri event name for queued backend commands. */
+const NATIVE_COMMAND_EVENT = "commands://queued";
+
+/** Unlisten function for the native command event (desktop app). */
+let nativeUnlisten: (() => void) | null = null;
+
+/** A backend mutation awaiting acknowledgement (desktop app). */
+interface QueuedCommand {
+  id: number;
+  version: number;
+  createdAt: number;
+  deliveries: number;
+}
+
+/** Whether a native drain is running. */
+let drainInFlight = false;
+
+/** Whether commands arrived while a drain was running. */
+let drainAgain = false;
+
 /** Whether the SSE stream is currently connected. */
 let sseConnected = false;
 
@@ -85,28 +111,34 @@ function applyServerState(data: Record<string, unknown>): void {
   }
 }
 
+/** Fetch the full state from the HTTP server. */
+async function fetchServerState(): Promise<Record<string, unknown> | null> {
+  const resp = await fetch(serverUrl("/api/state/full"), {
+    signal: AbortSignal.timeout(5000),
+  });
+  if (!resp.ok) return null;
+
+  const body = (await resp.json()) as {
+    ok: boolean;
+    lastPushAt: number | null;
+    data: Record<string, unknown>;
+  };
+
+  return body.ok && body.data ? body.data : null;
+}
+
 /** Fetch the full server state and apply it locally. */
 async function fetchAndApplyState(): Promise<void> {
   if (fetchInFlight) return;
   fetchInFlight = true;
 
   try {
-    const resp = await fetch(serverUrl("/api/state/full"), {
-      signal: AbortSignal.timeout(5000),
-    });
-    if (!resp.ok) return;
-
-    const body = (await resp.json()) as {
-      ok: boolean;
-      lastPushAt: number | null;
-      data: Record<string, unknown>;
-    };
-
-    if (!body.ok || !body.data) return;
+    const data = await fetchServerState();
+    if (!data) return;
 
     applyingServerState = true;
     try {
-      applyServerState(body.data);
+      applyServerState(data);
     } finally {
       applyingServerState = false;
     }
@@ -161,6 +193,60 @@ function connectSSE(): void {
   });
 }
 
+// ---------------------------------------------------------------------------
+// Native command queue (desktop app)
+// ---------------------------------------------------------------------------
+
+/**
+ * Apply every pending backend command, then acknowledge it.
+ * Commands are only acked once state at or past their version is applied.
+ */
+async function drainNativeCommands(): Promise<void> {
+  if (drainInFlight) {
+    drainAgain = true;
+    return;
+  }
+  drainInFlight = true;
+
+  try {
+    do {
+      drainAgain = false;
+      const { invoke } = await import("@tauri-apps/api/core");
+      const pending = await invoke<QueuedCommand[]>("commands_pending");
+      if (pending.length === 0) break;
+
+      const snapshot = await pullNativeState();
+      applyingServerState = true;
+      try {
+        applyServerState(snapshot.data);
+      } finally {
+        applyingServerState = false;
+      }
+      lastKnownVersion = snapshot.version;
+
+      const ids = pending.filter((c) => c.version <= snapshot.version).map((c) => c.id);
+      await invoke("commands_ack", { ids });
+      notifyServerContact();
+    } while (drainAgain);
+  } catch (e: unknown) {
+    console.warn("[command-consumer] Cannot apply native commands:", e);
+  } finally {
+    drainInFlight = false;
+  }
+}
+
+/** Listen for queued backend commands and pick up any left from a reload. */
+async function connectNative(): Promise<void> {
+  const { listen } = await import("@tauri-apps/api/event");
+  const unlisten = await listen<QueuedCommand>(NATIVE_COMMAND_EVENT, () => {
+    drainNativeCommands();
+  });
+  nativeUnlisten = unlisten;
+  notifyServerContact();
+  // Redelivery: commands queued before this page loaded are still pending
+  await drainNativeCommands();
+}
+
 // ---------------------------------------------------------------------------
 // Fallback poll loop
 // ---------------------------------------------------------------------------
@@ -195,7 +281,13 @@ function stopPolling(): void {
  * Call this AFTER all stores are initialized (alongside initStateSync).
  */
 export function initCommandConsumer(): void {
-  if (eventSource !== null || pollTimer !== null) return; // Already running
+  if (eventSource !== null || pollTimer !== null || nativeUnlisten !== null) return; // Already running
+  if (isTauri()) {
+    connectNative().catch((e: unknown) => {
+      console.warn("[command-consumer] Cannot listen for native commands:", e);
+    });
+    return;
+  }
   connectSSE();
 }
 
@@ -208,5 +300,9 @@ export function stopCommandConsumer(): void {
     eventSource = null;
     sseConnected = false;
   }
+  if (nativeUnlisten !== null) {
+    nativeUnlisten();
+    nativeUnlisten = null;
+  }
   stopPolling();
 }
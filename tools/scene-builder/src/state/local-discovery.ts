@@ -6,7 +6,8 @@
  * (sajou.app static build).
  *
  * Services probed:
- * - Claude Code: relative SSE endpoint `/__signals__/stream` (dev mode only)
+ * - Claude Code: relative SSE endpoint `/__signals__/stream` (dev mode) or the
+ *   embedded Rust signal server (Tauri desktop)
  * - OpenClaw: WebSocket probe on port 18789
  * - LM Studio: HTTP probe on port 1234 (`/v1/models`)
  * - Ollama: HTTP probe on port 11434 (`/v1/models`)
@@ -14,6 +15,10 @@
  *
  * For OpenClaw, also attempts to fetch the gateway auth token from the
  * dev server via `GET /api/openclaw/token` (silently fails in production).
+ *
+ * In the Tauri desktop app, OpenClaw / LM Studio / Ollama (and anything else
+ * listed in the user's `local-services.json` registry) are probed natively
+ * by the `discover_local_services` command instead.
  */
 
 import type { TransportProtocol } from "../types.js";
@@ -120,6 +125,33 @@ async function relativeProbe(
   }
 }
 
+/**
+ * Ask the Tauri backend for its embedded signal server.
+ * Resolves the server's base URL, or `null` if it failed to start.
+ */
+async function nativeSignalServerProbe(): Promise<string | null> {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    const info = await invoke<{ port: number }>("signal_server_info");
+    return `http://127.0.0.1:${info.port}`;
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Probe the services of the native registry (Tauri desktop).
+ * Resolves an empty list if the command is unavailable.
+ */
+async function nativeServicesProbe(): Promise<DiscoveredService[]> {
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    return await invoke<DiscoveredService[]>("discover_local_services");
+  } catch {
+    return [];
+  }
+}
+
 // ---------------------------------------------------------------------------
 // Public API
 // ---------------------------------------------------------------------------
@@ -127,21 +159,39 @@ async function relativeProbe(
 /** Probe all known local services directly from the browser. */
 export async function discoverLocalServices(): Promise<DiscoveredService[]> {
   // Claude Code probe only makes sense in dev mode (Vite dev server provides
-  // the /__signals__/stream SSE endpoint). In Tauri production builds or
-  // static deployments, the endpoint doesn't exist — and Tauri's SPA fallback
-  // would return index.html as 200, causing a false positive.
-  const claudeCodeProbe: Promise<DiscoveredService | null> = import.meta.env?.DEV
-    ? relativeProbe("/__signals__/stream", PROBE_TIMEOUT).then(
-        (up): DiscoveredService => ({
-          id: "local:claude-code",
-          label: "Claude Code",
-          protocol: "sse" as TransportProtocol,
-          url: "/__signals__/stream",
-          available: up,
-          models: [],
-        }),
-      )
-    : Promise.resolve(null);
+  // the /__signals__/stream SSE endpoint) or in the Tauri desktop app (the
+  // embedded Rust signal server). In static deployments the endpoint doesn't
+  // exist — and Tauri's SPA fallback would return index.html as 200, causing
+  // a false positive, hence the native probe instead of relativeProbe.
+  let claudeCodeProbe: Promise<DiscoveredService | null> = Promise.resolve(null);
+  if (import.meta.env?.DEV) {
+    claudeCodeProbe = relativeProbe("/__signals__/stream", PROBE_TIMEOUT).then(
+      (up): DiscoveredService => ({
+        id: "local:claude-code",
+        label: "Claude Code",
+        protocol: "sse" as TransportProtocol,
+        url: "/__signals__/stream",
+        available: up,
+        models: [],
+      }),
+    );
+  } else if ("__TAURI_INTERNALS__" in window) {
+    claudeCodeProbe = nativeSignalServerProbe().then(
+      (url): DiscoveredService => ({
+        id: "local:claude-code",
+        label: "Claude Code",
+        protocol: "sse" as TransportProtocol,
+        url: url ?? "",
+        available: url !== null,
+        models: [],
+      }),
+    );
+  }
+
+  if ("__TAURI_INTERNALS__" in window) {
+    const [claudeCode, native] = await Promise.all([claudeCodeProbe, nativeServicesProbe()]);
+    return claudeCode ? [claudeCode, ...native] : native;
+  }
 
   const results = await Promise.allSettled([
     claudeCodeProbe,
@@ -194,10 +244,19 @@ export async function discoverLocalServices(): Promise<DiscoveredService[]> {
   return services;
 }
 
+/** Structured error from the Rust `read_openclaw_token` command. */
+interface OpenClawCredentialError {
+  kind: "noHome" | "unknownProfile" | "missingFile" | "invalidJson" | "missingKey";
+  path?: string;
+  key?: string;
+  message?: string;
+}
+
 /**
  * Fetch the OpenClaw gateway auth token.
  *
- * - **Tauri desktop**: reads ~/.openclaw/openclaw.json via Rust command.
+ * - **Tauri desktop**: reads the OpenClaw config via Rust command
+ *   (`OPENCLAW_CONFIG_PATH` / `OPENCLAW_STATE_DIR` honoured).
  * - **Vite dev server**: fetches from GET /api/openclaw/token.
  * - **Production browser**: silently returns null.
  */
@@ -207,7 +266,12 @@ export async function fetchOpenClawToken(): Promise<string | null> {
     try {
       const { invoke } = await import("@tauri-apps/api/core");
       return await invoke<string>("read_openclaw_token");
-    } catch {
+    } catch (e: unknown) {
+      const err = e as OpenClawCredentialError;
+      // No config at all is the normal case for users without OpenClaw
+      if (err.kind !== "missingFile") {
+        console.warn(`[local-discovery] OpenClaw token unavailable (${err.kind}):`, err);
+      }
       return null;
     }
   }
@@ -282,6 +346,37 @@ export function initMIDIHotPlug(): () => void {
   });
 }
 
+/**
+ * Re-read the OpenClaw token when its config file changes (Tauri desktop).
+ *
+ * Only touches a token that was auto-filled (or is still empty) — a key the
+ * user typed by hand is never overwritten. Returns an unsubscribe function.
+ */
+export function initOpenClawConfigWatch(): () => void {
+  if (!("__TAURI_INTERNALS__" in window)) return () => {};
+
+  let unlisten: (() => void) | null = null;
+  let stopped = false;
+  void import("@tauri-apps/api/event").then(async ({ listen }) => {
+    const fn = await listen<{ profile: string }>("openclaw://config-changed", async (event) => {
+      if (event.payload.profile !== "default") return;
+      const source = getSource("local:openclaw");
+      if (!source || (source.apiKey && !source.tokenAutoFilled)) return;
+      const token = await fetchOpenClawToken();
+      if (token && token !== source.apiKey) {
+        updateSource("local:openclaw", { apiKey: token, tokenAutoFilled: true });
+      }
+    });
+    if (stopped) fn();
+    else unlisten = fn;
+  });
+
+  return () => {
+    stopped = true;
+    unlisten?.();
+  };
+}
+
 // ---------------------------------------------------------------------------
 // Periodic rescan
 // ---------------------------------------------------------------------------
@@ -0,0 +1,96 @@
+/**
+ * Project-folder backend for scene-builder persistence (desktop app).
+ *
+ * Same API as `persistence-db.ts`, backed by the `project_*` Tauri commands:
+ * one JSON file per store in the open project folder, asset bytes under
+ * `assets/`. Each store holds a single record (key "current"); assets are
+ * keyed by path.
+ */
+
+import type { StoreName } from "./persistence-db.js";
+
+async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke: tauriInvoke } = await import("@tauri-apps/api/core");
+  return tauriInvoke<T>(cmd, args);
+}
+
+// ---------------------------------------------------------------------------
+// Binary transfer (asset bytes travel as base64)
+// ---------------------------------------------------------------------------
+
+export function toBase64(buffer: ArrayBuffer): string {
+  const bytes = new Uint8Array(buffer);
+  let binary = "";
+  // Chunked to stay under the argument limit of String.fromCharCode.
+  for (let i = 0; i < bytes.length; i += 0x8000) {
+    binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000));
+  }
+  return btoa(binary);
+}
+
+export function fromBase64(data: string): ArrayBuffer {
+  const binary = atob(data);
+  const bytes = new Uint8Array(binary.length);
+  for (let i = 0; i < binary.length; i++) {
+    bytes[i] = binary.charCodeAt(i);
+  }
+  return bytes.buffer;
+}
+
+/** Asset record as the commands exchange it: metadata plus base64 bytes. */
+type WireAsset = Record<string, unknown> & { data: string };
+
+// ---------------------------------------------------------------------------
+// CRUD helpers
+// ---------------------------------------------------------------------------
+
+/** Write a store's record, or an asset (a record with a `buffer`). */
+export async function fsPut(store: StoreName, _key: string, value: unknown): Promise<void> {
+  if (store === "assets") {
+    const { buffer, ...asset } = value as Record<string, unknown> & { buffer: ArrayBuffer };
+    await invoke("project_write_asset", { asset, data: toBase64(buffer) });
+    return;
+  }
+  await invoke("project_write", { section: store, record: value });
+}
+
+/** Read a store's record. Returns undefined if never saved. */
+export async function fsGet<T>(store: StoreName, key: string): Promise<T | undefined> {
+  if (store === "assets") {
+    const assets = await fsGetAll<Record<string, unknown>>("assets");
+    return assets.find((a) => a["path"] === key) as T | undefined;
+  }
+  const record = await invoke<T | null>("project_read", { section: store });
+  return record ?? undefined;
+}
+
+/** Read all values of a store. */
+export async function fsGetAll<T>(store: StoreName): Promise<T[]> {
+  if (store === "assets") {
+    const assets = await invoke<WireAsset[]>("project_read_assets");
+    return assets.map(({ data, ...meta }) => ({ ...meta, buffer: fromBase64(data) }) as T);
+  }
+  const record = await fsGet<T>(store, "current");
+  return record === undefined ? [] : [record];
+}
+
+/** Read all keys of a store. */
+export async function fsGetAllKeys(store: StoreName): Promise<string[]> {
+  if (store === "assets") {
+    return invoke<string[]>("project_asset_keys");
+  }
+  return (await fsGet(store, "current")) === undefined ? [] : ["current"];
+}
+
+/**
+ * Journal a store's new data for crash recovery, ahead of its debounced
+ * save. Only for the stores the backend state store does not mirror.
+ */
+export async function fsJournal(store: StoreName, data: unknown): Promise<void> {
+  await invoke("project_journal", { section: store, data });
+}
+
+/** Remove every store and asset from the project folder. */
+export async function fsClearAll(): Promise<void> {
+  await invoke("project_clear");
+}
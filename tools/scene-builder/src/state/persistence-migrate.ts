@@ -0,0 +1,118 @@
+/**
+ * One-time migration of browser-era IndexedDB data into a project folder.
+ *
+ * Scenes built before the desktop app saved to disk live in the webview's
+ * `sajou-scene-builder` IndexedDB (any version 1–3). On the first launch
+ * under Tauri with an empty project folder, every store is dumped (asset
+ * buffers as base64) and handed to `project_import_indexeddb`, which writes
+ * the folder and reports what it could not convert. The database itself is
+ * left untouched.
+ */
+
+import { toBase64 } from "./persistence-fs.js";
+
+const DB_NAME = "sajou-scene-builder";
+
+/** Set once the import has run (or there was nothing to import). */
+const LS_MIGRATED = "sajou:indexeddb-migrated";
+
+interface DumpEntry {
+  key: IDBValidKey;
+  value: unknown;
+}
+
+/** Mirrors `MigrationReport` in `src-tauri/src/project/migrate.rs`. */
+export interface MigrationReport {
+  dbVersion: number;
+  sections: string[];
+  assets: number;
+  issues: { store: string; key: string | null; message: string }[];
+}
+
+// ---------------------------------------------------------------------------
+// Dump
+// ---------------------------------------------------------------------------
+
+/** Open the existing database without upgrading it; null if there is none. */
+async function openExisting(): Promise<IDBDatabase | null> {
+  const databases = await indexedDB.databases();
+  if (!databases.some((d) => d.name === DB_NAME)) return null;
+
+  return new Promise<IDBDatabase>((resolve, reject) => {
+    const req = indexedDB.open(DB_NAME);
+    req.onsuccess = () => resolve(req.result);
+    req.onerror = () => reject(req.error);
+  });
+}
+
+function dumpStore(db: IDBDatabase, store: string): Promise<DumpEntry[]> {
+  return new Promise<DumpEntry[]>((resolve, reject) => {
+    const tx = db.transaction(store, "readonly");
+    const keysReq = tx.objectStore(store).getAllKeys();
+    const valuesReq = tx.objectStore(store).getAll();
+    tx.oncomplete = () => {
+      resolve(keysReq.result.map((key, i) => ({ key, value: valuesReq.result[i] })));
+    };
+    tx.onerror = () => reject(tx.error);
+  });
+}
+
+/** Asset buffers cannot cross the IPC boundary as-is. */
+function encodeAsset(value: unknown): unknown {
+  if (typeof value !== "object" || value === null) return value;
+  const { buffer, ...meta } = value as Record<string, unknown>;
+  return buffer instanceof ArrayBuffer ? { ...meta, buffer: toBase64(buffer) } : meta;
+}
+
+// ---------------------------------------------------------------------------
+// Migration
+// ---------------------------------------------------------------------------
+
+/**
+ * Import the IndexedDB into the open project folder, once.
+ * Returns the report, or null when there was nothing to do.
+ */
+export async function migrateIndexedDb(): Promise<MigrationReport | null> {
+  if (localStorage.getItem(LS_MIGRATED)) return null;
+
+  const { invoke } = await import("@tauri-apps/api/core");
+  const existing = await invoke<unknown>("project_read", { section: "scene" });
+  if (existing) {
+    // The folder already holds a project; never overwrite it.
+    localStorage.setItem(LS_MIGRATED, "skipped");
+    return null;
+  }
+
+  const db = await openExisting();
+  if (!db) {
+    localStorage.setItem(LS_MIGRATED, "none");
+    return null;
+  }
+
+  try {
+    const stores: Record<string, DumpEntry[]> = {};
+    for (const store of Array.from(db.objectStoreNames)) {
+      const entries = await dumpStore(db, store);
+      stores[store] = store === "assets"
+        ? entries.map(({ key, value }) => ({ key, value: encodeAsset(value) }))
+        : entries;
+    }
+
+    const report = await invoke<MigrationReport>("project_import_indexeddb", {
+      dump: { dbVersion: db.version, stores },
+    });
+    localStorage.setItem(LS_MIGRATED, new Date().toISOString());
+
+    console.info(
+      `[persistence] Imported IndexedDB v${report.dbVersion}: ` +
+        `${report.sections.length} stores, ${report.assets} assets`,
+    );
+    for (const issue of report.issues) {
+      const where = issue.key ? `${issue.store}/${issue.key}` : issue.store;
+      console.warn(`[persistence] Migration: ${where}: ${issue.message}`);
+    }
+    return report;
+  } finally {
+    db.close();
+  }
+}
@@ -1,9 +1,10 @@
 /**
  * Persistence orchestrator for scene-builder.
  *
- * Auto-saves all persistent state to IndexedDB (debounced) and restores
- * it on startup. Remote signal sources and editor preferences are saved
- * to localStorage for fast access.
+ * Auto-saves all persistent state (debounced) and restores it on startup:
+ * to IndexedDB in the browser, to the open project folder on disk in the
+ * desktop app (`persistence-fs.ts`). Remote signal sources and editor
+ * preferences are saved to localStorage for fast access.
  *
  * Non-persisted: undo stack, compositor, local sources (re-discovered),
  * connection status, active selections.
@@ -11,6 +12,9 @@
 
 import { dbPut, dbGet, dbGetAll, dbGetAllKeys, dbClearAll } from "./persistence-db.js";
 import type { StoreName } from "./persistence-db.js";
+import { fsPut, fsGet, fsGetAll, fsGetAllKeys, fsClearAll, fsJournal } from "./persistence-fs.js";
+import { migrateIndexedDb } from "./persistence-migrate.js";
+import { isTauri } from "../utils/platform-fetch.js";
 
 // State stores
 import { getSceneState, setSceneState, resetSceneState, subscribeScene } from "./scene-state.js";
@@ -76,6 +80,45 @@ import type { BindingState } from "./binding-store.js";
 import type { ShaderEditorState } from "../shader-editor/shader-types.js";
 import type { SketchEditorState } from "../sketch-editor/sketch-types.js";
 
+// ---------------------------------------------------------------------------
+// Storage backend
+// ---------------------------------------------------------------------------
+
+/** Key-value stores as `persistence-db.ts` exposes them. */
+interface StorageBackend {
+  put(store: StoreName, key: string, value: unknown): Promise<void>;
+  get<T>(store: StoreName, key: string): Promise<T | undefined>;
+  getAll<T>(store: StoreName): Promise<T[]>;
+  getAllKeys(store: StoreName): Promise<unknown[]>;
+  clearAll(): Promise<void>;
+}
+
+const idbBackend: StorageBackend = {
+  put: dbPut,
+  get: dbGet,
+  getAll: dbGetAll,
+  getAllKeys: dbGetAllKeys,
+  clearAll: dbClearAll,
+};
+
+const fsBackend: StorageBackend = {
+  put: fsPut,
+  get: fsGet,
+  getAll: fsGetAll,
+  getAllKeys: fsGetAllKeys,
+  clearAll: fsClearAll,
+};
+
+/** The project folder in the desktop app, IndexedDB in the browser. */
+function storage(): StorageBackend {
+  return isTauri() ? fsBackend : idbBackend;
+}
+
+/** Where state is persisted, for log messages. */
+function storageName(): string {
+  return isTauri() ? "project folder" : "IndexedDB";
+}
+
 // ---------------------------------------------------------------------------
 // Versioned envelope
 // ---------------------------------------------------------------------------
@@ -264,51 +307,108 @@ function restoreEditorPrefs(): void {
 
 const debounceTimers = new Map<string, ReturnType<typeof setTimeout>>();
 
-/** Debounced save to IndexedDB. */
-function debouncedSave(store: StoreName, serialize: () => unknown): void {
-  const existing = debounceTimers.get(store);
+/** The save each pending timer will run, so it can be flushed early. */
+const pendingSaves = new Map<string, () => Promise<void>>();
+
+/** Pending journal writes, one per store. */
+const journalTimers = new Map<StoreName, ReturnType<typeof setTimeout>>();
+
+/** Saves that have started but not finished. */
+const inFlightSaves = new Set<Promise<void>>();
+
+/** Set while stores are reset or reloaded — their changes are not new edits. */
+let autoSaveSuspended = false;
+
+/** Run `save` after `delay`, replacing any pending save under `key`. */
+function scheduleSave(key: string, delay: number, save: () => Promise<void>): void {
+  const existing = debounceTimers.get(key);
   if (existing) clearTimeout(existing);
 
+  pendingSaves.set(key, save);
   debounceTimers.set(
+    key,
+    setTimeout(() => {
+      debounceTimers.delete(key);
+      pendingSaves.delete(key);
+      const running = save().finally(() => inFlightSaves.delete(running));
+      inFlightSaves.add(running);
+    }, delay),
+  );
+}
+
+/** Drop pending saves (and journal writes) without running them. */
+function cancelPendingSaves(): void {
+  for (const timer of debounceTimers.values()) clearTimeout(timer);
+  debounceTimers.clear();
+  pendingSaves.clear();
+  for (const timer of journalTimers.values()) clearTimeout(timer);
+  journalTimers.clear();
+}
+
+/**
+ * Stores the backend state store does not mirror, so its crash journal
+ * misses them: the desktop app journals them from here instead.
+ */
+const JOURNALED_STORES: ReadonlySet<StoreName> = new Set<StoreName>(["entities", "timeline"]);
+
+/** Journal `store` shortly, well ahead of its debounced save. */
+function journalSoon(store: StoreName, serialize: () => unknown): void {
+  const existing = journalTimers.get(store);
+  if (existing) clearTimeout(existing);
+  journalTimers.set(
     store,
     setTimeout(() => {
-      debounceTimers.delete(store);
-      dbPut(store, "current", wrap(serialize())).catch((err: unknown) => {
-        console.error(`[persistence] Failed to save ${store}:`, err);
+      journalTimers.delete(store);
+      fsJournal(store, serialize()).catch((err: unknown) => {
+        console.error(`[persistence] Failed to journal ${store}:`, err);
       });
-    }, 500),
+    }, 100),
+  );
+}
+
+/** Debounced save to the storage backend. */
+function debouncedSave(store: StoreName, serialize: () => unknown): void {
+  if (autoSaveSuspended) return;
+  if (isTauri() && JOURNALED_STORES.has(store)) journalSoon(store, serialize);
+  scheduleSave(store, 500, () =>
+    storage().put(store, "current", wrap(serialize())).catch((err: unknown) => {
+      console.error(`[persistence] Failed to save ${store}:`, err);
+    }),
   );
 }
 
 /** Debounced save to localStorage. */
 function debouncedLocalSave(key: string, serialize: () => string): void {
-  const existing = debounceTimers.get(key);
-  if (existing) clearTimeout(existing);
+  scheduleSave(key, 300, async () => {
+    try {
+      localStorage.setItem(key, serialize());
+    } catch (err: unknown) {
+      console.error(`[persistence] Failed to save ${key}:`, err);
+    }
+  });
+}
 
-  debounceTimers.set(
-    key,
-    setTimeout(() => {
-      debounceTimers.delete(key);
-      try {
-        localStorage.setItem(key, serialize());
-      } catch (err: unknown) {
-        console.error(`[persistence] Failed to save ${key}:`, err);
-      }
-    }, 300),
-  );
+/**
+ * Run every pending save now and wait for them, plus any already running.
+ * Used before the desktop app saves, opens or closes a project.
+ */
+export async function flushSaves(): Promise<void> {
+  const saves = [...pendingSaves.values()];
+  cancelPendingSaves();
+  await Promise.all([...saves.map((save) => save()), ...inFlightSaves]);
 }
 
 // ---------------------------------------------------------------------------
 // Asset persistence (incremental)
 // ---------------------------------------------------------------------------
 
-/** Save assets incrementally — only new ones that are not yet in IndexedDB. */
+/** Save assets incrementally — only new ones that are not yet stored. */
 async function saveAssetsIncremental(): Promise<void> {
   const { assets } = getAssetStore();
   if (assets.length === 0) return;
 
   const existingKeys = new Set(
-    (await dbGetAllKeys("assets")).map((k) => String(k)),
+    (await storage().getAllKeys("assets")).map((k) => String(k)),
   );
 
   for (const asset of assets) {
@@ -327,7 +427,7 @@ async function saveAssetsIncremental(): Promise<void> {
         frameCount: asset.frameCount,
         detectedFps: asset.detectedFps,
       };
-      await dbPut("assets", asset.path, record);
+      await storage().put("assets", asset.path, record);
     } catch (err: unknown) {
       console.error(`[persistence] Failed to save asset ${asset.path}:`, err);
     }
@@ -340,7 +440,7 @@ async function saveAssetsIncremental(): Promise<void> {
 
 /** Start subscribing to all stores and auto-saving changes. */
 export function initAutoSave(): void {
-  // IndexedDB stores — debounced 500ms
+  // Backend stores — debounced 500ms
   subscribeScene(() =>
     debouncedSave("scene", () => getSceneState()),
   );
@@ -368,16 +468,11 @@ export function initAutoSave(): void {
 
   // Assets — incremental, debounced
   subscribeAssets(() => {
-    const existing = debounceTimers.get("assets");
-    if (existing) clearTimeout(existing);
-    debounceTimers.set(
-      "assets",
-      setTimeout(() => {
-        debounceTimers.delete("assets");
-        saveAssetsIncremental().catch((err: unknown) => {
-          console.error("[persistence] Failed to save assets:", err);
-        });
-      }, 500),
+    if (autoSaveSuspended) return;
+    scheduleSave("assets", 500, () =>
+      saveAssetsIncremental().catch((err: unknown) => {
+        console.error("[persistence] Failed to save assets:", err);
+      }),
     );
   });
 
@@ -413,20 +508,38 @@ function mimeFromFormat(format: AssetFormat): string {
 }
 
 /**
- * Attempt to restore state from IndexedDB + localStorage.
+ * Attempt to restore state from the storage backend + localStorage.
  * Returns true if scene data was found and restored.
  */
 export async function restoreState(): Promise<boolean> {
+  if (isTauri()) {
+    // Bring browser-era scenes over before the first read from disk.
+    await migrateIndexedDb().catch((err: unknown) => {
+      console.warn("[persistence] IndexedDB migration failed:", err);
+    });
+    // Offer back edits journaled before a crash (the backend asks the user).
+    await import("@tauri-apps/api/core")
+      .then(({ invoke }) => invoke<string[]>("project_recover"))
+      .then((recovered) => {
+        if (recovered.length > 0) {
+          console.info(`[persistence] Recovered unsaved ${recovered.join(", ")}`);
+        }
+      })
+      .catch((err: unknown) => {
+        console.warn("[persistence] Crash recovery failed:", err);
+      });
+  }
+
   try {
     // 1. Check if scene data exists
-    const sceneRecord = await dbGet<VersionedData<SceneState>>("scene", "current");
+    const sceneRecord = await storage().get<VersionedData<SceneState>>("scene", "current");
     if (!sceneRecord?.data) return false; // First launch — nothing to restore
 
     // 2. Restore scene state
     setSceneState(sceneRecord.data);
 
     // 3. Restore entity definitions
-    const entityRecord = await dbGet<VersionedData<Record<string, { id: string }>>>("entities", "current");
+    const entityRecord = await storage().get<VersionedData<Record<string, { id: string }>>>("entities", "current");
     if (entityRecord?.data) {
       for (const [id, entry] of Object.entries(entityRecord.data)) {
         setEntity(id, entry as Parameters<typeof setEntity>[1]);
@@ -434,7 +547,7 @@ export async function restoreState(): Promise<boolean> {
     }
 
     // 4. Restore choreography state
-    const choreoRecord = await dbGet<VersionedData<ChoreographyEditorState>>("choreographies", "current");
+    const choreoRecord = await storage().get<VersionedData<ChoreographyEditorState>>("choreographies", "current");
     if (choreoRecord?.data) {
       setChoreographyState({
         ...choreoRecord.data,
@@ -444,7 +557,7 @@ export async function restoreState(): Promise<boolean> {
     }
 
     // 5. Restore wiring state
-    const wiringRecord = await dbGet<VersionedData<WiringState>>("wires", "current");
+    const wiringRecord = await storage().get<VersionedData<WiringState>>("wires", "current");
     if (wiringRecord?.data) {
       setWiringState({
         ...wiringRecord.data,
@@ -453,13 +566,13 @@ export async function restoreState(): Promise<boolean> {
     }
 
     // 6. Restore binding state
-    const bindingRecord = await dbGet<VersionedData<BindingState>>("bindings", "current");
+    const bindingRecord = await storage().get<VersionedData<BindingState>>("bindings", "current");
     if (bindingRecord?.data) {
       setBindingState(bindingRecord.data);
     }
 
     // 7. Restore signal timeline
-    const timelineRecord = await dbGet<VersionedData<SignalTimelineState>>("timeline", "current");
+    const timelineRecord = await storage().get<VersionedData<SignalTimelineState>>("timeline", "current");
     if (timelineRecord?.data) {
       setSignalTimelineState({
         ...timelineRecord.data,
@@ -468,7 +581,7 @@ export async function restoreState(): Promise<boolean> {
     }
 
     // 8. Restore shader state
-    const shaderRecord = await dbGet<VersionedData<ShaderEditorState>>("shaders", "current");
+    const shaderRecord = await storage().get<VersionedData<ShaderEditorState>>("shaders", "current");
     if (shaderRecord?.data) {
       setShaderState({
         ...shaderRecord.data,
@@ -478,7 +591,7 @@ export async function restoreState(): Promise<boolean> {
     }
 
     // 9. Restore p5 state
-    const p5Record = await dbGet<VersionedData<SketchEditorState>>("p5", "current");
+    const p5Record = await storage().get<VersionedData<SketchEditorState>>("p5", "current");
     if (p5Record?.data) {
       setSketchState({
         ...p5Record.data,
@@ -488,7 +601,7 @@ export async function restoreState(): Promise<boolean> {
     }
 
     // 10. Restore assets (ArrayBuffer → File → objectUrl)
-    const assetRecords = await dbGetAll<StoredAsset>("assets");
+    const assetRecords = await storage().getAll<StoredAsset>("assets");
     if (assetRecords.length > 0) {
       const assetFiles: AssetFile[] = [];
       const categories = new Set<string>();
@@ -527,7 +640,7 @@ export async function restoreState(): Promise<boolean> {
     // 10. Clear undo stack — restored state has no undo history
     clearHistory();
 
-    console.info("[persistence] State restored from IndexedDB");
+    console.info(`[persistence] State restored from ${storageName()}`);
     return true;
   } catch (err: unknown) {
     console.error("[persistence] Failed to restore state:", err);
@@ -542,20 +655,17 @@ export async function restoreState(): Promise<boolean> {
 /** Immediately save all stores — bypasses debounce. */
 export async function forcePersistAll(): Promise<void> {
   // Cancel any pending debounced saves
-  for (const [key, timer] of debounceTimers) {
-    clearTimeout(timer);
-    debounceTimers.delete(key);
-  }
+  cancelPendingSaves();
 
   await Promise.all([
-    dbPut("scene", "current", wrap(getSceneState())),
-    dbPut("entities", "current", wrap(getEntityStore().entities)),
-    dbPut("choreographies", "current", wrap(getChoreographyState())),
-    dbPut("wires", "current", wrap(getWiringState())),
-    dbPut("bindings", "current", wrap(getBindingState())),
-    dbPut("timeline", "current", wrap(getSignalTimelineState())),
-    dbPut("shaders", "current", wrap(getShaderState())),
-    dbPut("p5", "current", wrap(getSketchState())),
+    storage().put("scene", "current", wrap(getSceneState())),
+    storage().put("entities", "current", wrap(getEntityStore().entities)),
+    storage().put("choreographies", "current", wrap(getChoreographyState())),
+    storage().put("wires", "current", wrap(getWiringState())),
+    storage().put("bindings", "current", wrap(getBindingState())),
+    storage().put("timeline", "current", wrap(getSignalTimelineState())),
+    storage().put("shaders", "current", wrap(getShaderState())),
+    storage().put("p5", "current", wrap(getSketchState())),
   ]);
 
   // Assets: save all (force, not incremental)
@@ -573,7 +683,7 @@ export async function forcePersistAll(): Promise<void> {
       frameCount: asset.frameCount,
       detectedFps: asset.detectedFps,
     };
-    await dbPut("assets", asset.path, record);
+    await storage().put("assets", asset.path, record);
   }
 
   // localStorage
@@ -591,23 +701,23 @@ export async function forcePersistAll(): Promise<void> {
 
 /** Clear all persisted data and reset stores to defaults. */
 export async function newScene(): Promise<void> {
-  // 1. Clear IndexedDB
-  await dbClearAll();
+  // 1. Clear the storage backend
+  await storage().clearAll();
+
+  await resetScene();
+}
 
+/**
+ * Reset stores to defaults after the storage backend was emptied — by
+ * `newScene`, or by the desktop app's `project_new`.
+ */
+export async function resetScene(): Promise<void> {
   // 2. Clear localStorage persistence keys
   localStorage.removeItem(LS_REMOTE_SOURCES);
   localStorage.removeItem(LS_EDITOR_PREFS);
 
-  // 3. Reset all stores to defaults
-  resetAssets();
-  resetEntities();
-  resetSceneState();
-  resetChoreographyState();
-  resetWiringState();
-  resetBindingState();
-  resetSignalTimeline();
-  resetShaderState();
-  resetSketchState();
+  // 3. Reset all stores to defaults (nothing to save: the backend is empty)
+  resetSceneStores();
   resetSignalSources();
   clearHistory();
 
@@ -615,29 +725,60 @@ export async function newScene(): Promise<void> {
   await scanAndSyncLocal();
 }
 
+/** Reset every store persisted in the backend, without saving the defaults. */
+function resetSceneStores(): void {
+  autoSaveSuspended = true;
+  try {
+    resetAssets();
+    resetEntities();
+    resetSceneState();
+    resetChoreographyState();
+    resetWiringState();
+    resetBindingState();
+    resetSignalTimeline();
+    resetShaderState();
+    resetSketchState();
+  } finally {
+    autoSaveSuspended = false;
+  }
+}
+
+/**
+ * Replace the stores with what the backend now holds — after the desktop
+ * app opened another project into it. Nothing is written back.
+ */
+export async function reloadState(): Promise<boolean> {
+  cancelPendingSaves();
+  resetSceneStores();
+  autoSaveSuspended = true;
+  try {
+    return await restoreState();
+  } finally {
+    cancelPendingSaves();
+    autoSaveSuspended = false;
+  }
+}
+
 // ---------------------------------------------------------------------------
 // Flush pending saves (beforeunload)
 // ---------------------------------------------------------------------------
 
 /** Synchronously flush all pending debounced saves. */
 function flushPendingSaves(): void {
-  for (const [key, timer] of debounceTimers) {
-    clearTimeout(timer);
-    debounceTimers.delete(key);
-  }
+  cancelPendingSaves();
 
-  // Best-effort synchronous writes for IndexedDB
-  // We cannot await promises in beforeunload, but we can start the transactions
+  // Best-effort writes to the storage backend
+  // We cannot await promises in beforeunload, but we can start the writes
   // and the browser will try to complete them before tearing down.
   try {
-    dbPut("scene", "current", wrap(getSceneState())).catch(() => {});
-    dbPut("entities", "current", wrap(getEntityStore().entities)).catch(() => {});
-    dbPut("choreographies", "current", wrap(getChoreographyState())).catch(() => {});
-    dbPut("wires", "current", wrap(getWiringState())).catch(() => {});
-    dbPut("bindings", "current", wrap(getBindingState())).catch(() => {});
-    dbPut("timeline", "current", wrap(getSignalTimelineState())).catch(() => {});
-    dbPut("shaders", "current", wrap(getShaderState())).catch(() => {});
-    dbPut("p5", "current", wrap(getSketchState())).catch(() => {});
+    storage().put("scene", "current", wrap(getSceneState())).catch(() => {});
+    storage().put("entities", "current", wrap(getEntityStore().entities)).catch(() => {});
+    storage().put("choreographies", "current", wrap(getChoreographyState())).catch(() => {});
+    storage().put("wires", "current", wrap(getWiringState())).catch(() => {});
+    storage().put("bindings", "current", wrap(getBindingState())).catch(() => {});
+    storage().put("timeline", "current", wrap(getSignalTimelineState())).catch(() => {});
+    storage().put("shaders", "current", wrap(getShaderState())).catch(() => {});
+    storage().put("p5", "current", wrap(getSketchState())).catch(() => {});
   } catch {
     // Best effort — page is unloading
   }
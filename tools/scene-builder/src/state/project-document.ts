@@ -0,0 +1,84 @@
+/**
+ * `.sajou` project documents (desktop app).
+ *
+ * The Rust backend (`src-tauri/src/project/document.rs`) owns the open
+ * document, its dirty state, the save prompts, native file dialogs and the
+ * recent-projects list. This module flushes pending autosaves before each
+ * operation and reloads the stores after Open / New.
+ */
+
+import { flushSaves, reloadState, resetScene } from "./persistence.js";
+
+/** Mirrors `Status` in `document.rs`. */
+export interface ProjectDocument {
+  path: string | null;
+  name: string;
+  dirty: boolean;
+}
+
+/** Mirrors `Recent` in `document.rs`. */
+export interface RecentProject {
+  path: string;
+  name: string;
+  openedAt: number;
+}
+
+async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke: tauriInvoke } = await import("@tauri-apps/api/core");
+  return tauriInvoke<T>(cmd, args);
+}
+
+/** The open document. */
+export function currentProject(): Promise<ProjectDocument> {
+  return invoke<ProjectDocument>("project_document");
+}
+
+/** Recently opened or saved projects, most recent first. */
+export function recentProjects(): Promise<RecentProject[]> {
+  return invoke<RecentProject[]>("project_recent");
+}
+
+/** Start an untitled project. Returns false if the user cancelled. */
+export async function newProject(): Promise<boolean> {
+  await flushSaves();
+  if (!(await invoke<boolean>("project_new"))) return false;
+  await resetScene();
+  return true;
+}
+
+/**
+ * Open the project at `path`, or one picked in a file dialog.
+ * Returns null if the user cancelled.
+ */
+export async function openProject(path?: string): Promise<ProjectDocument | null> {
+  await flushSaves();
+  const opened = await invoke<ProjectDocument | null>("project_open", { path: path ?? null });
+  if (opened) await reloadState();
+  return opened;
+}
+
+/** Save to the open project (asks for a location when untitled). */
+export async function saveProject(): Promise<ProjectDocument | null> {
+  await flushSaves();
+  return invoke<ProjectDocument | null>("project_save");
+}
+
+/** Save to a new location picked in a file dialog. */
+export async function saveProjectAs(): Promise<ProjectDocument | null> {
+  await flushSaves();
+  return invoke<ProjectDocument | null>("project_save_as", { path: null });
+}
+
+/** Flush autosaves when the window is about to close, so nothing is lost. */
+export async function initProjectDocument(): Promise<void> {
+  const { listen } = await import("@tauri-apps/api/event");
+  await listen<number>("project://close-requested", (event) => {
+    flushSaves()
+      .catch((err: unknown) => {
+        console.error("[project] Flush before close failed:", err);
+      })
+      .finally(() => {
+        void invoke("project_close_ready", { request: event.payload });
+      });
+  });
+}
@@ -0,0 +1,72 @@
+/**
+ * Autosave snapshots of the project (desktop app).
+ *
+ * The Rust backend (`src-tauri/src/project/snapshots.rs`) snapshots the
+ * project folder on a timer, deduplicated by content hash. This module
+ * lists, diffs and restores them; a restore reloads the editor.
+ */
+
+import { flushSaves, reloadState } from "./persistence.js";
+
+/** Mirrors `SnapshotInfo` in `snapshots.rs`. */
+export interface ProjectSnapshot {
+  id: string;
+  createdAt: number;
+  hash: string;
+  sections: string[];
+  assets: number;
+}
+
+/** Mirrors `Change` in `snapshots.rs`. */
+export interface SnapshotChange {
+  section: string;
+  /** JSON pointer inside the store's data, or the asset path. */
+  path: string;
+  kind: "added" | "removed" | "changed";
+}
+
+/** Mirrors `Settings` in `snapshots.rs`. */
+export interface SnapshotSettings {
+  count: number;
+  intervalSecs: number;
+}
+
+async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke: tauriInvoke } = await import("@tauri-apps/api/core");
+  return tauriInvoke<T>(cmd, args);
+}
+
+/** Snapshots of the open project, newest first. */
+export function listSnapshots(): Promise<ProjectSnapshot[]> {
+  return invoke<ProjectSnapshot[]>("project_snapshots");
+}
+
+/** Snapshot now. Resolves to null when nothing changed since the last one. */
+export async function takeSnapshot(): Promise<ProjectSnapshot | null> {
+  await flushSaves();
+  return invoke<ProjectSnapshot | null>("project_snapshot_now");
+}
+
+/** Changes from snapshot `from` to `to`, or to the current state. */
+export async function diffSnapshot(from: string, to?: string): Promise<SnapshotChange[]> {
+  if (to === undefined) await flushSaves();
+  return invoke<SnapshotChange[]>("project_snapshot_diff", { from, to: to ?? null });
+}
+
+/**
+ * Restore snapshot `id` and reload the editor. The state it replaces is
+ * snapshotted first, so the restore can itself be undone.
+ */
+export async function restoreSnapshot(id: string): Promise<void> {
+  await flushSaves();
+  await invoke("project_snapshot_restore", { id });
+  await reloadState();
+}
+
+export function snapshotSettings(): Promise<SnapshotSettings> {
+  return invoke<SnapshotSettings>("project_snapshot_settings");
+}
+
+export function setSnapshotSettings(settings: SnapshotSettings): Promise<void> {
+  return invoke("set_project_snapshot_settings", { settings });
+}
@@ -7,6 +7,8 @@
  * that URL instead of through the proxy.
  */
 
+import { isTauri } from "../utils/platform-fetch.js";
+
 const STORAGE_KEY = "sajou:server-url";
 
 /** Whether the server has been probed and found available. */
@@ -54,12 +56,17 @@ export function serverUrl(path: string): string {
  * Returns the server state if the server is available and has been
  * mutated (lastMutationAt !== null). Returns null if the server is
  * unreachable or has only default empty state.
+ *
+ * In the desktop app the state store lives in the Rust backend and is
+ * always available; it is read through the `state_pull` command.
  */
 export async function probeServer(): Promise<{
   available: boolean;
   hasState: boolean;
   data: Record<string, unknown> | null;
 }> {
+  if (isTauri()) return probeNativeStore();
+
   try {
     const resp = await fetch(serverUrl("/api/state/full"), {
       signal: AbortSignal.timeout(2000),
@@ -89,6 +96,38 @@ export async function probeServer(): Promise<{
   }
 }
 
+/** Versioned snapshot returned by the Rust `state_pull` command. */
+export interface NativeStateSnapshot {
+  version: number;
+  lastMutationAt: number | null;
+  data: Record<string, unknown>;
+}
+
+/** Read the Rust state store (desktop app). */
+export async function pullNativeState(): Promise<NativeStateSnapshot> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  return invoke<NativeStateSnapshot>("state_pull");
+}
+
+/** Probe equivalent for the Rust state store. */
+async function probeNativeStore(): Promise<{
+  available: boolean;
+  hasState: boolean;
+  data: Record<string, unknown> | null;
+}> {
+  try {
+    const snapshot = await pullNativeState();
+    serverAvailable = true;
+    if (snapshot.lastMutationAt === null) {
+      return { available: true, hasState: false, data: null };
+    }
+    return { available: true, hasState: true, data: snapshot.data };
+  } catch {
+    serverAvailable = false;
+    return { available: false, hasState: false, data: null };
+  }
+}
+
 /** Whether the server was found available on last probe. */
 export function isServerAvailable(): boolean {
   return serverAvailable === true;
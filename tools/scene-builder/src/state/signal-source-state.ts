@@ -71,6 +71,21 @@ export interface DiscoveredService {
   available: boolean;
   needsApiKey?: boolean;
   models?: string[];
+  /** Per-model details (desktop Ollama probe of `/api/tags`). */
+  modelDetails?: OllamaModelInfo[];
+}
+
+/** An installed Ollama model, as listed by the desktop app. */
+export interface OllamaModelInfo {
+  name: string;
+  size: number;
+  modifiedAt: string | null;
+  details: {
+    format: string | null;
+    family: string | null;
+    parameterSize: string | null;
+    quantizationLevel: string | null;
+  };
 }
 
 /**
@@ -270,6 +285,11 @@ export function detectProtocol(url: string): TransportProtocol {
   if (lower.includes("18789") || lower.includes("openclaw")) return "openclaw";
   if (lower.startsWith("ws://") || lower.startsWith("wss://")) return "websocket";
   if (lower.includes("anthropic")) return "anthropic";
+  if (lower.includes("generativelanguage.googleapis.com") || lower.includes("gemini")) return "gemini";
+  // Native Ollama needs the desktop backend; browsers use its OpenAI layer.
+  if ("__TAURI_INTERNALS__" in window && (lower.includes(":11434") || lower.includes("ollama"))) {
+    return "ollama";
+  }
   return "sse";
 }
 
@@ -5,6 +5,9 @@
  * External tools (MCP server, CLI) need access via REST endpoints.
  * This module subscribes to all stores and pushes a snapshot to
  * `POST /api/state/push` on every change (debounced).
+ *
+ * In the desktop app the snapshot goes to the Rust state store through
+ * the `state_push` command instead.
  */
 
 import { getSceneState, subscribeScene } from "./scene-state.js";
@@ -18,6 +21,7 @@ import { getSketchState, subscribeSketch } from "../sketch-editor/sketch-state.j
 import { getConnectionStatus, notifyServerContact, notifyServerLost } from "./server-connection.js";
 import { serverUrl } from "./server-config.js";
 import { isApplyingServerState } from "./command-consumer.js";
+import { isTauri } from "../utils/platform-fetch.js";
 
 /** Debounce interval in milliseconds. */
 const DEBOUNCE_MS = 300;
@@ -46,6 +50,14 @@ function pushState(): void {
 
   const snapshot = collectSnapshot();
 
+  if (isTauri()) {
+    import("@tauri-apps/api/core")
+      .then(({ invoke }) => invoke("state_push", { snapshot }))
+      .then(() => notifyServerContact())
+      .catch(() => notifyServerLost());
+    return;
+  }
+
   fetch(serverUrl("/api/state/push"), {
     method: "POST",
     headers: { "Content-Type": "application/json" },
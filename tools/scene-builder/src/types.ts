@@ -973,7 +973,7 @@ export type ConnectionStatus = "disconnected" | "connecting" | "connected" | "er
 export type SourceCategory = "local" | "remote";
 
 /** Transport protocol for a signal source. */
-export type TransportProtocol = "websocket" | "sse" | "openai" | "anthropic" | "openclaw" | "midi";
+export type TransportProtocol = "websocket" | "sse" | "openai" | "anthropic" | "gemini" | "ollama" | "openclaw" | "midi";
 
 /**
  * A single signal source in the V2 multi-source architecture.
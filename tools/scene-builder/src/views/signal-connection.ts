@@ -8,6 +8,7 @@
  *   - **WebSocket** (`ws://` / `wss://`) — for the sajou emitter and real-time sources
  *   - **SSE** (Server-Sent Events over HTTP/S) — for generic streaming endpoints
  *   - **OpenAI** (auto-detected) — for OpenAI-compatible APIs (LM Studio, Ollama, vLLM…)
+ *   - **Ollama** (desktop app) — Ollama's native `/api/chat`, streamed by the Rust backend
  *
  * All incoming messages from all sources are merged into shared signal/debug
  * listener channels. Each source's connection state is stored back into the
@@ -15,7 +16,7 @@
  */
 
 import type { SignalType } from "../types.js";
-import { updateSource } from "../state/signal-source-state.js";
+import { getSource, updateSource } from "../state/signal-source-state.js";
 import { getSignalTimelineState } from "../state/signal-timeline-state.js";
 import {
   parseMessage,
@@ -36,7 +37,7 @@ import { platformFetch } from "../utils/platform-fetch.js";
 export type ConnectionStatus = "disconnected" | "connecting" | "connected" | "error" | "unavailable";
 
 /** Transport protocol. */
-export type TransportProtocol = "websocket" | "sse" | "openai" | "anthropic" | "openclaw" | "midi";
+export type TransportProtocol = "websocket" | "sse" | "openai" | "anthropic" | "gemini" | "ollama" | "openclaw" | "midi";
 
 /** A parsed signal event received from a source. */
 export interface ReceivedSignal {
@@ -52,6 +53,8 @@ export interface ReceivedSignal {
   correlationId?: string;
   /** The typed payload. */
   payload: Record<string, unknown>;
+  /** Producer data outside the payload schema (model, token totals, channel…). */
+  metadata?: Record<string, unknown>;
   /** The full raw JSON string. */
   raw: string;
 }
@@ -87,6 +90,9 @@ const openClawReconnectState = new Map<string, { attempts: number; timer: Return
 /** Map of sourceId → connected MIDIInput (for cleanup on disconnect). */
 const midiConnections = new Map<string, MIDIInput>();
 
+/** Source IDs whose connection runs in the Rust backend (desktop app). */
+const nativeSources = new Set<string>();
+
 // ---------------------------------------------------------------------------
 // Global listeners (aggregate across all sources)
 // ---------------------------------------------------------------------------
@@ -224,9 +230,15 @@ export async function connectSource(
     connectOpenClaw(conn, url, apiKey);
   } else if (protocol === "websocket") {
     connectWebSocket(conn, url);
+  } else if (protocol === "ollama" && "__TAURI_INTERNALS__" in window) {
+    await connectOllamaNative(conn, url);
+  } else if (protocol === "gemini") {
+    await connectGemini(conn, url, apiKey);
   } else if (protocol === "anthropic") {
     debug(`[${sourceId}] Probing for Anthropic API…`, "info", sourceId);
-    const probeResult = await probeAnthropic(url, apiKey, sourceId);
+    const probeResult = "__TAURI_INTERNALS__" in window
+      ? await probeAnthropicNative(url, apiKey, sourceId)
+      : await probeAnthropic(url, apiKey, sourceId);
     if (probeResult) {
       setSourceState(sourceId, {
         protocol: "anthropic",
@@ -240,6 +252,10 @@ export async function connectSource(
         "info",
         sourceId,
       );
+    } else if ("__TAURI_INTERNALS__" in window) {
+      // The key now lives in the backend only — don't replay it over SSE.
+      connections.delete(sourceId);
+      setSourceState(sourceId, { status: "error", error: "Anthropic API unavailable" });
     } else {
       debug(`[${sourceId}] Anthropic probe failed — falling back to SSE.`, "info", sourceId);
       connectSSE(conn, url, apiKey);
@@ -284,6 +300,13 @@ export function disconnectSource(sourceId: string): void {
   // Clean up OpenClaw keepalive + reconnect state
   clearOpenClawTimers(sourceId);
 
+  // Stop a backend-owned connection
+  if (nativeSources.delete(sourceId)) {
+    void import("@tauri-apps/api/core").then(({ invoke }) =>
+      invoke("source_disconnect", { sourceId }),
+    );
+  }
+
   // Clean up MIDI connection
   const midiInput = midiConnections.get(sourceId);
   if (midiInput) {
@@ -300,18 +323,24 @@ export function disconnectSource(sourceId: string): void {
   });
 }
 
-/** Send a prompt to a connected source (OpenAI or Anthropic) and stream the response. */
+/** Send a prompt to a connected source (OpenAI, Anthropic, Gemini or Ollama) and stream the response. */
 export async function sendPromptToSource(
   sourceId: string,
   url: string,
   apiKey: string,
   model: string,
   prompt: string,
-  protocol?: TransportProtocol,
+  protocol: TransportProtocol | undefined = getSource(sourceId)?.protocol,
 ): Promise<void> {
   if (protocol === "anthropic") {
     return sendAnthropicPrompt(sourceId, url, apiKey, model, prompt);
   }
+  if (protocol === "ollama" && "__TAURI_INTERNALS__" in window) {
+    return sendOllamaPromptNative(sourceId, url, model, prompt);
+  }
+  if (protocol === "gemini") {
+    return sendGeminiPromptNative(sourceId, url, model, prompt);
+  }
   return sendOpenAIPrompt(sourceId, url, apiKey, model, prompt);
 }
 
@@ -329,6 +358,10 @@ async function sendOpenAIPrompt(
     connections.set(sourceId, conn);
   }
 
+  if ("__TAURI_INTERNALS__" in window) {
+    return sendOpenAIPromptNative(conn, url, apiKey, model, prompt);
+  }
+
   const correlationId = crypto.randomUUID();
   const baseUrl = url.replace(/\/+$/, "");
   const endpoint = `${baseUrl}/v1/chat/completions`;
@@ -393,6 +426,39 @@ async function sendOpenAIPrompt(
   }
 }
 
+/**
+ * Stream an OpenAI-compatible completion from the Rust backend, so long
+ * generations are decoded off the UI thread. Signals and log lines arrive
+ * through {@link initNativeSources}; aborting `conn.sseAbort` stops the stream.
+ */
+async function sendOpenAIPromptNative(
+  conn: SourceConnection,
+  url: string,
+  apiKey: string,
+  model: string,
+  prompt: string,
+): Promise<void> {
+  const { sourceId } = conn;
+  const { invoke } = await import("@tauri-apps/api/core");
+
+  const abort = new AbortController();
+  abort.signal.addEventListener("abort", () => {
+    void invoke("source_stop_prompt", { sourceId });
+  });
+  conn.sseAbort = abort;
+  setSourceState(sourceId, { streaming: true });
+
+  try {
+    await invoke("openai_prompt", { sourceId, url, apiKey: apiKey || null, model, prompt });
+  } catch (e) {
+    const msg = e instanceof Error ? e.message : String(e);
+    setSourceState(sourceId, { status: "error", error: msg, streaming: false });
+  } finally {
+    if (conn.sseAbort === abort) conn.sseAbort = null;
+    setSourceState(sourceId, { streaming: false });
+  }
+}
+
 /** Stop an active prompt stream for a specific source. */
 export function stopSourcePrompt(sourceId: string): void {
   const conn = connections.get(sourceId);
@@ -449,6 +515,11 @@ function detectProtocol(url: string): TransportProtocol {
   if (lower.includes("18789") || lower.includes("openclaw")) return "openclaw";
   if (lower.startsWith("ws://") || lower.startsWith("wss://")) return "websocket";
   if (lower.includes("anthropic")) return "anthropic";
+  if (lower.includes("generativelanguage.googleapis.com") || lower.includes("gemini")) return "gemini";
+  // Native Ollama needs the desktop backend; browsers use its OpenAI layer.
+  if ("__TAURI_INTERNALS__" in window && (lower.includes(":11434") || lower.includes("ollama"))) {
+    return "ollama";
+  }
   return "sse";
 }
 
@@ -575,6 +646,13 @@ const OPENCLAW_MAX_BACKOFF_MS = 30_000;
  * After connection: keepalive pings, signal parsing, exponential backoff reconnect.
  */
 function connectOpenClaw(conn: SourceConnection, url: string, apiKey: string): void {
+  // Desktop app: the Rust backend owns plain ws:// gateway connections, so
+  // they survive webview reloads and skip mixed-content restrictions.
+  if ("__TAURI_INTERNALS__" in window && url.startsWith("ws://")) {
+    void connectOpenClawNative(conn, url, apiKey);
+    return;
+  }
+
   // Clear any pending reconnect timer
   const reconnState = openClawReconnectState.get(conn.sourceId);
   if (reconnState?.timer) {
@@ -736,6 +814,29 @@ function connectOpenClaw(conn: SourceConnection, url: string, apiKey: string): v
   });
 }
 
+/**
+ * Start an OpenClaw connection in the Rust backend. Without an API key the
+ * backend reads the token from the OpenClaw config itself.
+ */
+async function connectOpenClawNative(conn: SourceConnection, url: string, apiKey: string): Promise<void> {
+  nativeSources.add(conn.sourceId);
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    await invoke("openclaw_connect", {
+      sourceId: conn.sourceId,
+      url,
+      token: apiKey || null,
+    });
+  } catch (e: unknown) {
+    nativeSources.delete(conn.sourceId);
+    connections.delete(conn.sourceId);
+    const err = e as { kind?: string; message?: string };
+    const msg = err.kind ? `OpenClaw token unavailable (${err.kind})` : String(e);
+    debug(`[${conn.sourceId}] ${msg}`, "error", conn.sourceId);
+    setSourceState(conn.sourceId, { status: "error", error: msg });
+  }
+}
+
 /** Clear all OpenClaw timers (reconnect) for a source. */
 function clearOpenClawTimers(sourceId: string): void {
   const keepalive = openClawKeepaliveTimers.get(sourceId);
@@ -962,6 +1063,73 @@ async function readOpenAIStream(
   }
 }
 
+// ---------------------------------------------------------------------------
+// Ollama (desktop app — native `/api/chat`)
+// ---------------------------------------------------------------------------
+
+/**
+ * List an Ollama server's models through the Rust backend. Without the
+ * desktop app, Ollama is reached through its OpenAI-compatible layer.
+ */
+async function connectOllamaNative(conn: SourceConnection, url: string): Promise<void> {
+  const { sourceId } = conn;
+  debug(`[${sourceId}] Listing Ollama models…`, "info", sourceId);
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    const models = await invoke<{ name: string }[]>("ollama_models", { url });
+    const names = models.map((m) => m.name);
+    setSourceState(sourceId, {
+      protocol: "ollama",
+      availableModels: names,
+      selectedModel: names[0] ?? "",
+      status: "connected",
+      error: null,
+    });
+    debug(`[${sourceId}] Ollama detected. ${names.length} model(s) available.`, "info", sourceId);
+  } catch (e) {
+    connections.delete(sourceId);
+    const msg = String(e);
+    debug(`[${sourceId}] ${msg}`, "error", sourceId);
+    setSourceState(sourceId, { status: "error", error: msg });
+  }
+}
+
+/**
+ * Stream an Ollama `/api/chat` response from the Rust backend. Signals and
+ * log lines arrive through {@link initNativeSources}; aborting
+ * `conn.sseAbort` stops the stream.
+ */
+async function sendOllamaPromptNative(
+  sourceId: string,
+  url: string,
+  model: string,
+  prompt: string,
+): Promise<void> {
+  let conn = connections.get(sourceId);
+  if (!conn) {
+    conn = { sourceId, ws: null, sseAbort: null };
+    connections.set(sourceId, conn);
+  }
+  const { invoke } = await import("@tauri-apps/api/core");
+
+  const abort = new AbortController();
+  abort.signal.addEventListener("abort", () => {
+    void invoke("source_stop_prompt", { sourceId });
+  });
+  conn.sseAbort = abort;
+  setSourceState(sourceId, { streaming: true });
+
+  try {
+    await invoke("ollama_prompt", { sourceId, url, model, prompt });
+  } catch (e) {
+    const msg = e instanceof Error ? e.message : String(e);
+    setSourceState(sourceId, { status: "error", error: msg, streaming: false });
+  } finally {
+    if (conn.sseAbort === abort) conn.sseAbort = null;
+    setSourceState(sourceId, { streaming: false });
+  }
+}
+
 // ---------------------------------------------------------------------------
 // Anthropic API
 // ---------------------------------------------------------------------------
@@ -1031,6 +1199,32 @@ async function probeAnthropic(
   }
 }
 
+/**
+ * Probe an Anthropic API from the Rust backend, which holds the API key.
+ * A key typed into the source is handed over once and cleared from the
+ * webview state; without one the backend's stored key or
+ * `ANTHROPIC_API_KEY` is used.
+ */
+async function probeAnthropicNative(
+  url: string,
+  apiKey: string,
+  sourceId: string,
+): Promise<{ models: string[] } | null> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  try {
+    if (apiKey) {
+      await invoke("set_api_key", { provider: "anthropic", key: apiKey });
+      updateSource(sourceId, { apiKey: "" });
+    }
+    const models = await invoke<string[]>("anthropic_probe", { url });
+    debug(`[${sourceId}] Anthropic probe: found ${models.length} model(s).`, "info", sourceId);
+    return { models };
+  } catch (e) {
+    debug(`[${sourceId}] ${String(e)}`, "warn", sourceId);
+    return null;
+  }
+}
+
 /** Send a prompt to an Anthropic source and stream the response as sajou signals. */
 async function sendAnthropicPrompt(
   sourceId: string,
@@ -1045,6 +1239,10 @@ async function sendAnthropicPrompt(
     connections.set(sourceId, conn);
   }
 
+  if ("__TAURI_INTERNALS__" in window) {
+    return sendAnthropicPromptNative(conn, url, model, prompt);
+  }
+
   const correlationId = crypto.randomUUID();
   const baseUrl = url.replace(/\/+$/, "");
   const endpoint = `${baseUrl}/v1/messages`;
@@ -1107,6 +1305,38 @@ async function sendAnthropicPrompt(
   }
 }
 
+/**
+ * Stream an Anthropic response from the Rust backend, authenticated with
+ * the key it holds. Signals and log lines arrive through
+ * {@link initNativeSources}; aborting `conn.sseAbort` stops the stream.
+ */
+async function sendAnthropicPromptNative(
+  conn: SourceConnection,
+  url: string,
+  model: string,
+  prompt: string,
+): Promise<void> {
+  const { sourceId } = conn;
+  const { invoke } = await import("@tauri-apps/api/core");
+
+  const abort = new AbortController();
+  abort.signal.addEventListener("abort", () => {
+    void invoke("source_stop_prompt", { sourceId });
+  });
+  conn.sseAbort = abort;
+  setSourceState(sourceId, { streaming: true });
+
+  try {
+    await invoke("anthropic_prompt", { sourceId, url, model, prompt });
+  } catch (e) {
+    const msg = e instanceof Error ? e.message : String(e);
+    setSourceState(sourceId, { status: "error", error: msg, streaming: false });
+  } finally {
+    if (conn.sseAbort === abort) conn.sseAbort = null;
+    setSourceState(sourceId, { streaming: false });
+  }
+}
+
 /**
  * Read an Anthropic SSE stream, translating events into sajou signals.
  *
@@ -1200,6 +1430,85 @@ async function readAnthropicStream(
   }
 }
 
+// ---------------------------------------------------------------------------
+// Gemini API (desktop app — native `streamGenerateContent`)
+// ---------------------------------------------------------------------------
+
+/**
+ * Probe a Gemini API from the Rust backend, which holds the API key. A key
+ * typed into the source is handed over once and cleared from the webview
+ * state; without one the backend's stored key or `GEMINI_API_KEY` is used.
+ */
+async function connectGemini(conn: SourceConnection, url: string, apiKey: string): Promise<void> {
+  const { sourceId } = conn;
+  if (!("__TAURI_INTERNALS__" in window)) {
+    connections.delete(sourceId);
+    const msg = "Gemini sources need the desktop app.";
+    debug(`[${sourceId}] ${msg}`, "error", sourceId);
+    setSourceState(sourceId, { status: "error", error: msg });
+    return;
+  }
+
+  debug(`[${sourceId}] Probing for Gemini API…`, "info", sourceId);
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    if (apiKey) {
+      await invoke("set_api_key", { provider: "gemini", key: apiKey });
+      updateSource(sourceId, { apiKey: "" });
+    }
+    const models = await invoke<string[]>("gemini_probe", { url });
+    setSourceState(sourceId, {
+      protocol: "gemini",
+      availableModels: models,
+      selectedModel: models[0] ?? "",
+      status: "connected",
+      error: null,
+    });
+    debug(`[${sourceId}] Gemini API detected. ${models.length} model(s) available.`, "info", sourceId);
+  } catch (e) {
+    connections.delete(sourceId);
+    const msg = String(e);
+    debug(`[${sourceId}] ${msg}`, "error", sourceId);
+    setSourceState(sourceId, { status: "error", error: msg });
+  }
+}
+
+/**
+ * Stream a Gemini response from the Rust backend, authenticated with the
+ * key it holds. Signals and log lines arrive through
+ * {@link initNativeSources}; aborting `conn.sseAbort` stops the stream.
+ */
+async function sendGeminiPromptNative(
+  sourceId: string,
+  url: string,
+  model: string,
+  prompt: string,
+): Promise<void> {
+  let conn = connections.get(sourceId);
+  if (!conn) {
+    conn = { sourceId, ws: null, sseAbort: null };
+    connections.set(sourceId, conn);
+  }
+  const { invoke } = await import("@tauri-apps/api/core");
+
+  const abort = new AbortController();
+  abort.signal.addEventListener("abort", () => {
+    void invoke("source_stop_prompt", { sourceId });
+  });
+  conn.sseAbort = abort;
+  setSourceState(sourceId, { streaming: true });
+
+  try {
+    await invoke("gemini_prompt", { sourceId, url, model, prompt });
+  } catch (e) {
+    const msg = e instanceof Error ? e.message : String(e);
+    setSourceState(sourceId, { status: "error", error: msg, streaming: false });
+  } finally {
+    if (conn.sseAbort === abort) conn.sseAbort = null;
+    setSourceState(sourceId, { streaming: false });
+  }
+}
+
 // ---------------------------------------------------------------------------
 // Shared message handling
 // ---------------------------------------------------------------------------
@@ -1266,6 +1575,12 @@ let localSSE: EventSource | null = null;
 /** The source ID currently connected via local SSE. */
 let localSSESourceId: string | null = null;
 
+/** Tauri event emitted by the Rust signal server for every ingested signal. */
+const NATIVE_SIGNAL_EVENT = "signal://received";
+
+/** Unlisten handle for the native signal event subscription. */
+let localNativeUnlisten: (() => void) | null = null;
+
 /**
  * Connect the local Claude Code signal pipeline:
  * 1. Install Claude Code hooks via `POST /api/tap/connect`
@@ -1279,10 +1594,10 @@ let localSSESourceId: string | null = null;
 export async function connectLocalSSE(sourceId = "local:claude-code"): Promise<void> {
   if (localSSE) return;
 
-  // Local SSE requires the Vite dev server endpoints — skip in Tauri production.
+  // Tauri production: no Vite dev server — signals come from the embedded
+  // Rust listener as webview events instead of an EventSource.
   if ("__TAURI_INTERNALS__" in window && !import.meta.env?.DEV) {
-    debug(`[${sourceId}] Local SSE not available in Tauri production.`, "warn", sourceId);
-    updateSource(sourceId, { status: "error", error: "Requires dev mode (tauri dev)" });
+    await connectLocalNative(sourceId);
     return;
   }
 
@@ -1322,18 +1637,7 @@ export async function connectLocalSSE(sourceId = "local:claude-code"): Promise<v
 
     try {
       const envelope = JSON.parse(raw) as Record<string, unknown>;
-      const signal: ReceivedSignal = {
-        id: String(envelope["id"] ?? crypto.randomUUID()),
-        type: String(envelope["type"] ?? "event") as SignalType,
-        timestamp: typeof envelope["timestamp"] === "number" ? envelope["timestamp"] : Date.now(),
-        source: String(envelope["source"] ?? "local"),
-        correlationId: typeof envelope["correlationId"] === "string" ? envelope["correlationId"] : undefined,
-        payload: (typeof envelope["payload"] === "object" && envelope["payload"] !== null
-          ? envelope["payload"]
-          : {}) as Record<string, unknown>,
-        raw,
-      };
-      dispatchSignal(signal, sourceId);
+      dispatchSignal(envelopeToSignal(envelope, raw), sourceId);
     } catch {
       debug(`[${sourceId}] Unparseable SSE message: ${raw.slice(0, 120)}`, "warn", sourceId);
     }
@@ -1354,12 +1658,29 @@ export async function disconnectLocalSSE(): Promise<void> {
     localSSE.close();
     localSSE = null;
   }
+  const native = localNativeUnlisten !== null;
+  if (localNativeUnlisten) {
+    localNativeUnlisten();
+    localNativeUnlisten = null;
+  }
 
   const sourceId = localSSESourceId ?? "local:claude-code";
   localSSESourceId = null;
 
   updateSource(sourceId, { status: "disconnected", error: null });
 
+  if (native) {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke("tap_uninstall_hooks", { projectDir: null });
+      debug(`[${sourceId}] Claude Code hooks removed.`, "info", sourceId);
+    } catch {
+      // Best-effort — the backend also removes hooks on exit
+    }
+    debug(`[${sourceId}] Native signal stream disconnected.`, "info", sourceId);
+    return;
+  }
+
   // Uninstall Claude Code hooks
   try {
     const resp = await fetch("/api/tap/disconnect", { method: "POST" });
@@ -1376,5 +1697,125 @@ export async function disconnectLocalSSE(): Promise<void> {
 
 /** Whether the local SSE stream is currently active. */
 export function isLocalSSEConnected(): boolean {
-  return localSSE !== null;
+  return localSSE !== null || localNativeUnlisten !== null;
+}
+
+/** Convert a local signal envelope (SSE frame or Tauri event) into a ReceivedSignal. */
+function envelopeToSignal(envelope: Record<string, unknown>, raw: string): ReceivedSignal {
+  return {
+    id: String(envelope["id"] ?? crypto.randomUUID()),
+    type: String(envelope["type"] ?? "event") as SignalType,
+    timestamp: typeof envelope["timestamp"] === "number" ? envelope["timestamp"] : Date.now(),
+    source: String(envelope["source"] ?? "local"),
+    correlationId: typeof envelope["correlationId"] === "string" ? envelope["correlationId"] : undefined,
+    payload: (typeof envelope["payload"] === "object" && envelope["payload"] !== null
+      ? envelope["payload"]
+      : {}) as Record<string, unknown>,
+    metadata: typeof envelope["metadata"] === "object" && envelope["metadata"] !== null
+      ? envelope["metadata"] as Record<string, unknown>
+      : undefined,
+    raw,
+  };
+}
+
+// ---------------------------------------------------------------------------
+// Local native stream — Tauri production (embedded Rust signal server)
+// ---------------------------------------------------------------------------
+
+/**
+ * Connect the local Claude Code pipeline in the desktop app:
+ * 1. Install hooks via the `tap_install_hooks` command (project picked once)
+ * 2. Subscribe to signals posted to the embedded Rust listener
+ */
+async function connectLocalNative(sourceId: string): Promise<void> {
+  if (localNativeUnlisten) return;
+
+  localSSESourceId = sourceId;
+  updateSource(sourceId, { status: "connecting", error: null });
+
+  // Install Claude Code hooks in the last tapped project, asking once.
+  try {
+    const { invoke } = await import("@tauri-apps/api/core");
+    const status = await invoke<{ lastProjectDir: string | null }>("tap_status");
+    const projectDir = status.lastProjectDir ?? await invoke<string | null>("tap_pick_project");
+    if (projectDir) {
+      await invoke("tap_install_hooks", { projectDir });
+      debug(`[${sourceId}] Claude Code hooks installed in ${projectDir}.`, "info", sourceId);
+    } else {
+      debug(`[${sourceId}] No Claude Code project selected — hooks not installed.`, "warn", sourceId);
+    }
+  } catch (e) {
+    debug(`[${sourceId}] Hook install failed: ${e instanceof Error ? e.message : String(e)}`, "error", sourceId);
+  }
+
+  try {
+    const { listen } = await import("@tauri-apps/api/event");
+    localNativeUnlisten = await listen<Record<string, unknown>>(NATIVE_SIGNAL_EVENT, (event) => {
+      dispatchSignal(envelopeToSignal(event.payload, JSON.stringify(event.payload)), sourceId);
+    });
+  } catch (e) {
+    debug(`[${sourceId}] Native signal stream unavailable: ${e instanceof Error ? e.message : String(e)}`, "error", sourceId);
+    updateSource(sourceId, { status: "error", error: "Native signal stream unavailable" });
+    return;
+  }
+
+  updateSource(sourceId, { status: "connected", error: null });
+  debug(`[${sourceId}] Connected to native signal stream.`, "info", sourceId);
+}
+
+// ---------------------------------------------------------------------------
+// Native sources — connections owned by the Rust backend (desktop app)
+// ---------------------------------------------------------------------------
+
+/** Status of a backend-owned source, as sent by the Rust side. */
+interface NativeSourceStatus {
+  sourceId: string;
+  status: ConnectionStatus;
+  error: string | null;
+}
+
+/** Apply a backend status update to the source store. */
+function applyNativeStatus({ sourceId, status, error }: NativeSourceStatus): void {
+  if (status === "disconnected" || status === "error") {
+    connections.delete(sourceId);
+    nativeSources.delete(sourceId);
+  } else if (!connections.has(sourceId)) {
+    connections.set(sourceId, { sourceId, ws: null, sseAbort: null });
+    nativeSources.add(sourceId);
+  }
+  setSourceState(sourceId, { status, error });
+}
+
+/**
+ * Subscribe to signals, status changes and log lines from backend-owned
+ * sources, and adopt connections that outlived a webview reload.
+ * No-op outside the desktop app. Call once at init.
+ */
+export async function initNativeSources(): Promise<void> {
+  if (!("__TAURI_INTERNALS__" in window)) return;
+
+  const { listen } = await import("@tauri-apps/api/event");
+  const { invoke } = await import("@tauri-apps/api/core");
+
+  await listen<{ sourceId: string; signal: Record<string, unknown>; raw: string }>(
+    "source://signal",
+    (event) => {
+      const { sourceId, signal, raw } = event.payload;
+      dispatchSignal(envelopeToSignal(signal, raw), sourceId);
+    },
+  );
+  await listen<NativeSourceStatus>("source://status", (event) => {
+    applyNativeStatus(event.payload);
+  });
+  await listen<{ sourceId: string; message: string; level: "info" | "warn" | "error" }>(
+    "source://debug",
+    (event) => {
+      const { sourceId, message, level } = event.payload;
+      debug(message, level, sourceId);
+    },
+  );
+
+  for (const status of await invoke<NativeSourceStatus[]>("source_statuses")) {
+    applyNativeStatus(status);
+  }
 }
@@ -206,7 +206,7 @@ function summarize(signal: ReceivedSignal): string {
     case "completion": {
       // Standard: taskId ✓/✗ result | OpenAI: totalTokens + finishReason
       const icon = p["success"] ? "✓" : "✗";
-      const tokens = p["totalTokens"];
+      const tokens = p["totalTokens"] ?? signal.metadata?.["totalTokens"];
       const reason = p["finishReason"];
       if (tokens !== undefined) return `${icon} ${tokens} tokens (${reason ?? "done"})`;
       return `${p["taskId"] ?? ""} ${icon} ${p["result"] ?? ""}`;
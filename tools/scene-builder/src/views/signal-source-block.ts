@@ -88,6 +88,8 @@ export function createSourceBlock(source: SignalSource): HTMLElement {
     sse: "SSE",
     openai: "OPENAI",
     anthropic: "ANTHROPIC",
+    gemini: "GEMINI",
+    ollama: "OLLAMA",
     openclaw: "CLAW",
     midi: "MIDI",
   };
@@ -155,8 +157,8 @@ export function createSourceBlock(source: SignalSource): HTMLElement {
   });
   block.appendChild(actionBtn);
 
-  // -- Prompt row (OpenAI or Anthropic when connected) --
-  if ((source.protocol === "openai" || source.protocol === "anthropic") && source.status === "connected") {
+  // -- Prompt row (LLM protocols when connected) --
+  if ((source.protocol === "openai" || source.protocol === "anthropic" || source.protocol === "gemini" || source.protocol === "ollama") && source.status === "connected") {
     const promptRow = document.createElement("div");
     promptRow.className = "source-block-prompt";
 
@@ -24,6 +24,7 @@ import {
   connectLocalSSE,
   disconnectLocalSSE,
 } from "./signal-connection.js";
+import { isTauri } from "../utils/platform-fetch.js";
 
 // ---------------------------------------------------------------------------
 // Status dot colors (same as old source-block)
@@ -191,7 +192,7 @@ function buildPopoverContent(content: HTMLElement, source: SignalSource): void {
 
   const protoBadge = document.createElement("span");
   protoBadge.className = `sv-chip-proto source-block-proto--${source.protocol}`;
-  protoBadge.textContent = { websocket: "WS", sse: "SSE", openai: "AI", openclaw: "CLAW", anthropic: "ANTH", midi: "MIDI" }[source.protocol] ?? source.protocol;
+  protoBadge.textContent = { websocket: "WS", sse: "SSE", openai: "AI", openclaw: "CLAW", anthropic: "ANTH", gemini: "GEM", ollama: "OLLAMA", midi: "MIDI" }[source.protocol] ?? source.protocol;
   statusRow.appendChild(protoBadge);
 
   if (source.eventsPerSecond > 0) {
@@ -238,7 +239,9 @@ function buildPopoverContent(content: HTMLElement, source: SignalSource): void {
     const protoSelect = document.createElement("select");
     protoSelect.className = "nc-popover-select";
     protoSelect.disabled = isActive;
-    for (const [value, label] of [["openai", "OpenAI"], ["sse", "SSE"], ["anthropic", "Anthropic"]] as const) {
+    for (const [value, label] of [["openai", "OpenAI"], ["ollama", "Ollama"], ["sse", "SSE"], ["anthropic", "Anthropic"], ["gemini", "Gemini"]] as const) {
+      // Ollama streams through the native backend only.
+      if (value === "ollama" && !isTauri()) continue;
       const opt = document.createElement("option");
       opt.value = value;
       opt.textContent = label;
@@ -373,8 +376,8 @@ function buildPopoverContent(content: HTMLElement, source: SignalSource): void {
     content.appendChild(actionBtn);
   }
 
-  // -- OpenAI prompt section (only when connected in OpenAI mode) --
-  if (source.protocol === "openai" && source.status === "connected") {
+  // -- Prompt section (only when connected in OpenAI or Ollama mode) --
+  if ((source.protocol === "openai" || source.protocol === "ollama") && source.status === "connected") {
     const sep = document.createElement("div");
     sep.className = "nc-popover-subtitle";
     sep.textContent = "prompt";
@@ -17,6 +17,7 @@
 import {
   onSignal,
   onDebug,
+  initNativeSources,
 } from "./signal-connection.js";
 import type { ReceivedSignal } from "./signal-connection.js";
 import {
@@ -32,7 +33,7 @@ import {
 } from "./signal-source-popover.js";
 import { initRawLog, addLogEntry, addDebugEntry } from "./signal-raw-log.js";
 import { createSimulatorBar } from "./simulator-bar.js";
-import { scanAndSyncLocal, initMIDIHotPlug, initPeriodicRescan } from "../state/local-discovery.js";
+import { scanAndSyncLocal, initMIDIHotPlug, initPeriodicRescan, initOpenClawConfigWatch } from "../state/local-discovery.js";
 
 // ---------------------------------------------------------------------------
 // DOM references
@@ -93,13 +94,24 @@ export function initSignalView(): void {
   });
 
   // ── Auto-discover local services (replaces connectLocalSSE) ──
-  scanAndSyncLocal();
+  // Desktop app: adopt backend-owned connections first, so a reload does
+  // not auto-connect a source that is still live in Rust.
+  initNativeSources()
+    .catch((e: unknown) => {
+      console.warn("[signal-view] Native sources unavailable:", e);
+    })
+    .finally(() => {
+      scanAndSyncLocal();
+    });
 
   // ── MIDI hot-plug: auto-rescan when devices are plugged/unplugged ──
   initMIDIHotPlug();
 
   // ── Periodic rescan every 30s (detects services started after sajou) ──
   initPeriodicRescan();
+
+  // ── OpenClaw token rotation: re-read the token when its config changes ──
+  initOpenClawConfigWatch();
 }
 
 // ---------------------------------------------------------------------------
@@ -118,6 +130,8 @@ const PROTO_LABELS: Record<string, string> = {
   sse: "SSE",
   openai: "AI",
   anthropic: "ANTH",
+  gemini: "GEM",
+  ollama: "OLLAMA",
   openclaw: "CLAW",
   midi: "MIDI",
 };
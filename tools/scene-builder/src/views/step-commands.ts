@@ -46,6 +46,8 @@ export const PROTOCOL_SIGNAL_TYPES: Record<TransportProtocol, SignalType[]> = {
   ],
   openai: ["text_delta", "thinking", "token_usage", "error", "completion", "event"],
   anthropic: ["text_delta", "thinking", "token_usage", "error", "completion", "event"],
+  gemini: ["text_delta", "thinking", "tool_call", "token_usage", "error", "completion", "event"],
+  ollama: ["text_delta", "thinking", "token_usage", "error", "completion", "event"],
   midi: [
     "midi.note_on", "midi.note_off", "midi.control_change",
     "midi.pitch_bend", "midi.program_change",
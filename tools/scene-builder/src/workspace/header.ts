@@ -8,6 +8,16 @@ import { exportScene } from "../io/export-scene.js";
 import { importScene } from "../io/import-scene.js";
 import { subscribeRunMode, isRunModeActive } from "../run-mode/run-mode-state.js";
 import { newScene } from "../state/persistence.js";
+import {
+  initProjectDocument,
+  newProject,
+  openProject,
+  recentProjects,
+  saveProject,
+  saveProjectAs,
+} from "../state/project-document.js";
+import { diffSnapshot, listSnapshots, restoreSnapshot } from "../state/project-history.js";
+import { isTauri } from "../utils/platform-fetch.js";
 import { undo, redo, canUndo, canRedo, subscribeUndo } from "../state/undo.js";
 import { shouldSuppressShortcut } from "../shortcuts/shortcut-registry.js";
 
@@ -96,6 +106,14 @@ function htmlConfirm(message: string): Promise<boolean> {
 
 /** Trigger "New Scene" with confirmation dialog. */
 async function triggerNewScene(): Promise<void> {
+  if (isTauri()) {
+    // The backend asks to save first, and only when there are changes.
+    await newProject().catch((err: unknown) => {
+      console.error("[scene-builder] New project failed:", err);
+    });
+    return;
+  }
+
   const confirmed = await htmlConfirm("Unsaved changes will be lost. Create a new scene?");
   if (!confirmed) return;
 
@@ -106,6 +124,121 @@ async function triggerNewScene(): Promise<void> {
   }
 }
 
+// ---------------------------------------------------------------------------
+// Project documents (desktop app)
+// ---------------------------------------------------------------------------
+
+/** Run a project operation, logging failures. */
+function runProjectAction(label: string, action: () => Promise<unknown>): void {
+  action().catch((err: unknown) => {
+    console.error(`[scene-builder] ${label} failed:`, err);
+  });
+}
+
+let recentMenu: HTMLElement | null = null;
+
+function closeRecentMenu(): void {
+  recentMenu?.remove();
+  recentMenu = null;
+}
+
+/** Restore a snapshot after confirming with a summary of what changes. */
+async function triggerRestoreSnapshot(id: string, label: string): Promise<void> {
+  const changes = await diffSnapshot(id);
+  if (changes.length === 0) {
+    console.info(`[scene-builder] Snapshot ${label} matches the current state`);
+    return;
+  }
+  const sections = [...new Set(changes.map((c) => c.section))].join(", ");
+  const confirmed = await htmlConfirm(
+    `Restore the snapshot from ${label}? It differs in ${sections}. ` +
+      "The current state is kept as a snapshot.",
+  );
+  if (!confirmed) return;
+  await restoreSnapshot(id);
+}
+
+/** Show "Open…", the recent projects and the snapshots below the Open button. */
+async function toggleRecentMenu(anchor: HTMLElement): Promise<void> {
+  if (recentMenu) {
+    closeRecentMenu();
+    return;
+  }
+  const [recents, snapshots] = await Promise.all([
+    recentProjects().catch(() => []),
+    listSnapshots().catch(() => []),
+  ]);
+
+  const menu = document.createElement("div");
+  menu.className = "header-menu";
+  const rect = anchor.getBoundingClientRect();
+  menu.style.left = `${rect.left}px`;
+  menu.style.top = `${rect.bottom + 4}px`;
+
+  const addItem = (
+    label: string,
+    title: string,
+    actionLabel: string,
+    action: () => Promise<unknown>,
+  ): void => {
+    const item = document.createElement("button");
+    item.className = "header-menu-item";
+    item.textContent = label;
+    item.title = title;
+    item.addEventListener("click", () => {
+      closeRecentMenu();
+      runProjectAction(actionLabel, action);
+    });
+    menu.append(item);
+  };
+  const addHeading = (text: string): void => {
+    const heading = document.createElement("div");
+    heading.className = "header-menu-label";
+    heading.textContent = text;
+    menu.append(heading);
+  };
+
+  addItem("Open…", "Choose a .sajou project", "Open project", () => openProject());
+  if (recents.length > 0) {
+    addHeading("Recent");
+    for (const recent of recents) {
+      addItem(recent.name, recent.path, "Open project", () => openProject(recent.path));
+    }
+  }
+  if (snapshots.length > 0) {
+    addHeading("Snapshots");
+    for (const snapshot of snapshots.slice(0, 10)) {
+      const label = new Date(snapshot.createdAt).toLocaleString();
+      const title = `${snapshot.sections.length} stores, ${snapshot.assets} assets`;
+      addItem(label, title, "Restore snapshot", () => triggerRestoreSnapshot(snapshot.id, label));
+    }
+  }
+
+  document.body.append(menu);
+  recentMenu = menu;
+  // Close on the next click anywhere else.
+  setTimeout(() => {
+    document.addEventListener("click", (e) => {
+      if (!menu.contains(e.target as Node)) closeRecentMenu();
+    }, { once: true });
+  });
+}
+
+/** Export the scene, showing native export progress on the Export button. */
+function triggerExport(): void {
+  const btnExport = document.getElementById("btn-export");
+  const title = btnExport?.title ?? "";
+  exportScene((progress) => {
+    if (btnExport) btnExport.title = `Exporting… ${progress.done}/${progress.total}`;
+  })
+    .catch((err: unknown) => {
+      console.error("[scene-builder] Export failed:", err);
+    })
+    .finally(() => {
+      if (btnExport) btnExport.title = title;
+    });
+}
+
 /** Sync Undo/Redo button disabled state with stack status. */
 function syncUndoButtons(): void {
   const btnUndo = document.getElementById("btn-undo");
@@ -117,6 +250,8 @@ function syncUndoButtons(): void {
 /** Initialize header button handlers. */
 export function initHeader(): void {
   const btnNew = document.getElementById("btn-new");
+  const btnOpen = document.getElementById("btn-open");
+  const btnSave = document.getElementById("btn-save");
   const btnImport = document.getElementById("btn-import");
   const btnExport = document.getElementById("btn-export");
   const btnRun = document.getElementById("btn-run");
@@ -127,11 +262,21 @@ export function initHeader(): void {
     void triggerNewScene();
   });
 
-  btnExport?.addEventListener("click", () => {
-    exportScene().catch((err: unknown) => {
-      console.error("[scene-builder] Export failed:", err);
+  if (isTauri()) {
+    btnOpen?.removeAttribute("hidden");
+    btnSave?.removeAttribute("hidden");
+    btnOpen?.addEventListener("click", () => {
+      if (btnOpen) void toggleRecentMenu(btnOpen);
     });
-  });
+    btnSave?.addEventListener("click", () => {
+      runProjectAction("Save", saveProject);
+    });
+    initProjectDocument().catch((err: unknown) => {
+      console.error("[scene-builder] Project close handler failed:", err);
+    });
+  }
+
+  btnExport?.addEventListener("click", triggerExport);
 
   btnImport?.addEventListener("click", () => {
     importScene().catch((err: unknown) => {
@@ -155,7 +300,8 @@ export function initHeader(): void {
   // Subscribe to run mode state changes to update button appearance
   subscribeRunMode(updateRunButton);
 
-  // Keyboard shortcuts: Ctrl+R (run), Ctrl+S (save), Ctrl+N (new)
+  // Keyboard shortcuts: Ctrl+R (run), Ctrl+S (save), Ctrl+N (new);
+  // desktop app: Ctrl+S saves the project, Ctrl+Shift+S saves as, Ctrl+O opens
   document.addEventListener("keydown", (e: KeyboardEvent) => {
     if (!(e.ctrlKey || e.metaKey)) return;
     if (shouldSuppressShortcut(e)) return;
@@ -166,10 +312,18 @@ export function initHeader(): void {
         void triggerRunMode();
         break;
       case "s":
+      case "S":
+        e.preventDefault();
+        if (isTauri()) {
+          runProjectAction("Save", e.shiftKey ? saveProjectAs : saveProject);
+          break;
+        }
+        triggerExport();
+        break;
+      case "o":
+        if (!isTauri()) break;
         e.preventDefault();
-        exportScene().catch((err: unknown) => {
-          console.error("[scene-builder] Export failed:", err);
-        });
+        runProjectAction("Open project", () => openProject());
         break;
       case "n":
         e.preventDefault();
@@ -11,7 +11,6 @@ import { initCanvasDropHandler } from "../canvas/canvas-drop-handler.js";
 import { initToolbarPanel } from "./toolbar.js";
 import { initHeader } from "./header.js";
 import { restoreState, initAutoSave } from "../state/persistence.js";
-import { isTauri } from "../utils/platform-fetch.js";
 import { initAutoWire } from "../state/auto-wire.js";
 import { initServerConnection } from "../state/server-connection.js";
 import { setSceneState } from "../state/scene-state.js";
@@ -180,9 +179,8 @@ export async function initWorkspace(): Promise<void> {
   //    Server wins if it has real state; otherwise initStateSync() will push
   //    the IDB state to the server on its first immediate push.
   //    initServerConnection handles probe → restore → start sync/commands.
-  if (!isTauri()) {
-    await initServerConnection(restoreFromServer);
-  }
+  //    In the desktop app the "server" is the Rust state store.
+  await initServerConnection(restoreFromServer);
 
   // Undo/redo shortcuts
   initUndoManager();
@@ -285,6 +283,4 @@ export async function initWorkspace(): Promise<void> {
   initAutoWire();
 
   // MCP command pipeline is started by initServerConnection() above.
-  // In Tauri production builds the server is not available — skipped via
-  // the isTauri() guard around initServerConnection().
 }